@@ -0,0 +1,218 @@
+//! Ciphertexts bound to associated data, produced by the Threshold Decryption Service and
+//! checked by the Decryption Servers before they produce a share.
+
+use rand::RngCore;
+use rand_chacha::{ rand_core::SeedableRng, ChaChaRng };
+use threshold_crypto::{
+    ff::Field,
+    group::{ CurveAffine, CurveProjective, EncodedPoint },
+    hash_g2,
+    pairing::{
+        bls12_381::{ Bls12, Fr, G1Affine, G1Compressed, G2Affine, G2Compressed },
+        Engine,
+    },
+    Ciphertext,
+    DecryptionShare,
+    PublicKey,
+    PublicKeyShare,
+};
+
+const LABEL_DOMAIN: &[u8] = b"threshold-decryption/associated-data/v1";
+const G1_SIZE: usize = 48;
+const G2_SIZE: usize = 96;
+
+/// A `threshold_crypto` ciphertext `(U, V, W)` split into its compressed components, as laid out
+/// by its bincode serialization: `U`, a length-prefixed `V` and `W`.
+struct CiphertextParts {
+    u: G1Affine,
+    v: Vec<u8>,
+    w: G2Affine,
+}
+
+/// Point of `G1` from its compressed encoding, the serialization of keys and decryption shares.
+fn g1_from_compressed(bytes: &[u8]) -> Option<G1Affine> {
+    if bytes.len() != G1_SIZE {
+        return None;
+    }
+    let mut point = G1Compressed::empty();
+    point.as_mut().copy_from_slice(bytes);
+    point.into_affine().ok()
+}
+
+impl CiphertextParts {
+    fn from_ciphertext(ciphertext: &Ciphertext) -> Option<Self> {
+        let bytes = bincode::serialize(ciphertext).ok()?;
+        if bytes.len() < G1_SIZE + 8 + G2_SIZE {
+            return None;
+        }
+        let (u_bytes, rest) = bytes.split_at(G1_SIZE);
+        let (v_bytes, w_bytes) = rest.split_at(rest.len() - G2_SIZE);
+        let mut w = G2Compressed::empty();
+        w.as_mut().copy_from_slice(w_bytes);
+        Some(Self {
+            u: g1_from_compressed(u_bytes)?,
+            v: bincode::deserialize(v_bytes).ok()?,
+            w: w.into_affine().ok()?,
+        })
+    }
+
+    fn into_ciphertext(self) -> Option<Ciphertext> {
+        let mut bytes = Vec::with_capacity(G1_SIZE + 8 + self.v.len() + G2_SIZE);
+        bytes.extend_from_slice(self.u.into_compressed().as_ref());
+        bytes.extend(bincode::serialize(&self.v).ok()?);
+        bytes.extend_from_slice(self.w.into_compressed().as_ref());
+        bincode::deserialize(&bytes).ok()
+    }
+}
+
+/// Hashes the ciphertext components together with the associated data into `G2`. This replaces
+/// the `hash_g1_g2(U, V)` input of the `threshold_crypto` validity proof, so `W` is only valid
+/// for the exact associated data the ciphertext was produced with.
+fn hash_with_associated_data(u: &G1Affine, v: &[u8], associated_data: &[u8]) -> G2Affine {
+    let mut message = Vec::with_capacity(
+        LABEL_DOMAIN.len() + 16 + v.len() + associated_data.len() + G1_SIZE
+    );
+    message.extend_from_slice(LABEL_DOMAIN);
+    message.extend_from_slice(&(v.len() as u64).to_be_bytes());
+    message.extend_from_slice(v);
+    message.extend_from_slice(&(associated_data.len() as u64).to_be_bytes());
+    message.extend_from_slice(associated_data);
+    message.extend_from_slice(u.into_compressed().as_ref());
+    hash_g2(&message).into_affine()
+}
+
+/// Encrypts `message` so that the resulting ciphertext only verifies together with
+/// `associated_data`. The ciphertext keeps the `threshold_crypto` layout, so decryption shares
+/// and their combination work unchanged once `verify` has succeeded.
+pub fn encrypt(
+    public_key: &PublicKey,
+    message: &[u8],
+    associated_data: &[u8]
+) -> Option<Ciphertext> {
+    let mut seed = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut seed);
+    let ciphertext = public_key.encrypt_with_rng(&mut ChaChaRng::from_seed(seed), message);
+    // Replaying the seed yields the same ephemeral scalar `r` used by `encrypt_with_rng`.
+    let r = Fr::random(&mut ChaChaRng::from_seed(seed));
+    let mut parts = CiphertextParts::from_ciphertext(&ciphertext)?;
+    parts.w = hash_with_associated_data(&parts.u, &parts.v, associated_data).mul(r).into_affine();
+    parts.into_ciphertext()
+}
+
+/// Checks that `ciphertext` was produced for `associated_data`.
+pub fn verify(ciphertext: &Ciphertext, associated_data: &[u8]) -> bool {
+    match CiphertextParts::from_ciphertext(ciphertext) {
+        Some(parts) => {
+            let hash = hash_with_associated_data(&parts.u, &parts.v, associated_data);
+            Bls12::pairing(G1Affine::one(), parts.w) == Bls12::pairing(parts.u, hash)
+        }
+        None => false,
+    }
+}
+
+/// Checks that `decryption_share` is the share of the holder of `public_key_share` for
+/// `ciphertext`, produced for `associated_data`. `PublicKeyShare::verify_decryption_share` checks
+/// shares against the unlabelled `W`, so it rejects every share of a labelled ciphertext.
+pub fn verify_decryption_share(
+    public_key_share: &PublicKeyShare,
+    decryption_share: &DecryptionShare,
+    ciphertext: &Ciphertext,
+    associated_data: &[u8]
+) -> bool {
+    let Some(parts) = CiphertextParts::from_ciphertext(ciphertext) else {
+        return false;
+    };
+    let decryption_share = bincode
+        ::serialize(decryption_share)
+        .ok()
+        .and_then(|bytes| g1_from_compressed(&bytes));
+    let public_key_share = g1_from_compressed(&public_key_share.to_bytes());
+    match (decryption_share, public_key_share) {
+        (Some(decryption_share), Some(public_key_share)) => {
+            let hash = hash_with_associated_data(&parts.u, &parts.v, associated_data);
+            Bls12::pairing(decryption_share, hash) == Bls12::pairing(public_key_share, parts.w)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use threshold_crypto::SecretKeySet;
+
+    #[test]
+    fn should_decrypt_ciphertext_bound_to_associated_data() {
+        let secret_key_set = SecretKeySet::random(1, &mut rand::thread_rng());
+        let public_key_set = secret_key_set.public_keys();
+        let ciphertext = encrypt(
+            &public_key_set.public_key(),
+            b"Hello, World!",
+            b"tenant=acme"
+        ).unwrap();
+
+        assert!(verify(&ciphertext, b"tenant=acme"));
+        assert!(!verify(&ciphertext, b"tenant=other"));
+        assert!(!ciphertext.verify());
+
+        let shares: BTreeMap<usize, _> = (0..2)
+            .map(|id| {
+                let secret_key_share = secret_key_set.secret_key_share(id);
+                (id, secret_key_share.decrypt_share_no_verify(&ciphertext))
+            })
+            .collect();
+        let decrypted_message = public_key_set.decrypt(&shares, &ciphertext).unwrap();
+        assert_eq!(decrypted_message, b"Hello, World!".to_vec());
+    }
+
+    #[test]
+    fn should_not_verify_unbound_ciphertext_with_associated_data() {
+        let secret_key_set = SecretKeySet::random(1, &mut rand::thread_rng());
+        let ciphertext = secret_key_set.public_keys().public_key().encrypt(b"Hello, World!");
+        assert!(!verify(&ciphertext, b""));
+    }
+
+    #[test]
+    fn should_verify_decryption_shares_of_labelled_ciphertext() {
+        let secret_key_set = SecretKeySet::random(1, &mut rand::thread_rng());
+        let public_key_set = secret_key_set.public_keys();
+        let ciphertext = encrypt(
+            &public_key_set.public_key(),
+            b"Hello, World!",
+            b"tenant=acme"
+        ).unwrap();
+        let secret_key_share = secret_key_set.secret_key_share(1);
+        let decryption_share = secret_key_share.decrypt_share_no_verify(&ciphertext);
+
+        assert!(
+            verify_decryption_share(
+                &public_key_set.public_key_share(1),
+                &decryption_share,
+                &ciphertext,
+                b"tenant=acme"
+            )
+        );
+        assert!(
+            !verify_decryption_share(
+                &public_key_set.public_key_share(1),
+                &decryption_share,
+                &ciphertext,
+                b"tenant=other"
+            )
+        );
+        assert!(
+            !verify_decryption_share(
+                &public_key_set.public_key_share(2),
+                &decryption_share,
+                &ciphertext,
+                b"tenant=acme"
+            )
+        );
+        assert!(
+            !public_key_set
+                .public_key_share(1)
+                .verify_decryption_share(&decryption_share, &ciphertext)
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::{ net::SocketAddr, sync::Arc };
+use axum::{ extract::{ Path, State }, http::StatusCode, routing::{ get, post }, Json, Router };
+use crate::approval_queue::{ ApprovalQueue, ApprovalRequest };
+
+#[derive(Clone)]
+pub struct AdminState {
+    pub approval_queue: Option<Arc<ApprovalQueue>>,
+}
+
+async fn list_approvals(
+    State(state): State<AdminState>
+) -> Result<Json<Vec<ApprovalRequest>>, StatusCode> {
+    let approval_queue = state.approval_queue.ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(approval_queue.list()))
+}
+
+async fn resolve_approval(state: AdminState, id: &str, approved: bool) -> StatusCode {
+    match state.approval_queue {
+        Some(approval_queue) if approval_queue.resolve(id, approved) => StatusCode::NO_CONTENT,
+        _ => StatusCode::NOT_FOUND,
+    }
+}
+
+async fn approve(State(state): State<AdminState>, Path(id): Path<String>) -> StatusCode {
+    resolve_approval(state, &id, true).await
+}
+
+async fn deny(State(state): State<AdminState>, Path(id): Path<String>) -> StatusCode {
+    resolve_approval(state, &id, false).await
+}
+
+/// Serves the admin endpoints on the loopback interface only.
+pub async fn serve(id: usize, port: u16, state: AdminState) {
+    let router = Router::new()
+        .route("/approvals", get(list_approvals))
+        .route("/approvals/:id/approve", post(approve))
+        .route("/approvals/:id/deny", post(deny))
+        .with_state(state);
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let listener = tokio::net::TcpListener::bind(address).await.unwrap();
+    tracing::info!(server_id = id, %address, "Admin endpoint listening");
+    axum::serve(listener, router).await.unwrap();
+}
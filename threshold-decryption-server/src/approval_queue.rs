@@ -0,0 +1,140 @@
+use std::{ collections::HashMap, sync::Mutex, time::Duration };
+use serde::Serialize;
+use tokio::{ sync::oneshot, time::timeout };
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ApprovalRequest {
+    pub id: String,
+    pub requester: Option<String>,
+    pub ciphertext_hash: String,
+    pub associated_data: Option<String>,
+    pub reason: Option<String>,
+    pub received_at: u64,
+    pub expires_at: u64,
+}
+
+struct PendingApproval {
+    request: ApprovalRequest,
+    decision: oneshot::Sender<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalOutcome {
+    Approved,
+    Denied,
+    TimedOut,
+}
+
+/// Decryption requests held until an operator approves or denies them through the admin
+/// endpoint of the server.
+pub struct ApprovalQueue {
+    approval_timeout: Duration,
+    pending: Mutex<HashMap<String, PendingApproval>>,
+}
+
+impl ApprovalQueue {
+    pub fn new(approval_timeout: Duration) -> Self {
+        Self {
+            approval_timeout,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn approval_timeout(&self) -> Duration {
+        self.approval_timeout
+    }
+
+    /// Holds `request` until it is resolved or the approval timeout elapses.
+    pub async fn wait_for_approval(&self, request: ApprovalRequest) -> ApprovalOutcome {
+        let id = request.id.clone();
+        let (decision, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id.clone(), PendingApproval { request, decision });
+        let outcome = match timeout(self.approval_timeout, receiver).await {
+            Ok(Ok(true)) => ApprovalOutcome::Approved,
+            Ok(Ok(false)) | Ok(Err(_)) => ApprovalOutcome::Denied,
+            Err(_) => ApprovalOutcome::TimedOut,
+        };
+        self.pending.lock().unwrap().remove(&id);
+        outcome
+    }
+
+    pub fn list(&self) -> Vec<ApprovalRequest> {
+        let mut requests: Vec<ApprovalRequest> = self.pending
+            .lock()
+            .unwrap()
+            .values()
+            .map(|pending_approval| pending_approval.request.clone())
+            .collect();
+        requests.sort_by_key(|request| request.received_at);
+        requests
+    }
+
+    /// Resolves a pending request, returning `false` when it does not exist (anymore).
+    pub fn resolve(&self, id: &str, approved: bool) -> bool {
+        match self.pending.lock().unwrap().remove(id) {
+            Some(pending_approval) => pending_approval.decision.send(approved).is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn approval_request(id: &str) -> ApprovalRequest {
+        ApprovalRequest {
+            id: id.to_string(),
+            requester: Some("billing".to_string()),
+            ciphertext_hash: "9f3a".to_string(),
+            associated_data: None,
+            reason: Some("refund".to_string()),
+            received_at: 1_700_000_000,
+            expires_at: 1_700_000_300,
+        }
+    }
+
+    /// Holds `id` in the queue, resolving it once it is listed when `approved` is set.
+    async fn hold(
+        approval_queue: Arc<ApprovalQueue>,
+        id: &str,
+        approved: Option<bool>
+    ) -> ApprovalOutcome {
+        let waiting_queue = Arc::clone(&approval_queue);
+        let request = approval_request(id);
+        let outcome = tokio::spawn(async move { waiting_queue.wait_for_approval(request).await });
+        while approval_queue.list().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(approval_queue.list()[0].id, id);
+        if let Some(approved) = approved {
+            assert!(approval_queue.resolve(id, approved));
+        }
+        let outcome = outcome.await.unwrap();
+        assert!(approval_queue.list().is_empty());
+        outcome
+    }
+
+    #[tokio::test]
+    async fn should_approve_pending_request() {
+        let approval_queue = Arc::new(ApprovalQueue::new(Duration::from_secs(5)));
+        let outcome = hold(approval_queue, "0a1b", Some(true)).await;
+        assert_eq!(outcome, ApprovalOutcome::Approved);
+    }
+
+    #[tokio::test]
+    async fn should_deny_pending_request() {
+        let approval_queue = Arc::new(ApprovalQueue::new(Duration::from_secs(5)));
+        let outcome = hold(approval_queue, "0a1b", Some(false)).await;
+        assert_eq!(outcome, ApprovalOutcome::Denied);
+    }
+
+    #[tokio::test]
+    async fn should_time_out_unresolved_request() {
+        let approval_queue = Arc::new(ApprovalQueue::new(Duration::from_millis(50)));
+        let outcome = hold(Arc::clone(&approval_queue), "0a1b", None).await;
+        assert_eq!(outcome, ApprovalOutcome::TimedOut);
+        assert!(!approval_queue.resolve("0a1b", true));
+    }
+}
@@ -0,0 +1,452 @@
+use std::{
+    fs::{ self, File, OpenOptions },
+    io::{ BufRead, BufReader, Write },
+    os::unix::fs::OpenOptionsExt,
+    path::Path,
+};
+use ring::{
+    digest,
+    rand::SystemRandom,
+    signature::{ Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519 },
+};
+use serde::{ Deserialize, Serialize };
+
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditDecision {
+    Produced,
+    Refused,
+}
+
+/// The decryption request a decision is recorded for.
+pub struct AuditedRequest {
+    pub request_id: String,
+    /// Id the service assigned to the HTTP request, to correlate records with its logs.
+    pub service_request_id: Option<String>,
+    pub requester: Option<String>,
+    pub ciphertext_hash: Option<String>,
+    pub timestamp: u64,
+}
+
+/// Fields covered by the record hash, in a fixed order so the JSON encoding is canonical.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct AuditRecordBody {
+    sequence: u64,
+    server_id: usize,
+    timestamp: u64,
+    request_id: String,
+    /// Skipped when absent so that records written before it existed keep their hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    service_request_id: Option<String>,
+    requester: Option<String>,
+    ciphertext_hash: Option<String>,
+    decision: AuditDecision,
+    reason: Option<String>,
+    previous_hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct AuditRecord {
+    #[serde(flatten)]
+    body: AuditRecordBody,
+    hash: String,
+    signature: String,
+}
+
+/// Signed pointer to the last record, kept next to the log so truncation can be detected. Points
+/// to the genesis hash while the log is empty, so that wiping the log is detected as well.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct AuditHead {
+    sequence: u64,
+    hash: String,
+    signature: String,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn from_hex(value: &str) -> Option<Vec<u8>> {
+    value
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).ok().filter(|pair| pair.len() == 2)?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+fn hash_body(body: &AuditRecordBody) -> String {
+    let serialized_body = serde_json::to_vec(body).unwrap();
+    to_hex(digest::digest(&digest::SHA256, &serialized_body).as_ref())
+}
+
+fn head_message(sequence: u64, hash: &str) -> Vec<u8> {
+    format!("audit-head:{}:{}", sequence, hash).into_bytes()
+}
+
+fn head_path(path: &str) -> String {
+    format!("{}.head", path)
+}
+
+/// Number of records the head says the log holds.
+fn head_records(head: &AuditHead) -> u64 {
+    if head.hash == GENESIS_HASH { 0 } else { head.sequence + 1 }
+}
+
+/// Reads the head of the log at `path`, `None` when there is none, failing when it is not signed
+/// by `public_key`.
+fn read_head(
+    path: &str,
+    public_key: &UnparsedPublicKey<Vec<u8>>
+) -> Result<Option<AuditHead>, String> {
+    if !Path::new(&head_path(path)).exists() {
+        return Ok(None);
+    }
+    let head_content = fs
+        ::read(head_path(path))
+        .map_err(|e| format!("Unable to read the signed head of the log. {}", e))?;
+    let head: AuditHead = serde_json::from_slice(&head_content).map_err(|e| e.to_string())?;
+    let head_signature = from_hex(&head.signature).ok_or("Invalid head signature encoding")?;
+    if public_key.verify(&head_message(head.sequence, &head.hash), &head_signature).is_err() {
+        return Err("Invalid head signature".to_string());
+    }
+    Ok(Some(head))
+}
+
+fn write_head(
+    path: &str,
+    signing_key: &Ed25519KeyPair,
+    sequence: u64,
+    hash: &str
+) -> Result<(), String> {
+    let head = AuditHead {
+        sequence,
+        hash: hash.to_string(),
+        signature: to_hex(signing_key.sign(&head_message(sequence, hash)).as_ref()),
+    };
+    let temporary_head_path = format!("{}.tmp", head_path(path));
+    let serialized_head = serde_json::to_vec(&head).map_err(|e| e.to_string())?;
+    fs::write(&temporary_head_path, serialized_head).map_err(|e| e.to_string())?;
+    fs::rename(&temporary_head_path, head_path(path)).map_err(|e| e.to_string())
+}
+
+/// Checks that `record` is intact and signed by `public_key`.
+fn verify_record(
+    record: &AuditRecord,
+    public_key: &UnparsedPublicKey<Vec<u8>>
+) -> Result<(), &'static str> {
+    if hash_body(&record.body) != record.hash {
+        return Err("record content was modified");
+    }
+    let signature = from_hex(&record.signature).ok_or("invalid signature encoding")?;
+    public_key.verify(record.hash.as_bytes(), &signature).map_err(|_| "invalid record signature")
+}
+
+/// Loads one of the server's Ed25519 signing keys, generating and storing a new one when missing.
+/// New keys are only readable by the server's user: whoever reads the audit key can sign a
+/// tampered log, so it must be kept apart from the log itself.
+pub fn load_signing_key(path: &str) -> Result<Ed25519KeyPair, String> {
+    if !Path::new(path).exists() {
+        let pkcs8_bytes = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).map_err(|e|
+            e.to_string()
+        )?;
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)
+            .and_then(|mut file| file.write_all(pkcs8_bytes.as_ref()))
+            .map_err(|e| e.to_string())?;
+    }
+    let pkcs8_bytes = fs::read(path).map_err(|e| e.to_string())?;
+    Ed25519KeyPair::from_pkcs8(&pkcs8_bytes).map_err(|e| e.to_string())
+}
+
+/// Append-only, hash-chained and signed record of every share a server produced or refused.
+pub struct AuditLog {
+    server_id: usize,
+    path: String,
+    file: File,
+    signing_key: Ed25519KeyPair,
+    next_sequence: u64,
+    last_hash: String,
+}
+
+impl AuditLog {
+    /// Opens the log at `path` to append to it, once its last record is checked against the
+    /// signed head, so that a tampered or truncated log is never extended.
+    pub fn open(
+        server_id: usize,
+        path: &str,
+        signing_key: Ed25519KeyPair
+    ) -> Result<Self, String> {
+        let public_key = UnparsedPublicKey::new(
+            &ED25519,
+            signing_key.public_key().as_ref().to_vec()
+        );
+        let mut next_sequence = 0;
+        let mut last_hash = GENESIS_HASH.to_string();
+        if Path::new(path).exists() {
+            let reader = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+            if let Some(line) = reader.lines().map_while(Result::ok).last() {
+                let record: AuditRecord = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+                verify_record(&record, &public_key).map_err(|e| format!("Last record: {}", e))?;
+                next_sequence = record.body.sequence + 1;
+                last_hash = record.hash;
+            }
+        }
+        match read_head(path, &public_key)? {
+            Some(head) if head_records(&head) != next_sequence || head.hash != last_hash => {
+                return Err(
+                    format!(
+                        "Log does not match its signed head, which points to {} records",
+                        head_records(&head)
+                    )
+                );
+            }
+            Some(_) => {}
+            None if next_sequence == 0 => {
+                write_head(path, &signing_key, 0, GENESIS_HASH)?;
+            }
+            None => {
+                return Err("Missing signed head of the log".to_string());
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            server_id,
+            path: path.to_string(),
+            file,
+            signing_key,
+            next_sequence,
+            last_hash,
+        })
+    }
+
+    pub fn public_key(&self) -> String {
+        to_hex(self.signing_key.public_key().as_ref())
+    }
+
+    pub fn append(
+        &mut self,
+        request: &AuditedRequest,
+        decision: AuditDecision,
+        reason: Option<String>
+    ) -> Result<(), String> {
+        let body = AuditRecordBody {
+            sequence: self.next_sequence,
+            server_id: self.server_id,
+            timestamp: request.timestamp,
+            request_id: request.request_id.clone(),
+            service_request_id: request.service_request_id.clone(),
+            requester: request.requester.clone(),
+            ciphertext_hash: request.ciphertext_hash.clone(),
+            decision,
+            reason,
+            previous_hash: self.last_hash.clone(),
+        };
+        let hash = hash_body(&body);
+        let signature = to_hex(self.signing_key.sign(hash.as_bytes()).as_ref());
+        let record = AuditRecord { body, hash: hash.clone(), signature };
+        let mut line = serde_json::to_vec(&record).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+        self.file.write_all(&line).map_err(|e| e.to_string())?;
+        self.file.sync_data().map_err(|e| e.to_string())?;
+
+        write_head(&self.path, &self.signing_key, self.next_sequence, &hash)?;
+
+        self.next_sequence += 1;
+        self.last_hash = hash;
+        Ok(())
+    }
+}
+
+/// Verifies the chain of an audit log against the public key of the server that wrote it,
+/// returning the number of verified records. Logs without signed head are rejected.
+pub fn verify(path: &str, public_key_hex: &str) -> Result<u64, String> {
+    let public_key_bytes = from_hex(public_key_hex).ok_or("Invalid public key encoding")?;
+    let public_key = UnparsedPublicKey::new(&ED25519, public_key_bytes);
+    let reader = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+    let mut expected_sequence = 0;
+    let mut previous_hash = GENESIS_HASH.to_string();
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        let record: AuditRecord = serde_json
+            ::from_str(&line)
+            .map_err(|e| format!("Line {}: malformed record. {}", line_number + 1, e))?;
+        if record.body.sequence != expected_sequence {
+            return Err(
+                format!(
+                    "Line {}: expected sequence {} but found {}",
+                    line_number + 1,
+                    expected_sequence,
+                    record.body.sequence
+                )
+            );
+        }
+        if record.body.previous_hash != previous_hash {
+            return Err(format!("Line {}: broken hash chain", line_number + 1));
+        }
+        verify_record(&record, &public_key).map_err(|e| {
+            format!("Line {}: {}", line_number + 1, e)
+        })?;
+        expected_sequence += 1;
+        previous_hash = record.hash;
+    }
+
+    let head = read_head(path, &public_key)?.ok_or(
+        "Missing signed head of the log, which may have been wiped"
+    )?;
+    if expected_sequence != head_records(&head) || previous_hash != head.hash {
+        return Err(
+            format!(
+                "Log was truncated: the signed head points to {} records but the log holds {}",
+                head_records(&head),
+                expected_sequence
+            )
+        );
+    }
+    Ok(expected_sequence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Path of a new file in the temporary directory, unique to the test.
+    fn temporary_path(name: &str) -> String {
+        let directory = std::env::temp_dir().join(
+            format!("decryption_server_audit_{}_{}", std::process::id(), name)
+        );
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory.join(name).to_string_lossy().into_owned()
+    }
+
+    fn audited_request(requester: &str) -> AuditedRequest {
+        AuditedRequest {
+            request_id: "5e1f".to_string(),
+            service_request_id: Some("0a1b".to_string()),
+            requester: Some(requester.to_string()),
+            ciphertext_hash: Some("9f3a".to_string()),
+            timestamp: 1_700_000_000,
+        }
+    }
+
+    /// Writes a log of three records, returning the public key that signed it.
+    fn write_log(path: &str) -> String {
+        let signing_key = load_signing_key(&format!("{}.key", path)).unwrap();
+        let mut audit_log = AuditLog::open(1, path, signing_key).unwrap();
+        for requester in ["alice", "bob", "carol"] {
+            let reason = (requester == "bob").then(|| "refused by policy".to_string());
+            let decision = if reason.is_some() {
+                AuditDecision::Refused
+            } else {
+                AuditDecision::Produced
+            };
+            audit_log.append(&audited_request(requester), decision, reason).unwrap();
+        }
+        audit_log.public_key()
+    }
+
+    fn lines(path: &str) -> Vec<String> {
+        fs::read_to_string(path).unwrap().lines().map(str::to_string).collect()
+    }
+
+    fn write_lines(path: &str, lines: &[String]) {
+        let content: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn should_verify_appended_records() {
+        let path = temporary_path("appended.log");
+        let public_key = write_log(&path);
+        assert_eq!(verify(&path, &public_key), Ok(3));
+
+        let signing_key = load_signing_key(&format!("{}.key", path)).unwrap();
+        let mut audit_log = AuditLog::open(1, &path, signing_key).unwrap();
+        audit_log.append(&audited_request("dave"), AuditDecision::Produced, None).unwrap();
+        assert_eq!(verify(&path, &public_key), Ok(4));
+    }
+
+    #[test]
+    fn should_verify_empty_log() {
+        let path = temporary_path("empty.log");
+        let signing_key = load_signing_key(&format!("{}.key", path)).unwrap();
+        let public_key = AuditLog::open(1, &path, signing_key).unwrap().public_key();
+        assert_eq!(verify(&path, &public_key), Ok(0));
+    }
+
+    #[test]
+    fn should_detect_modified_record() {
+        let path = temporary_path("modified.log");
+        let public_key = write_log(&path);
+        let mut lines = lines(&path);
+        lines[1] = lines[1].replace("bob", "eve");
+        write_lines(&path, &lines);
+        assert!(verify(&path, &public_key).unwrap_err().contains("modified"));
+    }
+
+    #[test]
+    fn should_detect_reordered_records() {
+        let path = temporary_path("reordered.log");
+        let public_key = write_log(&path);
+        let mut lines = lines(&path);
+        lines.swap(1, 2);
+        write_lines(&path, &lines);
+        assert!(verify(&path, &public_key).unwrap_err().contains("expected sequence 1"));
+    }
+
+    #[test]
+    fn should_detect_truncated_log() {
+        let path = temporary_path("truncated.log");
+        let public_key = write_log(&path);
+        let lines = lines(&path);
+        write_lines(&path, &lines[..2]);
+        assert!(verify(&path, &public_key).unwrap_err().contains("truncated"));
+
+        let signing_key = load_signing_key(&format!("{}.key", path)).unwrap();
+        assert!(AuditLog::open(1, &path, signing_key).is_err());
+    }
+
+    #[test]
+    fn should_detect_wiped_log() {
+        let path = temporary_path("wiped.log");
+        let public_key = write_log(&path);
+        write_lines(&path, &[]);
+        fs::remove_file(head_path(&path)).unwrap();
+        assert!(verify(&path, &public_key).unwrap_err().contains("Missing signed head"));
+    }
+
+    #[test]
+    fn should_not_extend_tampered_log() {
+        let path = temporary_path("tampered.log");
+        write_log(&path);
+        let mut lines = lines(&path);
+        lines[2] = lines[2].replace("carol", "eve");
+        write_lines(&path, &lines);
+        let signing_key = load_signing_key(&format!("{}.key", path)).unwrap();
+        assert!(AuditLog::open(1, &path, signing_key).is_err());
+    }
+
+    #[test]
+    fn should_store_new_signing_key_readable_by_owner_only() {
+        let path = temporary_path("signing.key");
+        load_signing_key(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
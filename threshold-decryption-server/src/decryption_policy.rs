@@ -0,0 +1,271 @@
+use std::{ collections::{ HashMap, VecDeque }, fmt, fs };
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct HourRange {
+    /// First allowed hour (UTC, 0-23).
+    pub start: u64,
+    /// First hour (UTC, 0-23) that is no longer allowed. Ranges may wrap around midnight.
+    pub end: u64,
+}
+
+impl HourRange {
+    fn contains(&self, hour: u64) -> bool {
+        if self.start <= self.end {
+            hour >= self.start && hour < self.end
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct UsageLimit {
+    pub max_decryptions: usize,
+    pub window_secs: u64,
+}
+
+/// Local rules a Decryption Server evaluates before producing a decryption share. Every rule is
+/// optional; an empty policy allows every correctly signed request.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DecryptionPolicy {
+    /// Requester identities allowed to decrypt. Any requester is allowed when unset. Requesters
+    /// are asserted by the service, so this rule cannot hold against a compromised service.
+    pub allowed_requesters: Option<Vec<String>>,
+    /// Allowed associated data, either exact values or prefixes ending with `*`. Associated data
+    /// is bound to the ciphertext, so this rule holds against a compromised service.
+    pub allowed_associated_data: Option<Vec<String>>,
+    /// Refuse ciphertexts that are not bound to any associated data.
+    pub require_associated_data: bool,
+    /// Hours of the day (UTC) in which decryptions are allowed.
+    pub allowed_hours_utc: Option<HourRange>,
+    /// Maximum number of decryptions per key id within a sliding window.
+    pub key_limit: Option<UsageLimit>,
+    /// Overrides of `key_limit` for specific key ids.
+    pub key_limits: HashMap<String, UsageLimit>,
+}
+
+impl DecryptionPolicy {
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&content).map_err(|e| e.to_string())
+    }
+}
+
+pub struct PolicyRequest<'a> {
+    pub requester: Option<&'a str>,
+    pub associated_data: Option<&'a [u8]>,
+    pub key_id: Option<&'a str>,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDenial {
+    RequesterNotAllowed(String),
+    AssociatedDataRequired,
+    AssociatedDataNotAllowed,
+    OutsideAllowedHours(u64),
+    KeyLimitExceeded(String),
+}
+
+impl fmt::Display for PolicyDenial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyDenial::RequesterNotAllowed(requester) =>
+                write!(f, "requester `{}` is not allowed", requester),
+            PolicyDenial::AssociatedDataRequired => write!(f, "associated data is required"),
+            PolicyDenial::AssociatedDataNotAllowed => write!(f, "associated data is not allowed"),
+            PolicyDenial::OutsideAllowedHours(hour) =>
+                write!(f, "decryptions are not allowed at {:02}:00 UTC", hour),
+            PolicyDenial::KeyLimitExceeded(key_id) =>
+                write!(f, "decryption limit exceeded for key `{}`", key_id),
+        }
+    }
+}
+
+pub struct PolicyEngine {
+    policy: DecryptionPolicy,
+    key_usage: HashMap<String, VecDeque<u64>>,
+}
+
+impl PolicyEngine {
+    pub fn new(policy: DecryptionPolicy) -> Self {
+        Self {
+            policy,
+            key_usage: HashMap::new(),
+        }
+    }
+
+    /// Evaluates every rule of the policy, the usage limit of the key included. The request is
+    /// only counted against the limit by `record_usage`, once its share is produced.
+    pub fn evaluate(&mut self, request: &PolicyRequest) -> Result<(), PolicyDenial> {
+        if let Some(allowed_requesters) = &self.policy.allowed_requesters {
+            let requester = request.requester.unwrap_or_default();
+            if !allowed_requesters.iter().any(|allowed| allowed == requester) {
+                return Err(PolicyDenial::RequesterNotAllowed(requester.to_string()));
+            }
+        }
+        match request.associated_data {
+            Some(associated_data) => {
+                if let Some(allowed_associated_data) = &self.policy.allowed_associated_data {
+                    let associated_data = String::from_utf8_lossy(associated_data);
+                    let is_allowed = allowed_associated_data
+                        .iter()
+                        .any(|pattern| matches_pattern(pattern, &associated_data));
+                    if !is_allowed {
+                        return Err(PolicyDenial::AssociatedDataNotAllowed);
+                    }
+                }
+            }
+            None => {
+                if self.policy.require_associated_data {
+                    return Err(PolicyDenial::AssociatedDataRequired);
+                }
+            }
+        }
+        if let Some(allowed_hours_utc) = &self.policy.allowed_hours_utc {
+            let hour = (request.timestamp / 3600) % 24;
+            if !allowed_hours_utc.contains(hour) {
+                return Err(PolicyDenial::OutsideAllowedHours(hour));
+            }
+        }
+        self.key_usage(request.key_id.unwrap_or("unknown"), request.timestamp).map(|_| ())
+    }
+
+    /// Counts a produced share against the usage limit of `key_id`, unless the limit was reached
+    /// in the meantime, e.g. while the request awaited approval.
+    pub fn record_usage(&mut self, key_id: &str, timestamp: u64) -> Result<(), PolicyDenial> {
+        if let Some(usage) = self.key_usage(key_id, timestamp)? {
+            usage.push_back(timestamp);
+        }
+        Ok(())
+    }
+
+    /// Decryptions of `key_id` within the window of its usage limit, `None` when it has none.
+    fn key_usage(
+        &mut self,
+        key_id: &str,
+        timestamp: u64
+    ) -> Result<Option<&mut VecDeque<u64>>, PolicyDenial> {
+        let key_limit = self.policy.key_limits.get(key_id).or(self.policy.key_limit.as_ref());
+        let Some(key_limit) = key_limit else {
+            return Ok(None);
+        };
+        let usage = self.key_usage.entry(key_id.to_string()).or_default();
+        let window_start = timestamp.saturating_sub(key_limit.window_secs);
+        while usage.front().is_some_and(|used_at| *used_at <= window_start) {
+            usage.pop_front();
+        }
+        if usage.len() >= key_limit.max_decryptions {
+            return Err(PolicyDenial::KeyLimitExceeded(key_id.to_string()));
+        }
+        Ok(Some(usage))
+    }
+}
+
+fn matches_pattern(pattern: &str, value: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => value.starts_with(prefix),
+        None => pattern == value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIDNIGHT: u64 = 1_700_006_400;
+
+    fn request(key_id: &str, timestamp: u64) -> PolicyRequest<'_> {
+        PolicyRequest {
+            requester: Some("billing"),
+            associated_data: None,
+            key_id: Some(key_id),
+            timestamp,
+        }
+    }
+
+    fn limit(max_decryptions: usize, window_secs: u64) -> UsageLimit {
+        UsageLimit {
+            max_decryptions,
+            window_secs,
+        }
+    }
+
+    #[test]
+    fn should_allow_hours_of_range_wrapping_past_midnight() {
+        let mut engine = PolicyEngine::new(DecryptionPolicy {
+            allowed_hours_utc: Some(HourRange { start: 22, end: 6 }),
+            ..DecryptionPolicy::default()
+        });
+        assert_eq!(engine.evaluate(&request("0a1b", MIDNIGHT - 3600)), Ok(()));
+        assert_eq!(engine.evaluate(&request("0a1b", MIDNIGHT + 5 * 3600)), Ok(()));
+        assert_eq!(
+            engine.evaluate(&request("0a1b", MIDNIGHT + 6 * 3600)),
+            Err(PolicyDenial::OutsideAllowedHours(6))
+        );
+        assert_eq!(
+            engine.evaluate(&request("0a1b", MIDNIGHT + 12 * 3600)),
+            Err(PolicyDenial::OutsideAllowedHours(12))
+        );
+    }
+
+    #[test]
+    fn should_match_prefix_patterns() {
+        assert!(matches_pattern("orders/*", "orders/42"));
+        assert!(matches_pattern("orders/*", "orders/"));
+        assert!(!matches_pattern("orders/*", "invoices/42"));
+        assert!(matches_pattern("*", "anything"));
+        assert!(matches_pattern("orders/42", "orders/42"));
+        assert!(!matches_pattern("orders/42", "orders/420"));
+    }
+
+    #[test]
+    fn should_override_key_limit_for_specific_keys() {
+        let mut engine = PolicyEngine::new(DecryptionPolicy {
+            key_limit: Some(limit(1, 60)),
+            key_limits: HashMap::from([("3c4d".to_string(), limit(2, 60))]),
+            ..DecryptionPolicy::default()
+        });
+        for timestamp in [MIDNIGHT, MIDNIGHT + 1] {
+            assert_eq!(engine.record_usage("3c4d", timestamp), Ok(()));
+        }
+        assert_eq!(
+            engine.evaluate(&request("3c4d", MIDNIGHT + 2)),
+            Err(PolicyDenial::KeyLimitExceeded("3c4d".to_string()))
+        );
+        assert_eq!(engine.record_usage("0a1b", MIDNIGHT), Ok(()));
+        assert_eq!(
+            engine.evaluate(&request("0a1b", MIDNIGHT + 2)),
+            Err(PolicyDenial::KeyLimitExceeded("0a1b".to_string()))
+        );
+    }
+
+    #[test]
+    fn should_expire_usage_out_of_the_sliding_window() {
+        let mut engine = PolicyEngine::new(DecryptionPolicy {
+            key_limit: Some(limit(2, 60)),
+            ..DecryptionPolicy::default()
+        });
+        assert_eq!(engine.record_usage("0a1b", MIDNIGHT), Ok(()));
+        assert_eq!(engine.record_usage("0a1b", MIDNIGHT + 30), Ok(()));
+        assert!(engine.record_usage("0a1b", MIDNIGHT + 59).is_err());
+        assert_eq!(engine.record_usage("0a1b", MIDNIGHT + 60), Ok(()));
+        assert!(engine.record_usage("0a1b", MIDNIGHT + 61).is_err());
+        assert_eq!(engine.record_usage("0a1b", MIDNIGHT + 90), Ok(()));
+    }
+
+    #[test]
+    fn should_not_count_evaluated_requests_against_the_limit() {
+        let mut engine = PolicyEngine::new(DecryptionPolicy {
+            key_limit: Some(limit(1, 60)),
+            ..DecryptionPolicy::default()
+        });
+        for timestamp in [MIDNIGHT, MIDNIGHT + 1, MIDNIGHT + 2] {
+            assert_eq!(engine.evaluate(&request("0a1b", timestamp)), Ok(()));
+        }
+        assert_eq!(engine.record_usage("0a1b", MIDNIGHT + 3), Ok(()));
+        assert!(engine.evaluate(&request("0a1b", MIDNIGHT + 4)).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use std::{ sync::Arc, time::{ Duration, SystemTime, UNIX_EPOCH } };
+use amqprs::{
+    channel::{ BasicPublishArguments, Channel, ExchangeDeclareArguments },
+    BasicProperties,
+};
+use ring::signature::{ Ed25519KeyPair, KeyPair };
+use serde::Serialize;
+use tracing::warn;
+use crate::{ identity, server_status::{ ServedKey, ServerStatus } };
+
+const HEARTBEATS_EXCHANGE: &str = "heartbeats_exchange";
+
+/// Liveness and key state of the server, published for the service's server registry.
+#[derive(Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+struct Heartbeat {
+    id: usize,
+    version: String,
+    /// Ed25519 public key the heartbeat is signed with.
+    identity_key: Vec<u8>,
+    key_fingerprints: Vec<ServedKey>,
+    /// Messages consumed since the previous heartbeat.
+    load: u64,
+    sent_at: u64,
+}
+
+fn signed_heartbeat(status: &ServerStatus, identity_key: &Ed25519KeyPair, load: u64) -> Vec<u8> {
+    let heartbeat = Heartbeat {
+        id: status.id,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        identity_key: identity_key.public_key().as_ref().to_vec(),
+        key_fingerprints: status.served_keys(),
+        load,
+        sent_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+    };
+    identity::sign(identity_key, &heartbeat)
+}
+
+/// Publishes a heartbeat signed with `identity_key` every `interval`, for as long as the server
+/// runs. Heartbeats that cannot be published are skipped.
+pub async fn publish(
+    channel: Channel,
+    status: Arc<ServerStatus>,
+    identity_key: Arc<Ed25519KeyPair>,
+    interval: Duration
+) {
+    let declared = channel
+        .exchange_declare(
+            ExchangeDeclareArguments::new(HEARTBEATS_EXCHANGE, "fanout").durable(true).to_owned()
+        ).await;
+    if let Err(e) = declared {
+        warn!(error = %e, "Unable to declare the heartbeats exchange");
+    }
+    let mut ticker = tokio::time::interval(interval);
+    let mut requests_handled = status.requests_handled.get();
+    loop {
+        ticker.tick().await;
+        let total_requests_handled = status.requests_handled.get();
+        let signed_heartbeat = signed_heartbeat(
+            &status,
+            &identity_key,
+            total_requests_handled - requests_handled
+        );
+        requests_handled = total_requests_handled;
+        let published = channel
+            .basic_publish(
+                BasicProperties::default(),
+                signed_heartbeat,
+                BasicPublishArguments::new(HEARTBEATS_EXCHANGE, "*")
+            ).await;
+        if let Err(e) = published {
+            warn!(error = %e, "Unable to publish heartbeat");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use threshold_crypto::{ poly::Poly, IntoFr, SecretKeySet };
+    use crate::identity::{ open, test_identity_key };
+
+    #[test]
+    fn should_sign_heartbeat_with_served_keys() {
+        let identity_key = test_identity_key();
+        let status = ServerStatus::new(1, false, 0);
+        let secret_key_set = SecretKeySet::from(Poly::from(vec![5.into_fr(), 7.into_fr()]));
+        let secret_key_share = secret_key_set.secret_key_share(1);
+        status.set_served_keys(
+            vec![ServedKey::new("0a1b", &secret_key_share, &secret_key_set.public_keys())]
+        );
+        let content = signed_heartbeat(&status, &identity_key, 3);
+        let heartbeat: Heartbeat = open(&content, identity_key.public_key().as_ref()).unwrap();
+        assert_eq!(heartbeat.id, 1);
+        assert_eq!(heartbeat.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(heartbeat.identity_key, identity_key.public_key().as_ref());
+        assert_eq!(heartbeat.key_fingerprints, status.served_keys());
+        assert_eq!(heartbeat.load, 3);
+    }
+
+    #[test]
+    fn should_not_verify_tampered_heartbeat() {
+        let identity_key = test_identity_key();
+        let mut content = signed_heartbeat(&ServerStatus::new(1, false, 0), &identity_key, 3);
+        let last = content.len() - 1;
+        content[last] ^= 1;
+        assert!(open::<Heartbeat>(&content, identity_key.public_key().as_ref()).is_none());
+    }
+}
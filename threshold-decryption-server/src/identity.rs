@@ -0,0 +1,54 @@
+use ring::signature::Ed25519KeyPair;
+use serde::Serialize;
+
+/// Serializes `message` signed with the identity key of the server: the Ed25519 signature
+/// followed by the serialized message, the format the service verifies.
+pub fn sign<T: Serialize>(identity_key: &Ed25519KeyPair, message: &T) -> Vec<u8> {
+    let serialized_message = bincode::serialize(message).unwrap();
+    let mut signed_message = identity_key.sign(&serialized_message).as_ref().to_vec();
+    signed_message.extend_from_slice(&serialized_message);
+    signed_message
+}
+
+/// Deserializes a message signed by `sign`, `None` when it is not signed by `public_key`.
+#[cfg(test)]
+pub fn open<T: serde::de::DeserializeOwned>(content: &[u8], public_key: &[u8]) -> Option<T> {
+    use ring::signature::{ UnparsedPublicKey, ED25519 };
+
+    let (signature, signed_message) = content.split_at(content.len().min(64));
+    UnparsedPublicKey::new(&ED25519, public_key).verify(signed_message, signature).ok()?;
+    bincode::deserialize(signed_message).ok()
+}
+
+/// Identity key generated for tests.
+#[cfg(test)]
+pub fn test_identity_key() -> Ed25519KeyPair {
+    let rng = ring::rand::SystemRandom::new();
+    let pkcs8_bytes = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    Ed25519KeyPair::from_pkcs8(pkcs8_bytes.as_ref()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::KeyPair;
+
+    #[test]
+    fn should_open_signed_message() {
+        let identity_key = test_identity_key();
+        let content = sign(&identity_key, &(1usize, "5e1f".to_string()));
+        let message: Option<(usize, String)> = open(&content, identity_key.public_key().as_ref());
+        assert_eq!(message, Some((1, "5e1f".to_string())));
+    }
+
+    #[test]
+    fn should_not_open_tampered_or_foreign_message() {
+        let identity_key = test_identity_key();
+        let mut content = sign(&identity_key, &(1usize, "5e1f".to_string()));
+        let other_key = test_identity_key();
+        assert!(open::<(usize, String)>(&content, other_key.public_key().as_ref()).is_none());
+        let last = content.len() - 1;
+        content[last] ^= 1;
+        assert!(open::<(usize, String)>(&content, identity_key.public_key().as_ref()).is_none());
+    }
+}
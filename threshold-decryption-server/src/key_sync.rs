@@ -0,0 +1,202 @@
+use std::{ sync::Arc, time::{ Duration, SystemTime, UNIX_EPOCH } };
+use amqprs::{
+    channel::{ BasicPublishArguments, Channel, ExchangeDeclareArguments },
+    BasicProperties,
+};
+use ring::signature::{ Ed25519KeyPair, KeyPair };
+use serde::Serialize;
+use tracing::{ info, warn };
+use crate::{ identity, server_status::{ ServedKey, ServerStatus } };
+
+const KEY_SYNC_EXCHANGE: &str = "key_sync_exchange";
+const KEY_REQUESTS_EXCHANGE: &str = "key_requests_exchange";
+
+/// Confirmation that the server loaded the key shares of a key sync, for the service to stop
+/// delivering them, or refusal of the sync when some share did not match its public key set.
+#[derive(Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+struct KeySyncAck {
+    id: usize,
+    /// Id of the key sync, copied from its `request_id` header.
+    sync_id: Option<String>,
+    /// Ed25519 public key the acknowledgement is signed with.
+    identity_key: Vec<u8>,
+    key_fingerprints: Vec<ServedKey>,
+    /// Key sets whose share did not match their public key set, empty once the sync is loaded.
+    mismatched_key_ids: Vec<String>,
+    sent_at: u64,
+}
+
+/// Request for the service to deliver the key shares of the server again.
+#[derive(Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+struct KeyShareRequest {
+    id: usize,
+    /// Ed25519 public key the request is signed with, the one the server was enrolled with.
+    identity_key: Vec<u8>,
+    sent_at: u64,
+}
+
+pub async fn setup_key_sync_exchange(channel: &Channel) {
+    channel
+        .exchange_declare(
+            ExchangeDeclareArguments::new(KEY_SYNC_EXCHANGE, "fanout").durable(true).to_owned()
+        ).await
+        .unwrap();
+}
+
+fn signed_acknowledgement(
+    status: &ServerStatus,
+    identity_key: &Ed25519KeyPair,
+    sync_id: Option<String>,
+    mismatched_key_ids: Vec<String>
+) -> Vec<u8> {
+    let ack = KeySyncAck {
+        id: status.id,
+        sync_id,
+        identity_key: identity_key.public_key().as_ref().to_vec(),
+        key_fingerprints: status.served_keys(),
+        mismatched_key_ids,
+        sent_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+    };
+    identity::sign(identity_key, &ack)
+}
+
+/// Publishes an acknowledgement of the key shares the server now serves, signed with
+/// `identity_key`. With `mismatched_key_ids`, reports the sync as refused instead.
+pub async fn acknowledge(
+    channel: &Channel,
+    status: &ServerStatus,
+    identity_key: &Ed25519KeyPair,
+    sync_id: Option<String>,
+    mismatched_key_ids: Vec<String>
+) {
+    let published = channel
+        .basic_publish(
+            BasicProperties::default(),
+            signed_acknowledgement(status, identity_key, sync_id, mismatched_key_ids),
+            BasicPublishArguments::new(KEY_SYNC_EXCHANGE, "*")
+        ).await;
+    match published {
+        Ok(()) => info!("Key sync acknowledged"),
+        Err(e) => warn!(error = %e, "Unable to acknowledge key sync"),
+    }
+}
+
+fn signed_key_share_request(status: &ServerStatus, identity_key: &Ed25519KeyPair) -> Vec<u8> {
+    let request = KeyShareRequest {
+        id: status.id,
+        identity_key: identity_key.public_key().as_ref().to_vec(),
+        sent_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+    };
+    identity::sign(identity_key, &request)
+}
+
+/// Requests the key shares of the server every `interval` until a key sync loads them, for
+/// servers started after their key sync was consumed or their queue was deleted.
+pub async fn request_key_shares(
+    channel: Channel,
+    status: Arc<ServerStatus>,
+    identity_key: Arc<Ed25519KeyPair>,
+    interval: Duration
+) {
+    let declared = channel
+        .exchange_declare(
+            ExchangeDeclareArguments::new(KEY_REQUESTS_EXCHANGE, "fanout").durable(true).to_owned()
+        ).await;
+    if let Err(e) = declared {
+        warn!(error = %e, "Unable to declare the key requests exchange");
+    }
+    loop {
+        // A key sync still queued for the server is consumed before any request is sent.
+        tokio::time::sleep(interval).await;
+        if !status.served_keys().is_empty() {
+            return;
+        }
+        let published = channel
+            .basic_publish(
+                BasicProperties::default(),
+                signed_key_share_request(&status, &identity_key),
+                BasicPublishArguments::new(KEY_REQUESTS_EXCHANGE, "*")
+            ).await;
+        match published {
+            Ok(()) => info!("Key shares not available, requested from the service"),
+            Err(e) => warn!(error = %e, "Unable to request key shares"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use threshold_crypto::{ poly::Poly, IntoFr, SecretKeySet };
+    use crate::identity::{ open, test_identity_key };
+
+    fn serving_status() -> ServerStatus {
+        let status = ServerStatus::new(1, false, 0);
+        let secret_key_set = SecretKeySet::from(Poly::from(vec![5.into_fr(), 7.into_fr()]));
+        let secret_key_share = secret_key_set.secret_key_share(1);
+        status.set_served_keys(
+            vec![ServedKey::new("0a1b", &secret_key_share, &secret_key_set.public_keys())]
+        );
+        status
+    }
+
+    #[test]
+    fn should_sign_acknowledgement_of_loaded_key_sync() {
+        let identity_key = test_identity_key();
+        let status = serving_status();
+        let content = signed_acknowledgement(
+            &status,
+            &identity_key,
+            Some("5e1f".to_string()),
+            Vec::new()
+        );
+        let ack: KeySyncAck = open(&content, identity_key.public_key().as_ref()).unwrap();
+        assert_eq!(ack.id, 1);
+        assert_eq!(ack.sync_id.as_deref(), Some("5e1f"));
+        assert_eq!(ack.identity_key, identity_key.public_key().as_ref());
+        assert_eq!(ack.key_fingerprints, status.served_keys());
+        assert!(ack.mismatched_key_ids.is_empty());
+    }
+
+    #[test]
+    fn should_sign_refusal_of_mismatched_key_sync() {
+        let identity_key = test_identity_key();
+        let content = signed_acknowledgement(
+            &ServerStatus::new(1, false, 0),
+            &identity_key,
+            Some("5e1f".to_string()),
+            vec!["3c4d".to_string()]
+        );
+        let ack: KeySyncAck = open(&content, identity_key.public_key().as_ref()).unwrap();
+        assert!(ack.key_fingerprints.is_empty());
+        assert_eq!(ack.mismatched_key_ids, vec!["3c4d".to_string()]);
+    }
+
+    #[test]
+    fn should_not_verify_acknowledgement_signed_by_another_key() {
+        let content = signed_acknowledgement(&serving_status(), &test_identity_key(), None, vec![]);
+        let other_key = test_identity_key();
+        assert!(open::<KeySyncAck>(&content, other_key.public_key().as_ref()).is_none());
+    }
+
+    #[test]
+    fn should_sign_key_share_request_with_enrolled_identity_key() {
+        let identity_key = test_identity_key();
+        let content = signed_key_share_request(&ServerStatus::new(2, false, 0), &identity_key);
+        let request: KeyShareRequest = open(&content, identity_key.public_key().as_ref()).unwrap();
+        assert_eq!(request.id, 2);
+        assert_eq!(request.identity_key, identity_key.public_key().as_ref());
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        assert!(now - request.sent_at <= 1);
+    }
+
+    #[test]
+    fn should_not_verify_tampered_key_share_request() {
+        let identity_key = test_identity_key();
+        let mut content = signed_key_share_request(&ServerStatus::new(2, false, 0), &identity_key);
+        content[64] ^= 1;
+        assert!(open::<KeyShareRequest>(&content, identity_key.public_key().as_ref()).is_none());
+    }
+}
@@ -1,6 +1,29 @@
+mod decryption_policy;
+mod approval_queue;
+mod admin_server;
+mod audit_log;
+mod server_status;
+mod status_server;
+mod trace_context;
+mod heartbeat;
+mod key_sync;
+mod refusal;
+mod identity;
+
 use tokio::sync::Notify;
-use ring::signature::{ UnparsedPublicKey, ED25519 };
-use std::{ env, time::{ SystemTime, UNIX_EPOCH } };
+use ring::{
+    digest,
+    rand::{ SecureRandom, SystemRandom },
+    signature::{ Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519 },
+};
+use std::{
+    collections::HashMap,
+    env,
+    net::SocketAddr,
+    process,
+    sync::{ Arc, Mutex },
+    time::{ Duration, SystemTime, UNIX_EPOCH },
+};
 use amqprs::{
     callbacks::{ DefaultChannelCallback, DefaultConnectionCallback },
     channel::{
@@ -14,28 +37,246 @@ use amqprs::{
     connection::{ Connection, OpenConnectionArguments },
     consumer::AsyncConsumer,
     BasicProperties,
+    FieldTable,
+    FieldValue,
 };
 use serde::{ Deserialize, Serialize };
-use threshold_crypto::{ serde_impl::SerdeSecret, Ciphertext, DecryptionShare, SecretKeyShare };
+use tracing::{ error, field, info, info_span, warn, Instrument, Span };
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{ fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter };
+use threshold_crypto::{
+    serde_impl::SerdeSecret,
+    Ciphertext,
+    DecryptionShare,
+    PublicKeySet,
+    SecretKeyShare,
+};
+use decryption_policy::{ DecryptionPolicy, PolicyEngine, PolicyRequest };
+use approval_queue::{ ApprovalOutcome, ApprovalQueue, ApprovalRequest };
+use admin_server::AdminState;
+use audit_log::{ AuditDecision, AuditLog, AuditedRequest };
+use server_status::{ ServedKey, ServerStatus };
 
 #[derive(Serialize, Deserialize, Debug)]
 struct PartialDecryption {
     id: usize,
     decryption_share: DecryptionShare,
+    /// Id of the request the share answers, copied from the `request_id` header.
+    request_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TenantKeyShare {
+    key_id: String,
+    secret_key_share: SerdeSecret<SecretKeyShare>,
+    /// Commitment of the key set, the share of this server must match.
+    public_key_set: PublicKeySet,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct DecryptionServerMessage {
     cipher_text: Option<Vec<u8>>,
     public_key: Option<Vec<u8>>,
-    secret_key_share: Option<Vec<u8>>,
+    secret_key_shares: Option<Vec<u8>>,
     timestamp: Option<u64>,
+    associated_data: Option<Vec<u8>>,
+    requester: Option<String>,
+    key_id: Option<String>,
+    reason: Option<String>,
 }
 
 struct DecryptionServer {
     id: usize,
     signature_public_key: Option<Vec<u8>>,
-    secret_key_share: Option<SecretKeyShare>,
+    /// Share of every tenant key set, by key id.
+    secret_key_shares: HashMap<String, SecretKeyShare>,
+    /// Shared with the requests awaiting approval, which count against usage limits once approved.
+    policy_engine: Arc<Mutex<PolicyEngine>>,
+    approval_queue: Option<Arc<ApprovalQueue>>,
+    audit_log: Arc<Mutex<AuditLog>>,
+    status: Arc<ServerStatus>,
+    identity_key: Arc<Ed25519KeyPair>,
+}
+
+fn decrypt_share(
+    secret_key_share: &SecretKeyShare,
+    cipher_text: &Ciphertext,
+    associated_data: Option<&[u8]>
+) -> Option<DecryptionShare> {
+    match associated_data {
+        Some(associated_data) =>
+            labelled_ciphertext
+                ::verify(cipher_text, associated_data)
+                .then(|| secret_key_share.decrypt_share_no_verify(cipher_text)),
+        None => secret_key_share.decrypt_share(cipher_text),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Id the service assigned to the request, sent in the `request_id` header.
+fn request_id_header(basic_properties: &BasicProperties) -> Option<String> {
+    let request_id = basic_properties.headers()?.get(&"request_id".try_into().ok()?)?;
+    match request_id {
+        FieldValue::S(request_id) => Some(request_id.to_string()),
+        _ => None,
+    }
+}
+
+/// Appends the decision to the audit log, returning `false` when it could not be recorded.
+/// Refusals carry the `reason` label of the refusals metric and a description.
+fn record_decision(
+    status: &ServerStatus,
+    audit_log: &Mutex<AuditLog>,
+    request: &AuditedRequest,
+    refusal: Option<(&'static str, String)>
+) -> bool {
+    let (decision, refusal) = match refusal {
+        Some((label, reason)) => {
+            warn!(audit_request_id = %request.request_id, %reason, "Decryption request refused");
+            status.refusals.with_label_values(&[label]).inc();
+            (AuditDecision::Refused, Some(reason))
+        }
+        None => (AuditDecision::Produced, None),
+    };
+    match audit_log.lock().unwrap().append(request, decision, refusal) {
+        Ok(()) => true,
+        Err(e) => {
+            error!(error = %e, "Unable to append to the audit log");
+            false
+        }
+    }
+}
+
+/// Records the refusal in the audit log, then publishes it for the service to stop awaiting the
+/// share.
+async fn refuse(
+    channel: &Channel,
+    status: &ServerStatus,
+    audit_log: &Mutex<AuditLog>,
+    identity_key: &Ed25519KeyPair,
+    request: &AuditedRequest,
+    label: &'static str,
+    reason: String
+) {
+    let detail = reason.clone();
+    record_decision(status, audit_log, request, Some((label, reason)));
+    let request_id = request.service_request_id.clone();
+    refusal::publish(channel, status.id, identity_key, request_id, label, &detail).await;
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn publish_partial_decryption(
+    channel: &Channel,
+    status: &ServerStatus,
+    (key_id, secret_key_share): (&str, &SecretKeyShare),
+    cipher_text: &Ciphertext,
+    associated_data: Option<&[u8]>,
+    policy_engine: &Mutex<PolicyEngine>,
+    audit_log: &Mutex<AuditLog>,
+    identity_key: &Ed25519KeyPair,
+    audited_request: &AuditedRequest
+) {
+    let decryption_share = info_span!("decrypt_share").in_scope(|| {
+        decrypt_share(secret_key_share, cipher_text, associated_data)
+    });
+    let Some(decryption_share) = decryption_share else {
+        let reason = "invalid ciphertext or associated data".to_string();
+        refuse(
+            channel,
+            status,
+            audit_log,
+            identity_key,
+            audited_request,
+            "invalid_ciphertext",
+            reason
+        ).await;
+        return;
+    };
+    // Only shares actually produced count against the usage limit of the key.
+    let produced_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let usage = policy_engine.lock().unwrap().record_usage(key_id, produced_at);
+    if let Err(denial) = usage {
+        let reason = format!("refused by policy, {}", denial);
+        refuse(channel, status, audit_log, identity_key, audited_request, "policy", reason).await;
+        return;
+    }
+    // A share is only released once its record is durably in the audit log.
+    if !record_decision(status, audit_log, audited_request, None) {
+        return;
+    }
+    let request_id = audited_request.service_request_id.clone();
+    let mut properties = BasicProperties::default();
+    if let Some(request_id) = &request_id {
+        let mut headers = FieldTable::new();
+        headers.insert(
+            "request_id".try_into().unwrap(),
+            FieldValue::S(request_id.clone().try_into().unwrap())
+        );
+        properties.with_headers(headers);
+    }
+    let partial_decryption = PartialDecryption {
+        id: status.id,
+        decryption_share,
+        request_id,
+    };
+    let serialized_partial_decryption = bincode::serialize(&partial_decryption).unwrap();
+    channel
+        .basic_publish(
+            properties.clone(),
+            serialized_partial_decryption,
+            BasicPublishArguments::new("partials_exchange", "*")
+        )
+        .instrument(info_span!("amqp_publish", exchange = "partials_exchange")).await
+        .unwrap();
+    status.shares_produced.inc();
+    info!("Partial decryption sent");
+}
+
+/// Replaces the served key shares with those of a key sync, once each is verified against the
+/// public key set it was dealt from. A single mismatched share refuses the whole sync, keeping
+/// the shares served, and the key ids of the mismatched shares are returned.
+fn load_key_shares(
+    status: &ServerStatus,
+    secret_key_shares: &mut HashMap<String, SecretKeyShare>,
+    tenant_key_shares: Vec<TenantKeyShare>
+) -> Result<(), Vec<String>> {
+    let mismatched_key_ids: Vec<String> = tenant_key_shares
+        .iter()
+        .filter(|tenant_key_share| {
+            tenant_key_share.secret_key_share.public_key_share() !=
+                tenant_key_share.public_key_set.public_key_share(status.id)
+        })
+        .map(|tenant_key_share| tenant_key_share.key_id.clone())
+        .collect();
+    if !mismatched_key_ids.is_empty() {
+        status.refused_key_syncs.inc();
+        return Err(mismatched_key_ids);
+    }
+    status.set_served_keys(
+        tenant_key_shares
+            .iter()
+            .map(|tenant_key_share| {
+                ServedKey::new(
+                    &tenant_key_share.key_id,
+                    &tenant_key_share.secret_key_share,
+                    &tenant_key_share.public_key_set
+                )
+            })
+            .collect()
+    );
+    *secret_key_shares = tenant_key_shares
+        .into_iter()
+        .map(|tenant_key_share| {
+            (tenant_key_share.key_id, tenant_key_share.secret_key_share.into_inner())
+        })
+        .collect();
+    Ok(())
 }
 
 #[async_trait::async_trait]
@@ -44,79 +285,247 @@ impl AsyncConsumer for DecryptionServer {
         &mut self,
         channel: &amqprs::channel::Channel,
         _deliver: amqprs::Deliver,
-        _basic_properties: BasicProperties,
+        basic_properties: BasicProperties,
+        content: Vec<u8>
+    ) {
+        let request_id = request_id_header(&basic_properties);
+        let span = info_span!("message", server_id = self.id, request_id = field::Empty);
+        if let Some(request_id) = &request_id {
+            span.record("request_id", request_id.as_str());
+        }
+        span.set_parent(trace_context::extract(basic_properties.headers()));
+        self.handle_message(channel, request_id, content).instrument(span).await;
+    }
+}
+
+impl DecryptionServer {
+    async fn refuse(
+        &self,
+        channel: &Channel,
+        audited_request: &AuditedRequest,
+        label: &'static str,
+        reason: String
+    ) {
+        refuse(
+            channel,
+            &self.status,
+            &self.audit_log,
+            &self.identity_key,
+            audited_request,
+            label,
+            reason
+        ).await;
+    }
+
+    async fn handle_message(
+        &mut self,
+        channel: &Channel,
+        request_id: Option<String>,
         content: Vec<u8>
     ) {
+        self.status.requests_handled.inc();
+        let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut audited_request = AuditedRequest {
+            request_id: to_hex(&digest::digest(&digest::SHA256, &content).as_ref()[..16]),
+            service_request_id: request_id,
+            requester: None,
+            ciphertext_hash: None,
+            timestamp: current_time,
+        };
         let mut message = DecryptionServerMessage {
             cipher_text: None,
             public_key: None,
-            secret_key_share: None,
+            secret_key_shares: None,
             timestamp: None,
+            associated_data: None,
+            requester: None,
+            key_id: None,
+            reason: None,
         };
-        if self.signature_public_key.is_none() && self.secret_key_share.is_none() {
-            message = bincode::deserialize(&content).unwrap_or(message);
+        let (signature, signed_message) = content.split_at(content.len().min(64));
+        // Until the first key sync, the service is only known by the key it signs the sync with.
+        let signature_public_key = self.signature_public_key.clone().or_else(|| {
+            bincode
+                ::deserialize::<DecryptionServerMessage>(signed_message)
+                .ok()
+                .and_then(|message| message.public_key)
+        });
+        let verification = info_span!("verify_signature").in_scope(|| {
+            signature_public_key.is_some_and(|signature_public_key| {
+                UnparsedPublicKey::new(&ED25519, signature_public_key)
+                    .verify(signed_message, signature)
+                    .is_ok()
+            })
+        });
+        if verification {
+            message = bincode::deserialize(signed_message).unwrap_or(message);
         } else {
-            let (signature, signed_message) = content.split_at(64);
-            let public_key = UnparsedPublicKey::new(
-                &ED25519,
-                self.signature_public_key.as_ref().unwrap()
-            );
-            if public_key.verify(signed_message, signature).is_ok() {
-                message = bincode::deserialize(&signed_message).unwrap_or(message);
-            } else {
-                println!("Server {}: Unrecognized sender signature", self.id);
-            }
+            let reason = "unrecognized sender signature".to_string();
+            self.status.signature_failures.inc();
+            // Unauthenticated messages are only audited, refusing them would let anyone fail
+            // decryptions of others by replaying their request id.
+            let refusal = Some(("signature", reason));
+            record_decision(&self.status, &self.audit_log, &audited_request, refusal);
+            return;
         }
         match
-            (message.cipher_text, message.public_key, message.secret_key_share, message.timestamp)
+            (message.cipher_text, message.public_key, message.secret_key_shares, message.timestamp)
         {
             (Some(cipher_text), None, None, Some(timestamp)) => {
-                let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+                let ciphertext_hash = to_hex(
+                    digest::digest(&digest::SHA256, &cipher_text).as_ref()
+                );
+                audited_request.requester = message.requester.clone();
+                audited_request.ciphertext_hash = Some(ciphertext_hash.clone());
                 let acceptable_range_in_secs = 10;
                 if timestamp < current_time - acceptable_range_in_secs {
-                    println!("Server {}: Message too old", self.id);
+                    let reason = "message too old".to_string();
+                    self.status.stale_messages.inc();
+                    self.refuse(channel, &audited_request, "stale", reason).await;
+                    return;
+                }
+                let policy_request = PolicyRequest {
+                    requester: message.requester.as_deref(),
+                    associated_data: message.associated_data.as_deref(),
+                    key_id: message.key_id.as_deref(),
+                    timestamp: current_time,
+                };
+                let evaluation = info_span!("evaluate_policy").in_scope(|| {
+                    self.policy_engine.lock().unwrap().evaluate(&policy_request)
+                });
+                if let Err(denial) = evaluation {
+                    let reason = format!("refused by policy, {}", denial);
+                    self.refuse(channel, &audited_request, "policy", reason).await;
                     return;
                 }
-                match &self.secret_key_share {
-                    Some(secret_key_share) => {
-                        let encrypted_message: Ciphertext = bincode
-                            ::deserialize(&cipher_text)
-                            .unwrap();
-                        let decryption_share = secret_key_share
-                            .decrypt_share(&encrypted_message)
-                            .unwrap();
-                        let partial_decryption = PartialDecryption {
-                            id: self.id,
-                            decryption_share,
+                let key_share = message.key_id.and_then(|key_id| {
+                    let secret_key_share = self.secret_key_shares.get(&key_id)?.clone();
+                    Some((key_id, secret_key_share))
+                });
+                let Some((key_id, secret_key_share)) = key_share else {
+                    let reason = "secret key share not available".to_string();
+                    self.refuse(channel, &audited_request, "no_share", reason).await;
+                    return;
+                };
+                let encrypted_message: Ciphertext = bincode::deserialize(&cipher_text).unwrap();
+                match &self.approval_queue {
+                    Some(approval_queue) => {
+                        let mut approval_id = [0u8; 8];
+                        SystemRandom::new().fill(&mut approval_id).unwrap();
+                        let approval_request = ApprovalRequest {
+                            id: to_hex(&approval_id),
+                            requester: message.requester,
+                            ciphertext_hash,
+                            associated_data: message.associated_data
+                                .as_deref()
+                                .map(|associated_data| {
+                                    String::from_utf8_lossy(associated_data).into_owned()
+                                }),
+                            reason: message.reason,
+                            received_at: current_time,
+                            expires_at: current_time + approval_queue.approval_timeout().as_secs(),
                         };
-                        let serialized_partial_decryption = bincode
-                            ::serialize(&partial_decryption)
-                            .unwrap();
-                        let properties = BasicProperties::default();
-                        channel
-                            .basic_publish(
-                                properties.clone(),
-                                serialized_partial_decryption,
-                                BasicPublishArguments::new("partials_exchange", "*")
-                            ).await
-                            .unwrap();
-                        println!("Server {}: Partial decryption sent", self.id);
+                        info!(
+                            approval_id = %approval_request.id,
+                            "Decryption request pending approval"
+                        );
+                        let approval_queue = Arc::clone(approval_queue);
+                        let audit_log = Arc::clone(&self.audit_log);
+                        let status = Arc::clone(&self.status);
+                        let identity_key = Arc::clone(&self.identity_key);
+                        let policy_engine = Arc::clone(&self.policy_engine);
+                        let channel = channel.clone();
+                        let span = Span::current();
+                        tokio::spawn(async move {
+                            let approval_id = approval_request.id.clone();
+                            let outcome = approval_queue
+                                .wait_for_approval(approval_request)
+                                .instrument(info_span!("await_approval")).await;
+                            match outcome {
+                                ApprovalOutcome::Approved => {
+                                    info!(%approval_id, "Decryption request approved");
+                                    publish_partial_decryption(
+                                        &channel,
+                                        &status,
+                                        (&key_id, &secret_key_share),
+                                        &encrypted_message,
+                                        message.associated_data.as_deref(),
+                                        &policy_engine,
+                                        &audit_log,
+                                        &identity_key,
+                                        &audited_request
+                                    ).await;
+                                }
+                                outcome => {
+                                    let reason = format!(
+                                        "approval {} not granted ({:?})",
+                                        approval_id,
+                                        outcome
+                                    );
+                                    refuse(
+                                        &channel,
+                                        &status,
+                                        &audit_log,
+                                        &identity_key,
+                                        &audited_request,
+                                        "not_approved",
+                                        reason
+                                    ).await;
+                                }
+                            }
+                        }.instrument(span));
                     }
                     None => {
-                        println!("Server {}: Secret key share not available", self.id);
+                        publish_partial_decryption(
+                            channel,
+                            &self.status,
+                            (&key_id, &secret_key_share),
+                            &encrypted_message,
+                            message.associated_data.as_deref(),
+                            &self.policy_engine,
+                            &self.audit_log,
+                            &self.identity_key,
+                            &audited_request
+                        ).await;
                     }
                 }
             }
-            (None, Some(public_key), Some(secret_key_share), None) => {
-                let deserialized_secret_key_share: SerdeSecret<SecretKeyShare> = bincode
-                    ::deserialize(&secret_key_share)
-                    .unwrap();
-                self.secret_key_share = Some(deserialized_secret_key_share.inner().to_owned());
+            (None, Some(public_key), Some(secret_key_shares), None) => {
+                let tenant_key_shares = bincode::deserialize::<Vec<TenantKeyShare>>(
+                    &secret_key_shares
+                );
+                let Ok(tenant_key_shares) = tenant_key_shares else {
+                    warn!("Invalid key shares received");
+                    return;
+                };
+                let loaded = load_key_shares(
+                    &self.status,
+                    &mut self.secret_key_shares,
+                    tenant_key_shares
+                );
+                if let Err(mismatched_key_ids) = loaded {
+                    error!(?mismatched_key_ids, "Key shares do not match their public key set");
+                    key_sync::acknowledge(
+                        channel,
+                        &self.status,
+                        &self.identity_key,
+                        audited_request.service_request_id,
+                        mismatched_key_ids
+                    ).await;
+                    return;
+                }
                 self.signature_public_key = Some(public_key);
-                println!("Server {}: Keys synced", self.id);
+                info!(tenants = self.secret_key_shares.len(), "Keys synced and verified");
+                key_sync::acknowledge(
+                    channel,
+                    &self.status,
+                    &self.identity_key,
+                    audited_request.service_request_id,
+                    Vec::new()
+                ).await;
             }
             _ => {
-                println!("Server {}: Invalid message received", self.id);
+                warn!("Invalid message received");
             }
         }
     }
@@ -129,7 +538,7 @@ async fn setup_decryption_exchange(channel: &Channel, queue_name: &str) {
             ExchangeDeclareArguments::new(exchange_name, "fanout").durable(true).to_owned()
         ).await
         .unwrap();
-    channel.queue_bind(QueueBindArguments::new(&queue_name, exchange_name, "*")).await.unwrap();
+    channel.queue_bind(QueueBindArguments::new(queue_name, exchange_name, "*")).await.unwrap();
 }
 
 async fn setup_secret_exchange(channel: &Channel, queue_name: &str, id: &usize) {
@@ -141,17 +550,151 @@ async fn setup_secret_exchange(channel: &Channel, queue_name: &str, id: &usize)
         ).await
         .unwrap();
     channel
-        .queue_bind(QueueBindArguments::new(&queue_name, exchange_name, &routing_key)).await
+        .queue_bind(QueueBindArguments::new(queue_name, exchange_name, &routing_key)).await
         .unwrap();
 }
 
+/// Logs JSON lines, or human readable lines when `LOG_FORMAT=text`, filtered by `RUST_LOG`
+/// (`info` by default). Spans are also exported to the OTLP collector at
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` when set, as `OTEL_SERVICE_NAME`.
+fn init_tracing(id: usize) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let json = env::var("LOG_FORMAT").as_deref() != Ok("text");
+    let tracer = env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok().map(|endpoint| {
+        let service_name = env
+            ::var("OTEL_SERVICE_NAME")
+            .unwrap_or(format!("decryption-server-{}", id));
+        trace_context
+            ::otlp_tracer(&endpoint, &service_name)
+            .unwrap_or_else(|e| panic!("Unable to export spans to {}. {}", endpoint, e))
+    });
+    tracing_subscriber
+        ::registry()
+        .with(filter)
+        .with(json.then(|| fmt::layer().json()))
+        .with((!json).then(fmt::layer))
+        .with(tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer)))
+        .init();
+}
+
 #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
 async fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("verify-audit-log") {
+        let (Some(path), Some(public_key)) = (args.get(2), args.get(3)) else {
+            eprintln!("Usage: {} verify-audit-log <path> <public_key_hex>", args[0]);
+            process::exit(2);
+        };
+        match audit_log::verify(path, public_key) {
+            Ok(records) => println!("Audit log {} is intact, {} records verified", path, records),
+            Err(e) => {
+                eprintln!("Audit log {} failed verification: {}", path, e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
     let id: usize = env::var("SERVER_ID").unwrap().parse().unwrap();
+    init_tracing(id);
+    let policy = match env::var("POLICY_FILE") {
+        Ok(policy_file) =>
+            DecryptionPolicy::from_file(&policy_file).unwrap_or_else(|e|
+                panic!("Server {}: Unable to load policy file {}. {}", id, policy_file, e)
+            ),
+        Err(_) => {
+            warn!(server_id = id, "No POLICY_FILE provided, allowing every signed request");
+            DecryptionPolicy::default()
+        }
+    };
+    let approval_queue = match env::var("APPROVAL_MODE").as_deref() {
+        Ok("true") => {
+            // The service must still await the share when an operator approves the request.
+            let decryption_timeout_in_secs: u64 = env
+                ::var("DECRYPTION_TIMEOUT_SECS")
+                .map(|value| value.parse().unwrap())
+                .unwrap_or(10);
+            let approval_timeout_in_secs: u64 = env
+                ::var("APPROVAL_TIMEOUT_SECS")
+                .map(|value| value.parse().unwrap())
+                .unwrap_or(decryption_timeout_in_secs);
+            if approval_timeout_in_secs > decryption_timeout_in_secs {
+                error!(
+                    server_id = id,
+                    approval_timeout_in_secs,
+                    decryption_timeout_in_secs,
+                    "APPROVAL_TIMEOUT_SECS exceeds the DECRYPTION_TIMEOUT_SECS of the service, \
+                    which would give up on approved decryptions"
+                );
+                process::exit(1);
+            }
+            info!(server_id = id, "Approval mode enabled");
+            Some(Arc::new(ApprovalQueue::new(Duration::from_secs(approval_timeout_in_secs))))
+        }
+        _ => None,
+    };
+    let admin_port: u16 = env
+        ::var("ADMIN_PORT")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(9100 + (id as u16));
+    tokio::spawn(
+        admin_server::serve(id, admin_port, AdminState {
+            approval_queue: approval_queue.clone(),
+        })
+    );
+    let started_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let status = Arc::new(ServerStatus::new(id, approval_queue.is_some(), started_at));
+    let status_address: SocketAddr = env
+        ::var("STATUS_ADDRESS")
+        .unwrap_or(format!("0.0.0.0:{}", 9200 + id))
+        .parse()
+        .unwrap_or_else(|e| panic!("Server {}: Invalid STATUS_ADDRESS. {}", id, e));
+    tokio::spawn(status_server::serve(status_address, Arc::clone(&status)));
+    let audit_log_file = env
+        ::var("AUDIT_LOG_FILE")
+        .unwrap_or(format!("decryption_server_{}_audit.log", id));
+    let audit_key_file = env
+        ::var("AUDIT_KEY_FILE")
+        .unwrap_or(format!("decryption_server_{}_audit.key", id));
+    let audit_log = audit_log
+        ::load_signing_key(&audit_key_file)
+        .and_then(|signing_key| AuditLog::open(id, &audit_log_file, signing_key))
+        .unwrap_or_else(|e| panic!("Server {}: Unable to open audit log. {}", id, e));
+    info!(
+        server_id = id,
+        audit_log_file,
+        audit_public_key = %audit_log.public_key(),
+        "Audit log opened"
+    );
+    let identity_key_file = env
+        ::var("IDENTITY_KEY_FILE")
+        .unwrap_or(format!("decryption_server_{}_identity.key", id));
+    let identity_key = audit_log
+        ::load_signing_key(&identity_key_file)
+        .map(Arc::new)
+        .unwrap_or_else(|e| panic!("Server {}: Unable to load identity key. {}", id, e));
+    info!(
+        server_id = id,
+        identity_public_key = %to_hex(identity_key.public_key().as_ref()),
+        "Identity key loaded"
+    );
+    let heartbeat_interval_in_secs: u64 = env
+        ::var("HEARTBEAT_INTERVAL_SECS")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(5);
+    let key_request_interval_in_secs: u64 = env
+        ::var("KEY_REQUEST_INTERVAL_SECS")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(10);
     let decryption_server = DecryptionServer {
         id,
         signature_public_key: None,
-        secret_key_share: None,
+        secret_key_shares: HashMap::new(),
+        policy_engine: Arc::new(Mutex::new(PolicyEngine::new(policy))),
+        approval_queue,
+        audit_log: Arc::new(Mutex::new(audit_log)),
+        status: Arc::clone(&status),
+        identity_key: Arc::clone(&identity_key),
     };
     let queue_name = format!("decryption_server_{}", id);
 
@@ -159,18 +702,148 @@ async fn main() {
         OpenConnectionArguments::new("localhost", 5672, "guest", "guest").heartbeat(30)
     ).await.unwrap();
     connection.register_callback(DefaultConnectionCallback).await.unwrap();
+    status.set_connection(connection.clone());
 
     let channel = connection.open_channel(None).await.unwrap();
     channel.register_callback(DefaultChannelCallback).await.unwrap();
     channel.queue_declare(QueueDeclareArguments::durable_client_named(&queue_name)).await.unwrap();
     setup_decryption_exchange(&channel, &queue_name).await;
     setup_secret_exchange(&channel, &queue_name, &id).await;
+    key_sync::setup_key_sync_exchange(&channel).await;
+    refusal::setup_refusals_exchange(&channel).await;
 
     let consume_args = BasicConsumeArguments::new(&queue_name, &format!("server_{}_consumer", id))
         .manual_ack(false)
         .finish();
     channel.basic_consume(decryption_server, consume_args).await.unwrap();
-    println!("Server {}: RabbitMQ connection established", id);
+    info!(server_id = id, "RabbitMQ connection established");
+
+    let heartbeat_channel = connection.open_channel(None).await.unwrap();
+    heartbeat_channel.register_callback(DefaultChannelCallback).await.unwrap();
+    tokio::spawn(
+        heartbeat::publish(
+            heartbeat_channel,
+            Arc::clone(&status),
+            Arc::clone(&identity_key),
+            Duration::from_secs(heartbeat_interval_in_secs)
+        )
+    );
+    let key_request_channel = connection.open_channel(None).await.unwrap();
+    key_request_channel.register_callback(DefaultChannelCallback).await.unwrap();
+    tokio::spawn(
+        key_sync::request_key_shares(
+            key_request_channel,
+            status,
+            identity_key,
+            Duration::from_secs(key_request_interval_in_secs)
+        )
+    );
     let guard = Notify::new();
     guard.notified().await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use threshold_crypto::{ poly::Poly, IntoFr, SecretKeySet };
+
+    fn key_set(coefficients: [u64; 2]) -> SecretKeySet {
+        SecretKeySet::from(Poly::from(coefficients.map(IntoFr::into_fr).to_vec()))
+    }
+
+    fn tenant_key_share(key_id: &str, key_set: &SecretKeySet, index: usize) -> TenantKeyShare {
+        TenantKeyShare {
+            key_id: key_id.to_string(),
+            secret_key_share: SerdeSecret(key_set.secret_key_share(index)),
+            public_key_set: key_set.public_keys(),
+        }
+    }
+
+    #[test]
+    fn should_decrypt_share_of_ciphertext_bound_to_its_associated_data() {
+        let key_set = key_set([5, 7]);
+        let public_key_set = key_set.public_keys();
+        let ciphertext = labelled_ciphertext::encrypt(
+            &public_key_set.public_key(),
+            b"data key",
+            b"tenant=acme"
+        ).unwrap();
+        let secret_key_share = key_set.secret_key_share(1);
+
+        let decryption_share = decrypt_share(&secret_key_share, &ciphertext, Some(b"tenant=acme"));
+        assert!(
+            decryption_share.is_some_and(|decryption_share| {
+                labelled_ciphertext::verify_decryption_share(
+                    &public_key_set.public_key_share(1),
+                    &decryption_share,
+                    &ciphertext,
+                    b"tenant=acme"
+                )
+            })
+        );
+        assert!(decrypt_share(&secret_key_share, &ciphertext, Some(b"tenant=other")).is_none());
+        // Without associated data the ciphertext goes through the regular validity check.
+        assert!(decrypt_share(&secret_key_share, &ciphertext, None).is_none());
+    }
+
+    #[test]
+    fn should_verify_unlabelled_ciphertext_despite_associated_data() {
+        let key_set = key_set([5, 7]);
+        let ciphertext = key_set.public_keys().public_key().encrypt(b"data key");
+        let secret_key_share = key_set.secret_key_share(1);
+
+        assert!(decrypt_share(&secret_key_share, &ciphertext, Some(b"tenant=acme")).is_none());
+        assert!(decrypt_share(&secret_key_share, &ciphertext, Some(b"")).is_none());
+        assert!(decrypt_share(&secret_key_share, &ciphertext, None).is_some());
+    }
+
+    #[test]
+    fn should_load_key_shares_matching_their_public_key_set() {
+        let status = ServerStatus::new(1, false, 0);
+        let mut secret_key_shares = HashMap::new();
+        let key_set = key_set([5, 7]);
+        let loaded = load_key_shares(
+            &status,
+            &mut secret_key_shares,
+            vec![tenant_key_share("0a1b", &key_set, 1)]
+        );
+        assert_eq!(loaded, Ok(()));
+        assert_eq!(secret_key_shares.get("0a1b"), Some(&key_set.secret_key_share(1)));
+        assert_eq!(
+            status.served_keys(),
+            vec![ServedKey::new("0a1b", &key_set.secret_key_share(1), &key_set.public_keys())]
+        );
+        assert_eq!(status.refused_key_syncs.get(), 0);
+    }
+
+    #[test]
+    fn should_refuse_key_sync_with_mismatched_share() {
+        let status = ServerStatus::new(1, false, 0);
+        let mut secret_key_shares = HashMap::new();
+        let served_key_set = key_set([5, 7]);
+        let served = vec![tenant_key_share("0a1b", &served_key_set, 1)];
+        assert_eq!(load_key_shares(&status, &mut secret_key_shares, served), Ok(()));
+        let served_keys = status.served_keys();
+
+        let other_key_set = key_set([11, 13]);
+        let loaded = load_key_shares(
+            &status,
+            &mut secret_key_shares,
+            vec![
+                tenant_key_share("0a1b", &served_key_set, 1),
+                // Share of another server.
+                tenant_key_share("3c4d", &other_key_set, 2),
+                // Share dealt from another key set.
+                TenantKeyShare {
+                    public_key_set: served_key_set.public_keys(),
+                    ..tenant_key_share("5e6f", &other_key_set, 1)
+                }
+            ]
+        );
+        assert_eq!(loaded, Err(vec!["3c4d".to_string(), "5e6f".to_string()]));
+        assert_eq!(status.refused_key_syncs.get(), 1);
+        assert_eq!(status.served_keys(), served_keys);
+        assert_eq!(secret_key_shares.len(), 1);
+        assert_eq!(secret_key_shares.get("0a1b"), Some(&served_key_set.secret_key_share(1)));
+    }
+}
@@ -0,0 +1,63 @@
+use amqprs::{
+    channel::{ BasicPublishArguments, Channel, ExchangeDeclareArguments },
+    BasicProperties,
+};
+use ring::signature::{ Ed25519KeyPair, KeyPair };
+use serde::Serialize;
+use tracing::warn;
+use crate::identity;
+
+const REFUSALS_EXCHANGE: &str = "refusals_exchange";
+
+/// Refusal to produce a decryption share, for the service to stop waiting for it.
+#[derive(Serialize)]
+struct Refusal {
+    id: usize,
+    /// Id of the refused request, copied from its `request_id` header.
+    request_id: Option<String>,
+    /// Ed25519 public key the refusal is signed with.
+    identity_key: Vec<u8>,
+    /// The `reason` label of the refusals metric, never `signature` as unauthenticated requests
+    /// are not refused publicly.
+    reason: String,
+    detail: String,
+}
+
+pub async fn setup_refusals_exchange(channel: &Channel) {
+    channel
+        .exchange_declare(
+            ExchangeDeclareArguments::new(REFUSALS_EXCHANGE, "fanout").durable(true).to_owned()
+        ).await
+        .unwrap();
+}
+
+/// Publishes the refusal of request `request_id`, signed with `identity_key`. Requests without
+/// id cannot be awaited by the service, so their refusal is not published.
+pub async fn publish(
+    channel: &Channel,
+    id: usize,
+    identity_key: &Ed25519KeyPair,
+    request_id: Option<String>,
+    reason: &str,
+    detail: &str
+) {
+    if request_id.is_none() {
+        return;
+    }
+    let refusal = Refusal {
+        id,
+        request_id,
+        identity_key: identity_key.public_key().as_ref().to_vec(),
+        reason: reason.to_string(),
+        detail: detail.to_string(),
+    };
+    let published = channel
+        .basic_publish(
+            BasicProperties::default(),
+            identity::sign(identity_key, &refusal),
+            BasicPublishArguments::new(REFUSALS_EXCHANGE, "*")
+        ).await;
+    if let Err(e) = published {
+        warn!(error = %e, "Unable to publish refusal");
+    }
+}
@@ -0,0 +1,139 @@
+use std::{ collections::HashMap, sync::Mutex };
+use amqprs::connection::Connection;
+use prometheus::{ Encoder, IntCounter, IntCounterVec, Opts, Registry, TextEncoder };
+use ring::digest;
+use serde::Serialize;
+use threshold_crypto::{ PublicKeySet, SecretKeyShare };
+
+/// A key share served by this server, identified by the fingerprint of its public key share.
+#[derive(Serialize, Debug, Clone)]
+#[cfg_attr(test, derive(serde::Deserialize, PartialEq, Eq))]
+pub struct ServedKey {
+    pub key_id: String,
+    pub public_key_share_fingerprint: String,
+    /// Fingerprint of the public key of the key set the share was verified against.
+    pub public_key_fingerprint: String,
+}
+
+impl ServedKey {
+    pub fn new(
+        key_id: &str,
+        secret_key_share: &SecretKeyShare,
+        public_key_set: &PublicKeySet
+    ) -> Self {
+        Self {
+            key_id: key_id.to_string(),
+            public_key_share_fingerprint: fingerprint(
+                &secret_key_share.public_key_share().to_bytes()
+            ),
+            public_key_fingerprint: fingerprint(&public_key_set.public_key().to_bytes()),
+        }
+    }
+}
+
+/// Liveness, key state and metrics of a Decryption Server, shared between the AMQP consumer and
+/// the status endpoint.
+pub struct ServerStatus {
+    pub id: usize,
+    pub approval_mode: bool,
+    pub started_at: u64,
+    registry: Registry,
+    /// Every message consumed, whatever its outcome.
+    pub requests_handled: IntCounter,
+    pub shares_produced: IntCounter,
+    pub refusals: IntCounterVec,
+    pub signature_failures: IntCounter,
+    pub stale_messages: IntCounter,
+    /// Key syncs refused for holding a share that does not match its public key set.
+    pub refused_key_syncs: IntCounter,
+    served_keys: Mutex<Vec<ServedKey>>,
+    connection: Mutex<Option<Connection>>,
+}
+
+fn fingerprint(bytes: &[u8]) -> String {
+    digest::digest(&digest::SHA256, bytes).as_ref()[..16]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+impl ServerStatus {
+    pub fn new(id: usize, approval_mode: bool, started_at: u64) -> Self {
+        let registry = Registry::new_custom(
+            Some("decryption_server".to_string()),
+            Some(HashMap::from([("server_id".to_string(), id.to_string())]))
+        ).unwrap();
+        let requests_handled = IntCounter::new(
+            "requests_handled_total",
+            "Messages consumed from the broker."
+        ).unwrap();
+        let shares_produced = IntCounter::new(
+            "shares_produced_total",
+            "Decryption shares published."
+        ).unwrap();
+        let refusals = IntCounterVec::new(
+            Opts::new("refusals_total", "Decryption requests refused, by reason."),
+            &["reason"]
+        ).unwrap();
+        let signature_failures = IntCounter::new(
+            "signature_failures_total",
+            "Messages whose sender signature could not be verified."
+        ).unwrap();
+        let stale_messages = IntCounter::new(
+            "stale_messages_total",
+            "Decryption requests refused for being too old."
+        ).unwrap();
+        registry.register(Box::new(requests_handled.clone())).unwrap();
+        registry.register(Box::new(shares_produced.clone())).unwrap();
+        registry.register(Box::new(refusals.clone())).unwrap();
+        registry.register(Box::new(signature_failures.clone())).unwrap();
+        let refused_key_syncs = IntCounter::new(
+            "refused_key_syncs_total",
+            "Key syncs refused for holding shares not matching their public key set."
+        ).unwrap();
+        registry.register(Box::new(stale_messages.clone())).unwrap();
+        registry.register(Box::new(refused_key_syncs.clone())).unwrap();
+        Self {
+            id,
+            approval_mode,
+            started_at,
+            registry,
+            requests_handled,
+            shares_produced,
+            refusals,
+            signature_failures,
+            stale_messages,
+            refused_key_syncs,
+            served_keys: Mutex::new(Vec::new()),
+            connection: Mutex::new(None),
+        }
+    }
+
+    pub fn set_served_keys(&self, mut served_keys: Vec<ServedKey>) {
+        served_keys.sort_by(|a, b| a.key_id.cmp(&b.key_id));
+        *self.served_keys.lock().unwrap() = served_keys;
+    }
+
+    pub fn served_keys(&self) -> Vec<ServedKey> {
+        self.served_keys.lock().unwrap().clone()
+    }
+
+    pub fn set_connection(&self, connection: Connection) {
+        *self.connection.lock().unwrap() = Some(connection);
+    }
+
+    pub fn is_broker_connected(&self) -> bool {
+        self.connection
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|connection| connection.is_open())
+    }
+
+    /// Every metric in the Prometheus text exposition format.
+    pub fn render_metrics(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
@@ -0,0 +1,75 @@
+use std::{ net::SocketAddr, sync::Arc, time::{ SystemTime, UNIX_EPOCH } };
+use axum::{
+    extract::State,
+    http::{ header, StatusCode },
+    response::IntoResponse,
+    routing::get,
+    Json,
+    Router,
+};
+use serde::Serialize;
+use crate::server_status::{ ServedKey, ServerStatus };
+
+#[derive(Serialize)]
+struct Readiness {
+    ready: bool,
+    key_shares_loaded: bool,
+    broker_connected: bool,
+    /// Key shares served, for the service to tell shares of its current key sets from stale ones.
+    keys: Vec<ServedKey>,
+}
+
+#[derive(Serialize)]
+struct StatusPage {
+    server_id: usize,
+    version: &'static str,
+    approval_mode: bool,
+    uptime_secs: u64,
+    broker_connected: bool,
+    keys: Vec<ServedKey>,
+}
+
+async fn healthz() -> &'static str {
+    "OK"
+}
+
+/// Ready once the server holds key shares and is connected to the broker.
+async fn readyz(State(status): State<Arc<ServerStatus>>) -> (StatusCode, Json<Readiness>) {
+    let keys = status.served_keys();
+    let key_shares_loaded = !keys.is_empty();
+    let broker_connected = status.is_broker_connected();
+    let ready = key_shares_loaded && broker_connected;
+    let status_code = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status_code, Json(Readiness { ready, key_shares_loaded, broker_connected, keys }))
+}
+
+async fn metrics(State(status): State<Arc<ServerStatus>>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], status.render_metrics())
+}
+
+async fn status_page(State(status): State<Arc<ServerStatus>>) -> Json<StatusPage> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    Json(StatusPage {
+        server_id: status.id,
+        version: env!("CARGO_PKG_VERSION"),
+        approval_mode: status.approval_mode,
+        uptime_secs: now.saturating_sub(status.started_at),
+        broker_connected: status.is_broker_connected(),
+        keys: status.served_keys(),
+    })
+}
+
+/// Serves the health, readiness, metrics and status endpoints. Unlike the admin endpoint it
+/// exposes no operation, so it may listen on every interface for orchestrators and scrapers.
+pub async fn serve(address: SocketAddr, status: Arc<ServerStatus>) {
+    let id = status.id;
+    let router = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics))
+        .route("/status", get(status_page))
+        .with_state(status);
+    let listener = tokio::net::TcpListener::bind(address).await.unwrap();
+    tracing::info!(server_id = id, %address, "Status endpoint listening");
+    axum::serve(listener, router).await.unwrap();
+}
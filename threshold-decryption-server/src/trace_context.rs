@@ -0,0 +1,59 @@
+use amqprs::{ FieldTable, FieldValue };
+use opentelemetry::{
+    global,
+    propagation::Extractor,
+    trace::{ TraceError, TracerProvider as _ },
+    Context,
+    KeyValue,
+};
+use opentelemetry_otlp::{ SpanExporter, WithExportConfig };
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    runtime,
+    trace::{ Tracer, TracerProvider },
+    Resource,
+};
+
+/// Exports spans over OTLP/gRPC to the collector at `endpoint`, joining the traces of the service
+/// through the W3C trace context of the messages.
+pub fn otlp_tracer(endpoint: &str, service_name: &str) -> Result<Tracer, TraceError> {
+    let exporter = SpanExporter::builder().with_tonic().with_endpoint(endpoint).build()?;
+    let tracer_provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new(vec![KeyValue::new("service.name", service_name.to_string())]))
+        .build();
+    let tracer = tracer_provider.tracer(service_name.to_string());
+    global::set_tracer_provider(tracer_provider);
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    Ok(tracer)
+}
+
+struct AmqpHeaderExtractor<'a>(Option<&'a FieldTable>);
+
+impl Extractor for AmqpHeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        match self.0?.get(&key.try_into().ok()?)? {
+            FieldValue::S(value) => Some(value.as_ref()),
+            _ => None,
+        }
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0
+            .map(|headers| {
+                headers
+                    .as_ref()
+                    .keys()
+                    .map(|key| key.as_ref().as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Trace context sent by the service in the `traceparent` and `tracestate` headers.
+pub fn extract(headers: Option<&FieldTable>) -> Context {
+    global::get_text_map_propagator(|propagator| {
+        propagator.extract(&AmqpHeaderExtractor(headers))
+    })
+}
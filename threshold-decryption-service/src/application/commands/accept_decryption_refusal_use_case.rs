@@ -0,0 +1,123 @@
+use thiserror::Error;
+use crate::domain::{
+    entities::decryption_server::DecryptionRefusal,
+    services::{
+        cryptography_service::ServerRefusal,
+        server_registry_service::ServerRegistryService,
+    },
+};
+
+/// Refusal whose signature was verified against `refusal.identity_key`.
+pub struct AcceptDecryptionRefusalRequestModel {
+    pub refusal: DecryptionRefusal,
+    pub n_servers: usize,
+}
+
+pub struct AcceptDecryptionRefusalResponseModel {
+    pub request_id: Option<String>,
+    pub refusal: ServerRefusal,
+}
+
+#[derive(Error, Debug)]
+pub enum AcceptDecryptionRefusalError {
+    #[error("Unable to read from Server Registry Service. {0}")] ServerRegistryServiceError(
+        String,
+    ),
+    #[error("Decryption Server {0} is not part of the cluster.")] UnknownServer(usize),
+    #[error("Decryption Server {0} is neither enrolled nor registered yet.")] UnenrolledServer(
+        usize,
+    ),
+    #[error("Refusal of server {0} signed with an unknown key.")] UnknownIdentityKey(usize),
+}
+
+pub struct AcceptDecryptionRefusalUseCase<'a> {
+    server_registry_service: &'a dyn ServerRegistryService,
+}
+
+impl<'a> AcceptDecryptionRefusalUseCase<'a> {
+    pub fn new(server_registry_service: &'a dyn ServerRegistryService) -> Self {
+        Self {
+            server_registry_service,
+        }
+    }
+
+    /// A refusal fails its decryption early, so it is only accepted from servers proving their
+    /// enrolled or pinned identity.
+    pub async fn interact(
+        &self,
+        request_model: AcceptDecryptionRefusalRequestModel
+    ) -> Result<AcceptDecryptionRefusalResponseModel, AcceptDecryptionRefusalError> {
+        let refusal = request_model.refusal;
+        let id = refusal.id;
+        if id >= request_model.n_servers {
+            return Err(AcceptDecryptionRefusalError::UnknownServer(id));
+        }
+        let identity_key = self.server_registry_service
+            .identity_key(id).await
+            .map_err(|e| AcceptDecryptionRefusalError::ServerRegistryServiceError(e.to_string()))?
+            .ok_or(AcceptDecryptionRefusalError::UnenrolledServer(id))?;
+        if identity_key != refusal.identity_key {
+            return Err(AcceptDecryptionRefusalError::UnknownIdentityKey(id));
+        }
+        Ok(AcceptDecryptionRefusalResponseModel {
+            request_id: refusal.request_id,
+            refusal: ServerRefusal {
+                server_id: id,
+                reason: refusal.reason,
+                detail: refusal.detail,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::server_registry_service::MockServerRegistryService;
+
+    fn request_model(identity_key: Vec<u8>) -> AcceptDecryptionRefusalRequestModel {
+        AcceptDecryptionRefusalRequestModel {
+            refusal: DecryptionRefusal {
+                id: 1,
+                request_id: Some("5e1f".to_string()),
+                identity_key,
+                reason: "policy".to_string(),
+                detail: "refused by policy, unknown requester".to_string(),
+            },
+            n_servers: 3,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_accept_decryption_refusal_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_identity_key()
+            .withf(|id| *id == 1)
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(Some(vec![7; 32])) }) });
+
+        let use_case = AcceptDecryptionRefusalUseCase::new(&mock_server_registry_service);
+        let response_model = use_case.interact(request_model(vec![7; 32])).await.unwrap();
+        assert_eq!(response_model.request_id, Some("5e1f".to_string()));
+        assert_eq!(response_model.refusal.server_id, 1);
+        assert_eq!(response_model.refusal.reason, "policy");
+    }
+
+    #[tokio::test]
+    async fn should_reject_decryption_refusal_of_unenrolled_server_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_identity_key()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(None) }) });
+
+        let use_case = AcceptDecryptionRefusalUseCase::new(&mock_server_registry_service);
+        let response_model = use_case.interact(request_model(vec![7; 32])).await;
+        assert!(
+            matches!(response_model, Err(AcceptDecryptionRefusalError::UnenrolledServer(1)))
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use thiserror::Error;
+use crate::domain::{
+    entities::decryption_server::{ stale_key_ids, KeyFingerprint, KeySyncAcknowledgement },
+    services::server_registry_service::ServerRegistryService,
+};
+
+/// Acknowledgement whose signature was verified against `acknowledgement.identity_key`.
+pub struct AcknowledgeKeySyncRequestModel {
+    pub acknowledgement: KeySyncAcknowledgement,
+    /// Fingerprints of the key shares each server should hold, by server id.
+    pub expected_key_fingerprints: Vec<Vec<KeyFingerprint>>,
+}
+
+pub struct AcknowledgeKeySyncResponseModel {
+    pub id: usize,
+    pub sync_id: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum AcknowledgeKeySyncError {
+    #[error("Unable to read from Server Registry Service. {0}")] ServerRegistryServiceError(
+        String,
+    ),
+    #[error("Decryption Server {0} is not part of the cluster.")] UnknownServer(usize),
+    #[error("Acknowledgement of server {0} signed with an unknown key.")] UnknownIdentityKey(
+        usize,
+    ),
+    #[error("Decryption Server {0} loaded outdated shares of keys {1:?}.")] StaleKeyShares(
+        usize,
+        Vec<String>,
+    ),
+    #[error("Decryption Server {0} refused mismatched shares of keys {1:?}.")] MismatchedKeyShares(
+        usize,
+        Vec<String>,
+    ),
+}
+
+pub struct AcknowledgeKeySyncUseCase<'a> {
+    server_registry_service: &'a dyn ServerRegistryService,
+}
+
+impl<'a> AcknowledgeKeySyncUseCase<'a> {
+    pub fn new(server_registry_service: &'a dyn ServerRegistryService) -> Self {
+        Self {
+            server_registry_service,
+        }
+    }
+
+    /// Accepts the acknowledgement when it is signed with the identity key of the server, if one
+    /// is known, and the server loaded the current share of every key set of the service.
+    pub async fn interact(
+        &self,
+        request_model: AcknowledgeKeySyncRequestModel
+    ) -> Result<AcknowledgeKeySyncResponseModel, AcknowledgeKeySyncError> {
+        let acknowledgement = request_model.acknowledgement;
+        let Some(expected_key_fingerprints) = request_model.expected_key_fingerprints.get(
+            acknowledgement.id
+        ) else {
+            return Err(AcknowledgeKeySyncError::UnknownServer(acknowledgement.id));
+        };
+        let identity_key = self.server_registry_service
+            .identity_key(acknowledgement.id).await
+            .map_err(|e| AcknowledgeKeySyncError::ServerRegistryServiceError(e.to_string()))?;
+        if identity_key.is_some_and(|identity_key| identity_key != acknowledgement.identity_key) {
+            return Err(AcknowledgeKeySyncError::UnknownIdentityKey(acknowledgement.id));
+        }
+        if !acknowledgement.mismatched_key_ids.is_empty() {
+            return Err(
+                AcknowledgeKeySyncError::MismatchedKeyShares(
+                    acknowledgement.id,
+                    acknowledgement.mismatched_key_ids
+                )
+            );
+        }
+        let stale_key_ids = stale_key_ids(
+            expected_key_fingerprints,
+            &acknowledgement.key_fingerprints
+        );
+        if !stale_key_ids.is_empty() {
+            return Err(AcknowledgeKeySyncError::StaleKeyShares(acknowledgement.id, stale_key_ids));
+        }
+        Ok(AcknowledgeKeySyncResponseModel {
+            id: acknowledgement.id,
+            sync_id: acknowledgement.sync_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::server_registry_service::MockServerRegistryService;
+
+    fn fingerprint(key_id: &str, fingerprint: &str) -> KeyFingerprint {
+        KeyFingerprint {
+            key_id: key_id.to_string(),
+            fingerprint: fingerprint.to_string(),
+            public_key_fingerprint: "9f3a".to_string(),
+        }
+    }
+
+    fn request_model(key_fingerprints: Vec<KeyFingerprint>) -> AcknowledgeKeySyncRequestModel {
+        AcknowledgeKeySyncRequestModel {
+            acknowledgement: KeySyncAcknowledgement {
+                id: 1,
+                sync_id: Some("5e1f".to_string()),
+                identity_key: vec![7; 32],
+                key_fingerprints,
+                mismatched_key_ids: Vec::new(),
+                sent_at: 1_700_000_000,
+            },
+            expected_key_fingerprints: vec![
+                vec![fingerprint("0a1b", "0000")],
+                vec![fingerprint("0a1b", "1111")],
+                vec![fingerprint("0a1b", "2222")]
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn should_acknowledge_key_sync_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_identity_key()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(Some(vec![7; 32])) }) });
+
+        let use_case = AcknowledgeKeySyncUseCase::new(&mock_server_registry_service);
+        let response_model = use_case
+            .interact(request_model(vec![fingerprint("0a1b", "1111")])).await
+            .unwrap();
+        assert_eq!(response_model.id, 1);
+        assert_eq!(response_model.sync_id, Some("5e1f".to_string()));
+    }
+
+    #[tokio::test]
+    async fn should_reject_stale_key_sync_acknowledgement_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_identity_key()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(None) }) });
+
+        let use_case = AcknowledgeKeySyncUseCase::new(&mock_server_registry_service);
+        let response_model = use_case.interact(
+            request_model(vec![fingerprint("0a1b", "2222")])
+        ).await;
+        assert!(
+            matches!(
+                response_model,
+                Err(AcknowledgeKeySyncError::StaleKeyShares(1, key_ids)) if key_ids == ["0a1b"]
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_mismatched_key_shares_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_identity_key()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(Some(vec![7; 32])) }) });
+
+        let mut request_model = request_model(Vec::new());
+        request_model.acknowledgement.mismatched_key_ids = vec!["0a1b".to_string()];
+        let use_case = AcknowledgeKeySyncUseCase::new(&mock_server_registry_service);
+        let response_model = use_case.interact(request_model).await;
+        assert!(
+            matches!(
+                response_model,
+                Err(AcknowledgeKeySyncError::MismatchedKeyShares(1, key_ids)) if key_ids == ["0a1b"]
+            )
+        );
+    }
+}
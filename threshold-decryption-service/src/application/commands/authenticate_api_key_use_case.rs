@@ -0,0 +1,168 @@
+use std::{ collections::BTreeMap, time::{ SystemTime, UNIX_EPOCH } };
+use serde_json::Value;
+use thiserror::Error;
+use crate::domain::{
+    entities::{ api_key::hash_api_key_secret, caller::Caller, scope::Grant },
+    services::api_key_service::ApiKeyService,
+};
+
+/// `last_used_at` is only persisted when it is older than this, to avoid a write per request.
+const LAST_USED_RESOLUTION_IN_SECS: u64 = 60;
+
+pub struct AuthenticateApiKeyRequestModel {
+    pub secret: String,
+}
+
+pub struct AuthenticateApiKeyResponseModel {
+    pub caller: Caller,
+}
+
+#[derive(Error, Debug)]
+pub enum AuthenticateApiKeyError {
+    #[error("Unable to look up API key in API Key Service. {0}")] ApiKeyServiceError(String),
+    #[error("Invalid API key.")] InvalidApiKey,
+    #[error("API key has expired.")] ExpiredApiKey,
+    #[error("API key has been revoked.")] RevokedApiKey,
+}
+
+pub struct AuthenticateApiKeyUseCase<'a> {
+    api_key_service: &'a dyn ApiKeyService,
+}
+
+impl<'a> AuthenticateApiKeyUseCase<'a> {
+    pub fn new(api_key_service: &'a dyn ApiKeyService) -> Self {
+        Self {
+            api_key_service,
+        }
+    }
+
+    pub async fn interact(
+        &self,
+        request_model: AuthenticateApiKeyRequestModel
+    ) -> Result<AuthenticateApiKeyResponseModel, AuthenticateApiKeyError> {
+        let mut api_key = self.api_key_service
+            .find_by_hash(hash_api_key_secret(&request_model.secret)).await
+            .map_err(|e| AuthenticateApiKeyError::ApiKeyServiceError(e.to_string()))?
+            .ok_or(AuthenticateApiKeyError::InvalidApiKey)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        if api_key.is_revoked() {
+            return Err(AuthenticateApiKeyError::RevokedApiKey);
+        }
+        if api_key.is_expired(now) {
+            return Err(AuthenticateApiKeyError::ExpiredApiKey);
+        }
+        let is_last_use_stale = api_key.last_used_at.is_none_or(|last_used_at| {
+            now >= last_used_at + LAST_USED_RESOLUTION_IN_SECS
+        });
+        if is_last_use_stale {
+            api_key.last_used_at = Some(now);
+            self.api_key_service
+                .save(api_key.clone()).await
+                .map_err(|e| AuthenticateApiKeyError::ApiKeyServiceError(e.to_string()))?;
+        }
+        let mut claims = BTreeMap::new();
+        claims.insert("sub".to_string(), Value::from(api_key.owner.clone()));
+        claims.insert("api_key_id".to_string(), Value::from(api_key.id.clone()));
+        claims.insert("api_key_name".to_string(), Value::from(api_key.name.clone()));
+        if let Some(expires_at) = api_key.expires_at {
+            claims.insert("exp".to_string(), Value::from(expires_at));
+        }
+        Ok(AuthenticateApiKeyResponseModel {
+            caller: Caller {
+                subject: api_key.owner,
+                issuer: None,
+                audience: Vec::new(),
+                expires_at: api_key.expires_at,
+                tenant: api_key.tenant,
+                grants: api_key.scopes
+                    .iter()
+                    .filter_map(|scope| scope.parse::<Grant>().ok())
+                    .collect(),
+                claims,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        entities::{ api_key::ApiKey, scope::Scope },
+        services::api_key_service::MockApiKeyService,
+    };
+
+    fn stored_api_key(key_hash: String, expires_at: Option<u64>) -> ApiKey {
+        ApiKey {
+            id: "0a1b".to_string(),
+            name: "ci".to_string(),
+            owner: "billing-team".to_string(),
+            key_hash,
+            created_at: 1,
+            expires_at,
+            last_used_at: None,
+            revoked_at: None,
+            scopes: vec!["decrypt:8c1f0b2d4e6a7c90".to_string()],
+            tenant: Some("billing".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_authenticate_api_key_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_find_by_hash()
+            .withf(|key_hash| *key_hash == hash_api_key_secret("tds_secret"))
+            .times(1)
+            .returning(|key_hash| {
+                Box::pin(async move { Ok(Some(stored_api_key(key_hash, None))) })
+            });
+        mock_api_key_service
+            .expect_save()
+            .withf(|api_key| api_key.last_used_at.is_some())
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
+
+        let use_case = AuthenticateApiKeyUseCase::new(&mock_api_key_service);
+        let request_model = AuthenticateApiKeyRequestModel { secret: "tds_secret".to_string() };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert_eq!(response_model.caller.subject, "billing-team");
+        assert_eq!(response_model.caller.tenant(), "billing");
+        assert_eq!(response_model.caller.claim("api_key_id"), Some(&Value::from("0a1b")));
+        assert!(response_model.caller.is_allowed(Scope::Decrypt, Some("8c1f0b2d4e6a7c90")));
+        assert!(!response_model.caller.is_allowed(Scope::Encrypt, Some("8c1f0b2d4e6a7c90")));
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_authenticate_expired_api_key_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_find_by_hash()
+            .times(1)
+            .returning(|key_hash| {
+                Box::pin(async move { Ok(Some(stored_api_key(key_hash, Some(1)))) })
+            });
+
+        let use_case = AuthenticateApiKeyUseCase::new(&mock_api_key_service);
+        let request_model = AuthenticateApiKeyRequestModel { secret: "tds_secret".to_string() };
+        let response_model = use_case.interact(request_model).await;
+        assert!(matches!(response_model, Err(AuthenticateApiKeyError::ExpiredApiKey)));
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_authenticate_unknown_api_key_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_find_by_hash()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(None) }) });
+
+        let use_case = AuthenticateApiKeyUseCase::new(&mock_api_key_service);
+        let request_model = AuthenticateApiKeyRequestModel { secret: "tds_secret".to_string() };
+        let response_model = use_case.interact(request_model).await;
+        assert!(matches!(response_model, Err(AuthenticateApiKeyError::InvalidApiKey)));
+    }
+}
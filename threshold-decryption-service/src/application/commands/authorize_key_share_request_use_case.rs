@@ -0,0 +1,148 @@
+use thiserror::Error;
+use crate::domain::{
+    entities::decryption_server::KeyShareRequest,
+    services::server_registry_service::ServerRegistryService,
+};
+
+/// Request whose signature was verified against `key_share_request.identity_key`.
+pub struct AuthorizeKeyShareRequestRequestModel {
+    pub key_share_request: KeyShareRequest,
+    pub n_servers: usize,
+    pub now: u64,
+    /// Age after which a request may be a replay and is refused.
+    pub max_age_secs: u64,
+}
+
+pub struct AuthorizeKeyShareRequestResponseModel {
+    pub id: usize,
+}
+
+#[derive(Error, Debug)]
+pub enum AuthorizeKeyShareRequestError {
+    #[error("Unable to read from Server Registry Service. {0}")] ServerRegistryServiceError(
+        String,
+    ),
+    #[error("Decryption Server {0} is not part of the cluster.")] UnknownServer(usize),
+    #[error("Decryption Server {0} has no enrolled identity key.")] UnenrolledServer(usize),
+    #[error("Key share request of server {0} signed with an unknown key.")] UnknownIdentityKey(
+        usize,
+    ),
+    #[error("Key share request of server {0} expired.")] ExpiredRequest(usize),
+}
+
+pub struct AuthorizeKeyShareRequestUseCase<'a> {
+    server_registry_service: &'a dyn ServerRegistryService,
+}
+
+impl<'a> AuthorizeKeyShareRequestUseCase<'a> {
+    pub fn new(server_registry_service: &'a dyn ServerRegistryService) -> Self {
+        Self {
+            server_registry_service,
+        }
+    }
+
+    /// Shares are only sent again to servers proving the identity they were enrolled with, so
+    /// that nobody can have the service resend them at will. A key pinned from the first
+    /// heartbeat of a server is not enough, as anyone may have sent that heartbeat.
+    pub async fn interact(
+        &self,
+        request_model: AuthorizeKeyShareRequestRequestModel
+    ) -> Result<AuthorizeKeyShareRequestResponseModel, AuthorizeKeyShareRequestError> {
+        let key_share_request = request_model.key_share_request;
+        let id = key_share_request.id;
+        if id >= request_model.n_servers {
+            return Err(AuthorizeKeyShareRequestError::UnknownServer(id));
+        }
+        if key_share_request.sent_at + request_model.max_age_secs < request_model.now {
+            return Err(AuthorizeKeyShareRequestError::ExpiredRequest(id));
+        }
+        let identity_key = self.server_registry_service
+            .enrolled_identity_key(id).await
+            .map_err(|e| AuthorizeKeyShareRequestError::ServerRegistryServiceError(e.to_string()))?
+            .ok_or(AuthorizeKeyShareRequestError::UnenrolledServer(id))?;
+        if identity_key != key_share_request.identity_key {
+            return Err(AuthorizeKeyShareRequestError::UnknownIdentityKey(id));
+        }
+        Ok(AuthorizeKeyShareRequestResponseModel { id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::server_registry_service::MockServerRegistryService;
+
+    fn request_model(identity_key: Vec<u8>, sent_at: u64) -> AuthorizeKeyShareRequestRequestModel {
+        AuthorizeKeyShareRequestRequestModel {
+            key_share_request: KeyShareRequest {
+                id: 2,
+                identity_key,
+                sent_at,
+            },
+            n_servers: 3,
+            now: 1_700_000_010,
+            max_age_secs: 15,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_authorize_key_share_request_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_enrolled_identity_key()
+            .withf(|id| *id == 2)
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(Some(vec![7; 32])) }) });
+
+        let use_case = AuthorizeKeyShareRequestUseCase::new(&mock_server_registry_service);
+        let response_model = use_case
+            .interact(request_model(vec![7; 32], 1_700_000_005)).await
+            .unwrap();
+        assert_eq!(response_model.id, 2);
+    }
+
+    #[tokio::test]
+    async fn should_refuse_unauthenticated_key_share_request_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_enrolled_identity_key()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(Some(vec![7; 32])) }) });
+
+        let use_case = AuthorizeKeyShareRequestUseCase::new(&mock_server_registry_service);
+        let response_model = use_case.interact(request_model(vec![8; 32], 1_700_000_005)).await;
+        assert!(
+            matches!(response_model, Err(AuthorizeKeyShareRequestError::UnknownIdentityKey(2)))
+        );
+    }
+
+    #[tokio::test]
+    async fn should_refuse_expired_key_share_request_use_case() {
+        let mock_server_registry_service = MockServerRegistryService::new();
+
+        let use_case = AuthorizeKeyShareRequestUseCase::new(&mock_server_registry_service);
+        let response_model = use_case.interact(request_model(vec![7; 32], 1_699_999_990)).await;
+        assert!(
+            matches!(response_model, Err(AuthorizeKeyShareRequestError::ExpiredRequest(2)))
+        );
+    }
+
+    #[tokio::test]
+    async fn should_refuse_key_share_request_of_unenrolled_server_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_enrolled_identity_key()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(None) }) });
+        mock_server_registry_service.expect_identity_key().never();
+
+        let use_case = AuthorizeKeyShareRequestUseCase::new(&mock_server_registry_service);
+        let response_model = use_case.interact(request_model(vec![7; 32], 1_700_000_005)).await;
+        assert!(
+            matches!(response_model, Err(AuthorizeKeyShareRequestError::UnenrolledServer(2)))
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+use thiserror::Error;
+use crate::domain::{
+    entities::api_key::{
+        generate_api_key_id,
+        generate_api_key_secret,
+        hash_api_key_secret,
+        ApiKey,
+    },
+    entities::scope::Grant,
+    services::api_key_service::ApiKeyService,
+};
+
+pub struct CreateApiKeyRequestModel {
+    pub name: String,
+    pub owner: String,
+    pub expires_in_secs: Option<u64>,
+    pub scopes: Vec<String>,
+    pub tenant: Option<String>,
+}
+
+pub struct CreateApiKeyResponseModel {
+    pub api_key: ApiKey,
+    /// The only time the secret is available, it is not stored.
+    pub secret: String,
+}
+
+#[derive(Error, Debug)]
+pub enum CreateApiKeyError {
+    #[error("Unable to store API key in API Key Service. {0}")] ApiKeyServiceError(String),
+    #[error("Invalid API key request. {0}")] InvalidRequest(String),
+}
+
+pub struct CreateApiKeyUseCase<'a> {
+    api_key_service: &'a dyn ApiKeyService,
+}
+
+impl<'a> CreateApiKeyUseCase<'a> {
+    pub fn new(api_key_service: &'a dyn ApiKeyService) -> Self {
+        Self {
+            api_key_service,
+        }
+    }
+
+    pub async fn interact(
+        &self,
+        request_model: CreateApiKeyRequestModel
+    ) -> Result<CreateApiKeyResponseModel, CreateApiKeyError> {
+        if request_model.name.trim().is_empty() || request_model.owner.trim().is_empty() {
+            return Err(
+                CreateApiKeyError::InvalidRequest("Name and owner must not be empty.".to_string())
+            );
+        }
+        for scope in &request_model.scopes {
+            scope
+                .parse::<Grant>()
+                .map_err(|e| CreateApiKeyError::InvalidRequest(e.to_string()))?;
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let secret = generate_api_key_secret();
+        let api_key = ApiKey {
+            id: generate_api_key_id(),
+            name: request_model.name,
+            owner: request_model.owner,
+            key_hash: hash_api_key_secret(&secret),
+            created_at: now,
+            expires_at: request_model.expires_in_secs.map(|expires_in_secs| now + expires_in_secs),
+            last_used_at: None,
+            revoked_at: None,
+            scopes: request_model.scopes,
+            tenant: request_model.tenant,
+        };
+        self.api_key_service
+            .save(api_key.clone()).await
+            .map_err(|e| CreateApiKeyError::ApiKeyServiceError(e.to_string()))?;
+        Ok(CreateApiKeyResponseModel { api_key, secret })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::api_key_service::{ ApiKeyServiceError, MockApiKeyService };
+
+    #[tokio::test]
+    async fn should_create_api_key_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_save()
+            .withf(|api_key| api_key.name == "ci" && api_key.expires_at.is_some())
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
+
+        let use_case = CreateApiKeyUseCase::new(&mock_api_key_service);
+        let request_model = CreateApiKeyRequestModel {
+            name: "ci".to_string(),
+            owner: "billing-team".to_string(),
+            expires_in_secs: Some(3600),
+            scopes: vec!["encrypt:8c1f0b2d4e6a7c90".to_string()],
+            tenant: None,
+        };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert_eq!(response_model.api_key.key_hash, hash_api_key_secret(&response_model.secret));
+        assert_ne!(response_model.api_key.key_hash, response_model.secret);
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_create_api_key_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_save()
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move { Err(ApiKeyServiceError::StorageError("Error".to_string())) })
+            });
+
+        let use_case = CreateApiKeyUseCase::new(&mock_api_key_service);
+        let request_model = CreateApiKeyRequestModel {
+            name: "ci".to_string(),
+            owner: "billing-team".to_string(),
+            expires_in_secs: None,
+            scopes: vec!["encrypt".to_string()],
+            tenant: None,
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(response_model.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_create_api_key_with_unknown_scope_use_case() {
+        let mock_api_key_service = MockApiKeyService::new();
+
+        let use_case = CreateApiKeyUseCase::new(&mock_api_key_service);
+        let request_model = CreateApiKeyRequestModel {
+            name: "ci".to_string(),
+            owner: "billing-team".to_string(),
+            expires_in_secs: None,
+            scopes: vec!["delete".to_string()],
+            tenant: None,
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(matches!(response_model, Err(CreateApiKeyError::InvalidRequest(_))));
+    }
+}
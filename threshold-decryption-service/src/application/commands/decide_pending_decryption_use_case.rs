@@ -0,0 +1,274 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+use thiserror::Error;
+use crate::{
+    application::commands::decrypt_message_use_case::{
+        DecryptMessageUseCase,
+        DecryptMessageRequestModel,
+    },
+    domain::{
+        entities::pending_decryption::{
+            ApprovalDecision,
+            PendingDecryption,
+            PendingDecryptionStatus,
+        },
+        services::{
+            audit_trail_service::AuditTrailService,
+            cryptography_service::CryptographyService,
+            pending_decryption_service::{
+                PendingDecryptionService,
+                PendingDecryptionServiceError,
+            },
+        },
+    },
+};
+
+pub struct DecidePendingDecryptionRequestModel {
+    pub id: String,
+    pub decision: ApprovalDecision,
+    /// Tenant of the approver, which must be the tenant of the pending decryption.
+    pub tenant: String,
+    /// Id of the approving request, which dispatches the decryption once approved.
+    pub request_id: Option<String>,
+}
+
+pub struct DecidePendingDecryptionResponseModel {
+    pub pending_decryption: PendingDecryption,
+}
+
+#[derive(Error, Debug)]
+pub enum DecidePendingDecryptionError {
+    #[error("Unable to access pending decryptions. {0}")] PendingDecryptionServiceError(String),
+    #[error("Pending decryption `{0}` not found.")] PendingDecryptionNotFound(String),
+    #[error("Decision rejected. {0}")] DecisionRejected(String),
+}
+
+impl From<PendingDecryptionServiceError> for DecidePendingDecryptionError {
+    fn from(error: PendingDecryptionServiceError) -> Self {
+        match error {
+            PendingDecryptionServiceError::NotFound(id) => {
+                DecidePendingDecryptionError::PendingDecryptionNotFound(id)
+            }
+            PendingDecryptionServiceError::RejectedDecision(reason) => {
+                DecidePendingDecryptionError::DecisionRejected(reason)
+            }
+            e => DecidePendingDecryptionError::PendingDecryptionServiceError(e.to_string()),
+        }
+    }
+}
+
+pub struct DecidePendingDecryptionUseCase<'a> {
+    pending_decryption_service: &'a dyn PendingDecryptionService,
+    cryptography_service: &'a dyn CryptographyService,
+    audit_trail_service: &'a dyn AuditTrailService,
+}
+
+impl<'a> DecidePendingDecryptionUseCase<'a> {
+    pub fn new(
+        pending_decryption_service: &'a dyn PendingDecryptionService,
+        cryptography_service: &'a dyn CryptographyService,
+        audit_trail_service: &'a dyn AuditTrailService
+    ) -> Self {
+        Self {
+            pending_decryption_service,
+            cryptography_service,
+            audit_trail_service,
+        }
+    }
+
+    /// Records the decision and, when it is the last approval required, dispatches the decryption
+    /// to the Decryption Servers and keeps its outcome for the requester.
+    #[tracing::instrument(name = "decide_pending_decryption_use_case", skip_all)]
+    pub async fn interact(
+        &self,
+        request_model: DecidePendingDecryptionRequestModel
+    ) -> Result<DecidePendingDecryptionResponseModel, DecidePendingDecryptionError> {
+        let belongs_to_tenant = self.pending_decryption_service
+            .find(request_model.id.clone()).await?
+            .is_some_and(|pending_decryption| pending_decryption.tenant == request_model.tenant);
+        if !belongs_to_tenant {
+            return Err(DecidePendingDecryptionError::PendingDecryptionNotFound(request_model.id));
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut pending_decryption = self.pending_decryption_service.decide(
+            request_model.id,
+            request_model.decision,
+            now
+        ).await?;
+        if pending_decryption.status != PendingDecryptionStatus::Approved {
+            return Ok(DecidePendingDecryptionResponseModel { pending_decryption });
+        }
+
+        let decryption = DecryptMessageUseCase::new(
+            self.cryptography_service,
+            self.audit_trail_service
+        ).interact(DecryptMessageRequestModel {
+            message: pending_decryption.message.clone(),
+            associated_data: pending_decryption.associated_data.clone(),
+            requester: Some(pending_decryption.requester.clone()),
+            reason: pending_decryption.reason.clone(),
+            tenant: pending_decryption.tenant.clone(),
+            request_id: request_model.request_id,
+        }).await;
+        match decryption {
+            Ok(response_model) => {
+                pending_decryption.status = PendingDecryptionStatus::Completed;
+                pending_decryption.decrypted_message = Some(response_model.decrypted_message);
+            }
+            Err(e) => {
+                pending_decryption.status = PendingDecryptionStatus::Failed;
+                pending_decryption.error = Some(e.to_string());
+            }
+        }
+        self.pending_decryption_service.save(pending_decryption.clone()).await?;
+        Ok(DecidePendingDecryptionResponseModel { pending_decryption })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::{
+        audit_trail_service::MockAuditTrailService,
+        cryptography_service::{ DecryptedMessage, MockCryptographyService },
+        pending_decryption_service::MockPendingDecryptionService,
+    };
+
+    fn pending_decryption(status: PendingDecryptionStatus) -> PendingDecryption {
+        PendingDecryption {
+            id: "0a1b".to_string(),
+            tenant: "billing".to_string(),
+            requester: "billing-service".to_string(),
+            message: b"Hello, World!".to_vec(),
+            associated_data: None,
+            reason: None,
+            required_approvals: 2,
+            approvers: vec!["alice".to_string(), "bob".to_string()],
+            denied_by: None,
+            created_at: 1000,
+            expires_at: u64::MAX,
+            status,
+            decrypted_message: None,
+            error: None,
+        }
+    }
+
+    fn finding_pending_decryption_service() -> MockPendingDecryptionService {
+        let mut mock_pending_decryption_service = MockPendingDecryptionService::new();
+        mock_pending_decryption_service
+            .expect_find()
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move {
+                    Ok(Some(pending_decryption(PendingDecryptionStatus::Pending)))
+                })
+            });
+        mock_pending_decryption_service
+    }
+
+    #[tokio::test]
+    async fn should_dispatch_approved_decryption_decide_pending_decryption_use_case() {
+        let mut mock_pending_decryption_service = finding_pending_decryption_service();
+        let mut mock_cryptography_service = MockCryptographyService::new();
+        let mut mock_audit_trail_service = MockAuditTrailService::new();
+
+        mock_pending_decryption_service
+            .expect_decide()
+            .withf(|_, decision, _| *decision == ApprovalDecision::Approve("bob".to_string()))
+            .times(1)
+            .returning(|_, _, _| {
+                Box::pin(async move { Ok(pending_decryption(PendingDecryptionStatus::Approved)) })
+            });
+        mock_cryptography_service
+            .expect_decrypt_message()
+            .withf(|_, context| {
+                context.requester == Some("billing-service".to_string()) &&
+                    context.request_id == Some("7f3c".to_string())
+            })
+            .times(1)
+            .returning(|message, _| {
+                Box::pin(async move {
+                    Ok(DecryptedMessage { plaintext: message, participants: vec![1, 2] })
+                })
+            });
+        mock_audit_trail_service
+            .expect_record()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
+        mock_pending_decryption_service
+            .expect_save()
+            .withf(|pending_decryption| {
+                pending_decryption.status == PendingDecryptionStatus::Completed &&
+                    pending_decryption.decrypted_message == Some("Hello, World!".to_string())
+            })
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
+
+        let use_case = DecidePendingDecryptionUseCase::new(
+            &mock_pending_decryption_service,
+            &mock_cryptography_service,
+            &mock_audit_trail_service
+        );
+        let request_model = DecidePendingDecryptionRequestModel {
+            id: "0a1b".to_string(),
+            decision: ApprovalDecision::Approve("bob".to_string()),
+            tenant: "billing".to_string(),
+            request_id: Some("7f3c".to_string()),
+        };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert_eq!(response_model.pending_decryption.status, PendingDecryptionStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn should_record_denial_decide_pending_decryption_use_case() {
+        let mut mock_pending_decryption_service = finding_pending_decryption_service();
+        let mock_cryptography_service = MockCryptographyService::new();
+        let mock_audit_trail_service = MockAuditTrailService::new();
+
+        mock_pending_decryption_service
+            .expect_decide()
+            .times(1)
+            .returning(|_, _, _| {
+                Box::pin(async move { Ok(pending_decryption(PendingDecryptionStatus::Denied)) })
+            });
+
+        let use_case = DecidePendingDecryptionUseCase::new(
+            &mock_pending_decryption_service,
+            &mock_cryptography_service,
+            &mock_audit_trail_service
+        );
+        let request_model = DecidePendingDecryptionRequestModel {
+            id: "0a1b".to_string(),
+            decision: ApprovalDecision::Deny("bob".to_string()),
+            tenant: "billing".to_string(),
+            request_id: None,
+        };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert_eq!(response_model.pending_decryption.status, PendingDecryptionStatus::Denied);
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_decide_pending_decryption_of_another_tenant_use_case() {
+        let mock_pending_decryption_service = finding_pending_decryption_service();
+        let mock_cryptography_service = MockCryptographyService::new();
+        let mock_audit_trail_service = MockAuditTrailService::new();
+
+        let use_case = DecidePendingDecryptionUseCase::new(
+            &mock_pending_decryption_service,
+            &mock_cryptography_service,
+            &mock_audit_trail_service
+        );
+        let request_model = DecidePendingDecryptionRequestModel {
+            id: "0a1b".to_string(),
+            decision: ApprovalDecision::Approve("bob".to_string()),
+            tenant: "payroll".to_string(),
+            request_id: None,
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(
+            matches!(
+                response_model,
+                Err(DecidePendingDecryptionError::PendingDecryptionNotFound(_))
+            )
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use thiserror::Error;
+use crate::domain::{
+    entities::data_key::{ encryption_context_associated_data, DataKey, EncryptionContext },
+    services::cryptography_service::{
+        CryptographyService,
+        CryptographyServiceError,
+        DecryptionContext,
+    },
+};
+
+pub struct DecryptDataKeyRequestModel {
+    pub encrypted_data_key: Vec<u8>,
+    pub encryption_context: EncryptionContext,
+    pub requester: Option<String>,
+    pub tenant: String,
+    pub request_id: Option<String>,
+}
+
+pub struct DecryptDataKeyResponseModel {
+    pub plaintext_data_key: Vec<u8>,
+}
+
+#[derive(Error, Debug)]
+pub enum DecryptDataKeyError {
+    #[error("Unable to decrypt data key from Cryptography Service. {0}")] CryptographyServiceError(
+        String,
+    ),
+    #[error("Invalid or broken data key encryption. {0}")] BrokenEncryptionError(String),
+    #[error(
+        "The provided encryption context does not match the data key."
+    )] EncryptionContextMismatch,
+}
+
+pub struct DecryptDataKeyUseCase<'a> {
+    cryptography_service: &'a dyn CryptographyService,
+}
+
+impl<'a> DecryptDataKeyUseCase<'a> {
+    pub fn new(cryptography_service: &'a dyn CryptographyService) -> Self {
+        Self {
+            cryptography_service,
+        }
+    }
+
+    /// The encryption context is bound to the data key as associated data, so another context is
+    /// refused before any Decryption Server produces a share.
+    #[tracing::instrument(name = "decrypt_data_key_use_case", skip_all)]
+    pub async fn interact(
+        &self,
+        request_model: DecryptDataKeyRequestModel
+    ) -> Result<DecryptDataKeyResponseModel, DecryptDataKeyError> {
+        let decrypted_data_key = self.cryptography_service
+            .decrypt_message(request_model.encrypted_data_key, DecryptionContext {
+                tenant: request_model.tenant,
+                associated_data: encryption_context_associated_data(
+                    &request_model.encryption_context
+                ),
+                requester: request_model.requester,
+                reason: None,
+                request_id: request_model.request_id,
+            }).await
+            .map_err(|e| {
+                match e {
+                    CryptographyServiceError::AssociatedDataMismatch =>
+                        DecryptDataKeyError::EncryptionContextMismatch,
+                    e => DecryptDataKeyError::CryptographyServiceError(e.to_string()),
+                }
+            })?;
+        let data_key = DataKey::from_bytes(&decrypted_data_key.plaintext).map_err(|e|
+            DecryptDataKeyError::BrokenEncryptionError(e.to_string())
+        )?;
+        if data_key.encryption_context != request_model.encryption_context {
+            return Err(DecryptDataKeyError::EncryptionContextMismatch);
+        }
+        Ok(DecryptDataKeyResponseModel {
+            plaintext_data_key: data_key.key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::cryptography_service::{
+        DecryptedMessage,
+        MockCryptographyService,
+    };
+
+    fn serialized_data_key(encryption_context: EncryptionContext) -> Vec<u8> {
+        (DataKey {
+            key: vec![7; 32],
+            encryption_context,
+        })
+            .to_bytes()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_decrypt_data_key_use_case() {
+        let mut mock_cryptography_service = MockCryptographyService::new();
+
+        mock_cryptography_service
+            .expect_decrypt_message()
+            .times(1)
+            .returning(|message, _| {
+                Box::pin(async move {
+                    Ok(DecryptedMessage {
+                        plaintext: message,
+                        participants: vec![1, 2],
+                    })
+                })
+            });
+
+        let use_case = DecryptDataKeyUseCase::new(&mock_cryptography_service);
+        let request_model = DecryptDataKeyRequestModel {
+            encrypted_data_key: serialized_data_key(EncryptionContext::new()),
+            encryption_context: EncryptionContext::new(),
+            requester: None,
+            tenant: String::from("billing"),
+            request_id: None,
+        };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert_eq!(response_model.plaintext_data_key, vec![7; 32]);
+    }
+
+    #[tokio::test]
+    async fn should_bind_encryption_context_as_associated_data_use_case() {
+        let mut mock_cryptography_service = MockCryptographyService::new();
+
+        mock_cryptography_service
+            .expect_decrypt_message()
+            .withf(|_, context| {
+                context.associated_data.as_deref() ==
+                    Some(b"encryption-context:{\"purpose\":\"billing\",\"tenant\":\"acme\"}")
+            })
+            .times(1)
+            .returning(|_, _| {
+                Box::pin(async move { Err(CryptographyServiceError::AssociatedDataMismatch) })
+            });
+
+        let use_case = DecryptDataKeyUseCase::new(&mock_cryptography_service);
+        let mut encryption_context = EncryptionContext::new();
+        encryption_context.insert("tenant".to_string(), "acme".to_string());
+        encryption_context.insert("purpose".to_string(), "billing".to_string());
+        let request_model = DecryptDataKeyRequestModel {
+            encrypted_data_key: vec![1, 2, 3],
+            encryption_context,
+            requester: None,
+            tenant: String::from("billing"),
+            request_id: None,
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(matches!(response_model, Err(DecryptDataKeyError::EncryptionContextMismatch)));
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_decrypt_data_key_with_another_context_use_case() {
+        let mut mock_cryptography_service = MockCryptographyService::new();
+
+        mock_cryptography_service
+            .expect_decrypt_message()
+            .times(1)
+            .returning(|message, _| {
+                Box::pin(async move {
+                    Ok(DecryptedMessage {
+                        plaintext: message,
+                        participants: vec![1, 2],
+                    })
+                })
+            });
+
+        let use_case = DecryptDataKeyUseCase::new(&mock_cryptography_service);
+        let mut encryption_context = EncryptionContext::new();
+        encryption_context.insert("tenant".to_string(), "acme".to_string());
+        let request_model = DecryptDataKeyRequestModel {
+            encrypted_data_key: serialized_data_key(encryption_context),
+            encryption_context: EncryptionContext::new(),
+            requester: None,
+            tenant: String::from("billing"),
+            request_id: None,
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(matches!(response_model, Err(DecryptDataKeyError::EncryptionContextMismatch)));
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_decrypt_data_key_use_case() {
+        let mut mock_cryptography_service = MockCryptographyService::new();
+
+        mock_cryptography_service
+            .expect_decrypt_message()
+            .times(1)
+            .returning(|_, _| {
+                Box::pin(async move {
+                    Err(CryptographyServiceError::DecryptionError("Error".to_string()))
+                })
+            });
+
+        let use_case = DecryptDataKeyUseCase::new(&mock_cryptography_service);
+        let request_model = DecryptDataKeyRequestModel {
+            encrypted_data_key: vec![1, 2, 3],
+            encryption_context: EncryptionContext::new(),
+            requester: None,
+            tenant: String::from("billing"),
+            request_id: None,
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(response_model.is_err());
+    }
+}
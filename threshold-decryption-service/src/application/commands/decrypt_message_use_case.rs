@@ -1,8 +1,20 @@
+use std::time::{ Instant, SystemTime, UNIX_EPOCH };
 use thiserror::Error;
-use crate::domain::services::cryptography_service::CryptographyService;
+use crate::domain::{
+    entities::audit_event::{ ciphertext_fingerprint, AuditEvent, AuditOperation, AuditOutcome },
+    services::{
+        audit_trail_service::AuditTrailService,
+        cryptography_service::{ CryptographyService, DecryptionContext },
+    },
+};
 
 pub struct DecryptMessageRequestModel {
     pub message: Vec<u8>,
+    pub associated_data: Option<Vec<u8>>,
+    pub requester: Option<String>,
+    pub reason: Option<String>,
+    pub tenant: String,
+    pub request_id: Option<String>,
 }
 
 pub struct DecryptMessageResponseModel {
@@ -15,77 +27,266 @@ pub enum DecryptMessageError {
         String,
     ),
     #[error("Invalid or broken message encryption. {0}")] BrokenEncryptionError(String),
+    #[error("Unable to record decryption in the audit trail. {0}")] AuditTrailError(String),
 }
 
 pub struct DecryptMessageUseCase<'a> {
     cryptography_service: &'a dyn CryptographyService,
+    audit_trail_service: &'a dyn AuditTrailService,
 }
 
 impl<'a> DecryptMessageUseCase<'a> {
-    pub fn new(cryptography_service: &'a dyn CryptographyService) -> Self {
+    pub fn new(
+        cryptography_service: &'a dyn CryptographyService,
+        audit_trail_service: &'a dyn AuditTrailService
+    ) -> Self {
         Self {
             cryptography_service,
+            audit_trail_service,
         }
     }
 
+    #[tracing::instrument(name = "decrypt_message_use_case", skip_all)]
     pub async fn interact(
         &self,
         request_model: DecryptMessageRequestModel
     ) -> Result<DecryptMessageResponseModel, DecryptMessageError> {
-        let decrypted_message = self.cryptography_service
-            .decrypt_message(request_model.message).await
-            .map_err(|e| DecryptMessageError::CryptographyServiceError(e.to_string()))?;
-        Ok(DecryptMessageResponseModel {
-            decrypted_message: String::from_utf8(decrypted_message).map_err(|e| {
-                DecryptMessageError::BrokenEncryptionError(e.to_string())
-            })?,
-        })
+        let started_at = Instant::now();
+        let fingerprint = ciphertext_fingerprint(&request_model.message);
+        let requester = request_model.requester.clone();
+        let decryption = self.cryptography_service
+            .decrypt_message(request_model.message, DecryptionContext {
+                tenant: request_model.tenant,
+                associated_data: request_model.associated_data,
+                requester: request_model.requester,
+                reason: request_model.reason,
+                request_id: request_model.request_id,
+            }).await
+            .map_err(|e| DecryptMessageError::CryptographyServiceError(e.to_string()));
+        let participants = decryption
+            .as_ref()
+            .map(|decrypted_message| decrypted_message.participants.clone())
+            .unwrap_or_default();
+        let response_model = decryption.and_then(|decrypted_message| {
+            Ok(DecryptMessageResponseModel {
+                decrypted_message: String::from_utf8(decrypted_message.plaintext).map_err(|e| {
+                    DecryptMessageError::BrokenEncryptionError(e.to_string())
+                })?,
+            })
+        });
+        self.audit_trail_service
+            .record(AuditEvent {
+                timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+                operation: AuditOperation::Decrypt,
+                requester,
+                ciphertext_fingerprint: Some(fingerprint),
+                participants,
+                outcome: match response_model {
+                    Ok(_) => AuditOutcome::Success,
+                    Err(_) => AuditOutcome::Failure,
+                },
+                error: response_model.as_ref().err().map(|e| e.to_string()),
+                latency_ms: started_at.elapsed().as_millis() as u64,
+            }).await
+            .map_err(|e| DecryptMessageError::AuditTrailError(e.to_string()))?;
+        response_model
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::services::cryptography_service::{
-        CryptographyServiceError,
-        MockCryptographyService,
+    use crate::domain::services::{
+        audit_trail_service::{ AuditTrailServiceError, MockAuditTrailService },
+        cryptography_service::{
+            CryptographyServiceError,
+            DecryptedMessage,
+            MockCryptographyService,
+        },
     };
 
+    fn decrypted_message(plaintext: Vec<u8>) -> DecryptedMessage {
+        DecryptedMessage {
+            plaintext,
+            participants: vec![1, 3],
+        }
+    }
+
+    fn recording_audit_trail_service() -> MockAuditTrailService {
+        let mut mock_audit_trail_service = MockAuditTrailService::new();
+        mock_audit_trail_service
+            .expect_record()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
+        mock_audit_trail_service
+    }
+
     #[tokio::test]
     async fn should_decrypt_message_use_case() {
         let mut mock_cryptography_service = MockCryptographyService::new();
+        let mock_audit_trail_service = recording_audit_trail_service();
 
         mock_cryptography_service
             .expect_decrypt_message()
+            .withf(|_, context| context.tenant == "billing")
             .times(1)
-            .returning(|message| { Box::pin(async move { Ok(message.to_vec()) }) });
+            .returning(|message, _| {
+                Box::pin(async move { Ok(decrypted_message(message.to_vec())) })
+            });
 
-        let use_case = DecryptMessageUseCase::new(&mock_cryptography_service);
+        let use_case = DecryptMessageUseCase::new(
+            &mock_cryptography_service,
+            &mock_audit_trail_service
+        );
         let request_model = DecryptMessageRequestModel {
             message: b"Hello, World!".to_vec(),
+            associated_data: None,
+            requester: None,
+            reason: None,
+            tenant: String::from("billing"),
+            request_id: None,
         };
         let response_model = use_case.interact(request_model).await.unwrap();
         assert_eq!(response_model.decrypted_message, "Hello, World!");
     }
 
+    #[tokio::test]
+    async fn should_forward_associated_data_decrypt_message_use_case() {
+        let mut mock_cryptography_service = MockCryptographyService::new();
+        let mock_audit_trail_service = recording_audit_trail_service();
+
+        mock_cryptography_service
+            .expect_decrypt_message()
+            .withf(|_, context| context.associated_data == Some(b"tenant=acme".to_vec()))
+            .times(1)
+            .returning(|message, _| {
+                Box::pin(async move { Ok(decrypted_message(message.to_vec())) })
+            });
+
+        let use_case = DecryptMessageUseCase::new(
+            &mock_cryptography_service,
+            &mock_audit_trail_service
+        );
+        let request_model = DecryptMessageRequestModel {
+            message: b"Hello, World!".to_vec(),
+            associated_data: Some(b"tenant=acme".to_vec()),
+            requester: None,
+            reason: None,
+            tenant: String::from("billing"),
+            request_id: None,
+        };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert_eq!(response_model.decrypted_message, "Hello, World!");
+    }
+
+    #[tokio::test]
+    async fn should_record_audit_event_decrypt_message_use_case() {
+        let mut mock_cryptography_service = MockCryptographyService::new();
+        let mut mock_audit_trail_service = MockAuditTrailService::new();
+
+        mock_cryptography_service
+            .expect_decrypt_message()
+            .times(1)
+            .returning(|message, _| {
+                Box::pin(async move { Ok(decrypted_message(message.to_vec())) })
+            });
+        mock_audit_trail_service
+            .expect_record()
+            .withf(|event| {
+                event.operation == AuditOperation::Decrypt &&
+                    event.outcome == AuditOutcome::Success &&
+                    event.requester == Some("token:0102".to_string()) &&
+                    event.ciphertext_fingerprint ==
+                        Some(ciphertext_fingerprint(b"Hello, World!")) &&
+                    event.participants == vec![1, 3] &&
+                    !serde_json::to_string(event).unwrap().contains("Hello, World!")
+            })
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
+
+        let use_case = DecryptMessageUseCase::new(
+            &mock_cryptography_service,
+            &mock_audit_trail_service
+        );
+        let request_model = DecryptMessageRequestModel {
+            message: b"Hello, World!".to_vec(),
+            associated_data: None,
+            requester: Some("token:0102".to_string()),
+            reason: None,
+            tenant: String::from("billing"),
+            request_id: None,
+        };
+        assert!(use_case.interact(request_model).await.is_ok());
+    }
+
     #[tokio::test]
     async fn should_fail_to_decrypt_message_use_case() {
         let mut mock_cryptography_service = MockCryptographyService::new();
+        let mut mock_audit_trail_service = MockAuditTrailService::new();
 
         mock_cryptography_service
             .expect_decrypt_message()
             .times(1)
-            .returning(|_| {
+            .returning(|_, _| {
                 Box::pin(async move {
                     Err(CryptographyServiceError::DecryptionError("Error".to_string()))
                 })
             });
+        mock_audit_trail_service
+            .expect_record()
+            .withf(|event| event.outcome == AuditOutcome::Failure && event.error.is_some())
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
 
-        let use_case = DecryptMessageUseCase::new(&mock_cryptography_service);
+        let use_case = DecryptMessageUseCase::new(
+            &mock_cryptography_service,
+            &mock_audit_trail_service
+        );
         let request_model = DecryptMessageRequestModel {
             message: b"Hello, World!".to_vec(),
+            associated_data: None,
+            requester: None,
+            reason: None,
+            tenant: String::from("billing"),
+            request_id: None,
         };
         let response_model = use_case.interact(request_model).await;
         assert!(response_model.is_err());
     }
+
+    #[tokio::test]
+    async fn should_fail_to_decrypt_message_without_audit_trail_use_case() {
+        let mut mock_cryptography_service = MockCryptographyService::new();
+        let mut mock_audit_trail_service = MockAuditTrailService::new();
+
+        mock_cryptography_service
+            .expect_decrypt_message()
+            .times(1)
+            .returning(|message, _| {
+                Box::pin(async move { Ok(decrypted_message(message.to_vec())) })
+            });
+        mock_audit_trail_service
+            .expect_record()
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move {
+                    Err(AuditTrailServiceError::RecordError("Error".to_string()))
+                })
+            });
+
+        let use_case = DecryptMessageUseCase::new(
+            &mock_cryptography_service,
+            &mock_audit_trail_service
+        );
+        let request_model = DecryptMessageRequestModel {
+            message: b"Hello, World!".to_vec(),
+            associated_data: None,
+            requester: None,
+            reason: None,
+            tenant: String::from("billing"),
+            request_id: None,
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(matches!(response_model, Err(DecryptMessageError::AuditTrailError(_))));
+    }
 }
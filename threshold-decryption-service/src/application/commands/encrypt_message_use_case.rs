@@ -1,8 +1,15 @@
+use std::time::{ Instant, SystemTime, UNIX_EPOCH };
 use thiserror::Error;
-use crate::domain::services::cryptography_service::CryptographyService;
+use crate::domain::{
+    entities::audit_event::{ ciphertext_fingerprint, AuditEvent, AuditOperation, AuditOutcome },
+    services::{ audit_trail_service::AuditTrailService, cryptography_service::CryptographyService },
+};
 
 pub struct EncryptMessageRequestModel {
     pub message: String,
+    pub associated_data: Option<Vec<u8>>,
+    pub requester: Option<String>,
+    pub tenant: String,
 }
 
 pub struct EncryptMessageResponseModel {
@@ -15,16 +22,22 @@ pub enum EncryptMessageError {
         String,
     ),
     #[error("Invalid or broken message encryption. {0}")] BrokenEncryptionError(String),
+    #[error("Unable to record encryption in the audit trail. {0}")] AuditTrailError(String),
 }
 
 pub struct EncryptMessageUseCase<'a> {
     cryptography_service: &'a dyn CryptographyService,
+    audit_trail_service: &'a dyn AuditTrailService,
 }
 
 impl<'a> EncryptMessageUseCase<'a> {
-    pub fn new(cryptography_service: &'a dyn CryptographyService) -> Self {
+    pub fn new(
+        cryptography_service: &'a dyn CryptographyService,
+        audit_trail_service: &'a dyn AuditTrailService
+    ) -> Self {
         Self {
             cryptography_service,
+            audit_trail_service,
         }
     }
 
@@ -32,11 +45,34 @@ impl<'a> EncryptMessageUseCase<'a> {
         &self,
         request_model: EncryptMessageRequestModel
     ) -> Result<EncryptMessageResponseModel, EncryptMessageError> {
-        let encrypted_message = self.cryptography_service
-            .encrypt_message(request_model.message).await
-            .map_err(|e| EncryptMessageError::CryptographyServiceError(e.to_string()))?;
+        let started_at = Instant::now();
+        let encryption = self.cryptography_service
+            .encrypt_message(
+                request_model.tenant,
+                request_model.message.into_bytes(),
+                request_model.associated_data
+            ).await
+            .map_err(|e| EncryptMessageError::CryptographyServiceError(e.to_string()));
+        self.audit_trail_service
+            .record(AuditEvent {
+                timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+                operation: AuditOperation::Encrypt,
+                requester: request_model.requester,
+                ciphertext_fingerprint: encryption
+                    .as_ref()
+                    .ok()
+                    .map(|encrypted_message| ciphertext_fingerprint(encrypted_message)),
+                participants: Vec::new(),
+                outcome: match encryption {
+                    Ok(_) => AuditOutcome::Success,
+                    Err(_) => AuditOutcome::Failure,
+                },
+                error: encryption.as_ref().err().map(|e| e.to_string()),
+                latency_ms: started_at.elapsed().as_millis() as u64,
+            }).await
+            .map_err(|e| EncryptMessageError::AuditTrailError(e.to_string()))?;
         Ok(EncryptMessageResponseModel {
-            encrypted_message,
+            encrypted_message: encryption?,
         })
     }
 }
@@ -44,23 +80,39 @@ impl<'a> EncryptMessageUseCase<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::services::cryptography_service::{
-        CryptographyServiceError,
-        MockCryptographyService,
+    use crate::domain::services::{
+        audit_trail_service::MockAuditTrailService,
+        cryptography_service::{ CryptographyServiceError, MockCryptographyService },
     };
 
     #[tokio::test]
     async fn should_encrypt_message_use_case() {
         let mut mock_cryptography_service = MockCryptographyService::new();
+        let mut mock_audit_trail_service = MockAuditTrailService::new();
 
         mock_cryptography_service
             .expect_encrypt_message()
             .times(1)
-            .returning(|message| { Box::pin(async move { Ok(message.into_bytes()) }) });
+            .returning(|_, message, _| { Box::pin(async move { Ok(message) }) });
+        mock_audit_trail_service
+            .expect_record()
+            .withf(|event| {
+                event.operation == AuditOperation::Encrypt &&
+                    event.outcome == AuditOutcome::Success &&
+                    event.ciphertext_fingerprint == Some(ciphertext_fingerprint(b"Hello, World!"))
+            })
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
 
-        let use_case = EncryptMessageUseCase::new(&mock_cryptography_service);
+        let use_case = EncryptMessageUseCase::new(
+            &mock_cryptography_service,
+            &mock_audit_trail_service
+        );
         let request_model = EncryptMessageRequestModel {
             message: String::from("Hello, World!"),
+            associated_data: None,
+            requester: None,
+            tenant: String::from("billing"),
         };
         let response_model = use_case.interact(request_model).await.unwrap();
         assert_eq!(response_model.encrypted_message, b"Hello, World!".to_vec());
@@ -69,19 +121,31 @@ mod tests {
     #[tokio::test]
     async fn should_fail_to_encrypt_message_use_case() {
         let mut mock_cryptography_service = MockCryptographyService::new();
+        let mut mock_audit_trail_service = MockAuditTrailService::new();
 
         mock_cryptography_service
             .expect_encrypt_message()
             .times(1)
-            .returning(|_| {
+            .returning(|_, _, _| {
                 Box::pin(async move {
                     Err(CryptographyServiceError::EncryptionError("Error".to_string()))
                 })
             });
+        mock_audit_trail_service
+            .expect_record()
+            .withf(|event| event.outcome == AuditOutcome::Failure)
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
 
-        let use_case = EncryptMessageUseCase::new(&mock_cryptography_service);
+        let use_case = EncryptMessageUseCase::new(
+            &mock_cryptography_service,
+            &mock_audit_trail_service
+        );
         let request_model = EncryptMessageRequestModel {
             message: String::from("Hello, World!"),
+            associated_data: None,
+            requester: None,
+            tenant: String::from("billing"),
         };
         let response_model = use_case.interact(request_model).await;
         assert!(response_model.is_err());
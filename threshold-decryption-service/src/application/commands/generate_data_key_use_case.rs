@@ -0,0 +1,132 @@
+use thiserror::Error;
+use crate::domain::{
+    entities::data_key::{
+        encryption_context_associated_data,
+        DataKey,
+        EncryptionContext,
+        KeySpec,
+    },
+    services::cryptography_service::CryptographyService,
+};
+
+pub struct GenerateDataKeyRequestModel {
+    pub key_spec: KeySpec,
+    pub encryption_context: EncryptionContext,
+    pub tenant: String,
+}
+
+pub struct GenerateDataKeyResponseModel {
+    pub key_spec: KeySpec,
+    pub plaintext_data_key: Vec<u8>,
+    pub encrypted_data_key: Vec<u8>,
+}
+
+#[derive(Error, Debug)]
+pub enum GenerateDataKeyError {
+    #[error("Unable to generate data key from Cryptography Service. {0}")] CryptographyServiceError(
+        String,
+    ),
+    #[error("Invalid data key encoding. {0}")] InvalidDataKeyError(String),
+}
+
+pub struct GenerateDataKeyUseCase<'a> {
+    cryptography_service: &'a dyn CryptographyService,
+}
+
+impl<'a> GenerateDataKeyUseCase<'a> {
+    pub fn new(cryptography_service: &'a dyn CryptographyService) -> Self {
+        Self {
+            cryptography_service,
+        }
+    }
+
+    pub async fn interact(
+        &self,
+        request_model: GenerateDataKeyRequestModel
+    ) -> Result<GenerateDataKeyResponseModel, GenerateDataKeyError> {
+        let plaintext_data_key = self.cryptography_service
+            .generate_data_key(request_model.key_spec.key_length()).await
+            .map_err(|e| GenerateDataKeyError::CryptographyServiceError(e.to_string()))?;
+        let associated_data = encryption_context_associated_data(
+            &request_model.encryption_context
+        );
+        let data_key = DataKey {
+            key: plaintext_data_key.clone(),
+            encryption_context: request_model.encryption_context,
+        };
+        let serialized_data_key = data_key
+            .to_bytes()
+            .map_err(|e| GenerateDataKeyError::InvalidDataKeyError(e.to_string()))?;
+        let encrypted_data_key = self.cryptography_service
+            .encrypt_message(request_model.tenant, serialized_data_key, associated_data).await
+            .map_err(|e| GenerateDataKeyError::CryptographyServiceError(e.to_string()))?;
+        Ok(GenerateDataKeyResponseModel {
+            key_spec: request_model.key_spec,
+            plaintext_data_key,
+            encrypted_data_key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::cryptography_service::{
+        CryptographyServiceError,
+        MockCryptographyService,
+    };
+
+    #[tokio::test]
+    async fn should_generate_data_key_use_case() {
+        let mut mock_cryptography_service = MockCryptographyService::new();
+
+        mock_cryptography_service
+            .expect_generate_data_key()
+            .times(1)
+            .returning(|key_length| { Box::pin(async move { Ok(vec![7; key_length]) }) });
+        mock_cryptography_service
+            .expect_encrypt_message()
+            .withf(|_, _, associated_data| {
+                associated_data.as_deref() == Some(b"encryption-context:{\"tenant\":\"acme\"}")
+            })
+            .times(1)
+            .returning(|_, message, _| { Box::pin(async move { Ok(message) }) });
+
+        let use_case = GenerateDataKeyUseCase::new(&mock_cryptography_service);
+        let mut encryption_context = EncryptionContext::new();
+        encryption_context.insert("tenant".to_string(), "acme".to_string());
+        let request_model = GenerateDataKeyRequestModel {
+            key_spec: KeySpec::Aes128,
+            encryption_context: encryption_context.clone(),
+            tenant: String::from("billing"),
+        };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert_eq!(response_model.plaintext_data_key, vec![7; 16]);
+        let data_key = DataKey::from_bytes(&response_model.encrypted_data_key).unwrap();
+        assert_eq!(data_key.key, vec![7; 16]);
+        assert_eq!(data_key.encryption_context, encryption_context);
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_generate_data_key_use_case() {
+        let mut mock_cryptography_service = MockCryptographyService::new();
+
+        mock_cryptography_service
+            .expect_generate_data_key()
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move {
+                    Err(CryptographyServiceError::DataKeyGenerationError("Error".to_string()))
+                })
+            });
+
+        let use_case = GenerateDataKeyUseCase::new(&mock_cryptography_service);
+        let request_model = GenerateDataKeyRequestModel {
+            key_spec: KeySpec::Aes256,
+            encryption_context: EncryptionContext::new(),
+            tenant: String::from("billing"),
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(response_model.is_err());
+    }
+}
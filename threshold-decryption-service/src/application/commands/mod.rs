@@ -1,2 +1,14 @@
 pub mod decrypt_message_use_case;
 pub mod encrypt_message_use_case;
+pub mod generate_data_key_use_case;
+pub mod decrypt_data_key_use_case;
+pub mod create_api_key_use_case;
+pub mod revoke_api_key_use_case;
+pub mod rotate_api_key_use_case;
+pub mod authenticate_api_key_use_case;
+pub mod request_decryption_approval_use_case;
+pub mod decide_pending_decryption_use_case;
+pub mod record_server_heartbeat_use_case;
+pub mod acknowledge_key_sync_use_case;
+pub mod authorize_key_share_request_use_case;
+pub mod accept_decryption_refusal_use_case;
@@ -0,0 +1,143 @@
+use thiserror::Error;
+use crate::domain::{
+    entities::decryption_server::{ RegisteredServer, ServerHeartbeat },
+    services::server_registry_service::ServerRegistryService,
+};
+
+/// Heartbeat whose signature was verified against `heartbeat.identity_key`.
+pub struct RecordServerHeartbeatRequestModel {
+    pub heartbeat: ServerHeartbeat,
+    pub n_servers: usize,
+    pub received_at: u64,
+}
+
+pub struct RecordServerHeartbeatResponseModel {
+    /// Whether this is the first heartbeat of the server since the service started.
+    pub first_seen: bool,
+}
+
+#[derive(Error, Debug)]
+pub enum RecordServerHeartbeatError {
+    #[error("Unable to save heartbeat in Server Registry Service. {0}")] ServerRegistryServiceError(
+        String,
+    ),
+    #[error("Decryption Server {0} is not part of the cluster.")] UnknownServer(usize),
+    #[error("Heartbeat of Decryption Server {0} signed with an unknown key.")] UnknownIdentityKey(
+        usize,
+    ),
+}
+
+pub struct RecordServerHeartbeatUseCase<'a> {
+    server_registry_service: &'a dyn ServerRegistryService,
+}
+
+impl<'a> RecordServerHeartbeatUseCase<'a> {
+    pub fn new(server_registry_service: &'a dyn ServerRegistryService) -> Self {
+        Self {
+            server_registry_service,
+        }
+    }
+
+    pub async fn interact(
+        &self,
+        request_model: RecordServerHeartbeatRequestModel
+    ) -> Result<RecordServerHeartbeatResponseModel, RecordServerHeartbeatError> {
+        let heartbeat = request_model.heartbeat;
+        if heartbeat.id >= request_model.n_servers {
+            return Err(RecordServerHeartbeatError::UnknownServer(heartbeat.id));
+        }
+        let identity_key = self.server_registry_service
+            .identity_key(heartbeat.id).await
+            .map_err(|e| RecordServerHeartbeatError::ServerRegistryServiceError(e.to_string()))?;
+        if identity_key.is_some_and(|identity_key| identity_key != heartbeat.identity_key) {
+            return Err(RecordServerHeartbeatError::UnknownIdentityKey(heartbeat.id));
+        }
+        let first_seen = !self.server_registry_service
+            .list().await
+            .map_err(|e| RecordServerHeartbeatError::ServerRegistryServiceError(e.to_string()))?
+            .iter()
+            .any(|server| server.heartbeat.id == heartbeat.id);
+        self.server_registry_service
+            .save(RegisteredServer {
+                heartbeat,
+                last_seen_at: request_model.received_at,
+            }).await
+            .map_err(|e| RecordServerHeartbeatError::ServerRegistryServiceError(e.to_string()))?;
+        Ok(RecordServerHeartbeatResponseModel { first_seen })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::server_registry_service::MockServerRegistryService;
+
+    fn heartbeat(id: usize, identity_key: Vec<u8>) -> ServerHeartbeat {
+        ServerHeartbeat {
+            id,
+            version: "0.1.0".to_string(),
+            identity_key,
+            key_fingerprints: Vec::new(),
+            load: 4,
+            sent_at: 1_700_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_record_server_heartbeat_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_identity_key()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(None) }) });
+        mock_server_registry_service
+            .expect_list()
+            .times(1)
+            .returning(|| { Box::pin(async move { Ok(Vec::new()) }) });
+        mock_server_registry_service
+            .expect_save()
+            .withf(|server| server.heartbeat.id == 1 && server.last_seen_at == 1_700_000_001)
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
+
+        let use_case = RecordServerHeartbeatUseCase::new(&mock_server_registry_service);
+        let request_model = RecordServerHeartbeatRequestModel {
+            heartbeat: heartbeat(1, vec![7; 32]),
+            n_servers: 3,
+            received_at: 1_700_000_001,
+        };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert!(response_model.first_seen);
+    }
+
+    #[tokio::test]
+    async fn should_reject_heartbeat_signed_with_unknown_key_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_identity_key()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(Some(vec![7; 32])) }) });
+        mock_server_registry_service.expect_save().never();
+
+        let use_case = RecordServerHeartbeatUseCase::new(&mock_server_registry_service);
+        let request_model = RecordServerHeartbeatRequestModel {
+            heartbeat: heartbeat(1, vec![8; 32]),
+            n_servers: 3,
+            received_at: 1_700_000_001,
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(
+            matches!(response_model, Err(RecordServerHeartbeatError::UnknownIdentityKey(1)))
+        );
+
+        let request_model = RecordServerHeartbeatRequestModel {
+            heartbeat: heartbeat(3, vec![7; 32]),
+            n_servers: 3,
+            received_at: 1_700_000_001,
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(matches!(response_model, Err(RecordServerHeartbeatError::UnknownServer(3))));
+    }
+}
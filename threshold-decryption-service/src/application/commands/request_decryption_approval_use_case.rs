@@ -0,0 +1,129 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+use thiserror::Error;
+use crate::domain::{
+    entities::pending_decryption::{
+        generate_pending_decryption_id,
+        PendingDecryption,
+        PendingDecryptionStatus,
+    },
+    services::pending_decryption_service::PendingDecryptionService,
+};
+
+pub struct RequestDecryptionApprovalRequestModel {
+    pub message: Vec<u8>,
+    pub associated_data: Option<Vec<u8>>,
+    pub requester: String,
+    pub reason: Option<String>,
+    pub tenant: String,
+    pub required_approvals: usize,
+    pub expires_in_secs: u64,
+}
+
+pub struct RequestDecryptionApprovalResponseModel {
+    pub pending_decryption: PendingDecryption,
+}
+
+#[derive(Error, Debug)]
+pub enum RequestDecryptionApprovalError {
+    #[error("Unable to store pending decryption. {0}")] PendingDecryptionServiceError(String),
+}
+
+pub struct RequestDecryptionApprovalUseCase<'a> {
+    pending_decryption_service: &'a dyn PendingDecryptionService,
+}
+
+impl<'a> RequestDecryptionApprovalUseCase<'a> {
+    pub fn new(pending_decryption_service: &'a dyn PendingDecryptionService) -> Self {
+        Self {
+            pending_decryption_service,
+        }
+    }
+
+    pub async fn interact(
+        &self,
+        request_model: RequestDecryptionApprovalRequestModel
+    ) -> Result<RequestDecryptionApprovalResponseModel, RequestDecryptionApprovalError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let pending_decryption = PendingDecryption {
+            id: generate_pending_decryption_id(),
+            tenant: request_model.tenant,
+            requester: request_model.requester,
+            message: request_model.message,
+            associated_data: request_model.associated_data,
+            reason: request_model.reason,
+            required_approvals: request_model.required_approvals.max(1),
+            approvers: Vec::new(),
+            denied_by: None,
+            created_at: now,
+            expires_at: now + request_model.expires_in_secs,
+            status: PendingDecryptionStatus::Pending,
+            decrypted_message: None,
+            error: None,
+        };
+        self.pending_decryption_service
+            .save(pending_decryption.clone()).await
+            .map_err(|e| {
+                RequestDecryptionApprovalError::PendingDecryptionServiceError(e.to_string())
+            })?;
+        Ok(RequestDecryptionApprovalResponseModel { pending_decryption })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::pending_decryption_service::{
+        MockPendingDecryptionService,
+        PendingDecryptionServiceError,
+    };
+
+    fn request_model() -> RequestDecryptionApprovalRequestModel {
+        RequestDecryptionApprovalRequestModel {
+            message: b"ciphertext".to_vec(),
+            associated_data: None,
+            requester: "billing-service".to_string(),
+            reason: Some("Chargeback investigation".to_string()),
+            tenant: "billing".to_string(),
+            required_approvals: 2,
+            expires_in_secs: 3600,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_request_decryption_approval_use_case() {
+        let mut mock_pending_decryption_service = MockPendingDecryptionService::new();
+
+        mock_pending_decryption_service
+            .expect_save()
+            .withf(|pending_decryption| {
+                pending_decryption.status == PendingDecryptionStatus::Pending &&
+                    pending_decryption.required_approvals == 2 &&
+                    pending_decryption.expires_at == pending_decryption.created_at + 3600
+            })
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
+
+        let use_case = RequestDecryptionApprovalUseCase::new(&mock_pending_decryption_service);
+        let response_model = use_case.interact(request_model()).await.unwrap();
+        assert_eq!(response_model.pending_decryption.requester, "billing-service");
+        assert!(response_model.pending_decryption.approvers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_request_decryption_approval_use_case() {
+        let mut mock_pending_decryption_service = MockPendingDecryptionService::new();
+
+        mock_pending_decryption_service
+            .expect_save()
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move {
+                    Err(PendingDecryptionServiceError::StorageError("Error".to_string()))
+                })
+            });
+
+        let use_case = RequestDecryptionApprovalUseCase::new(&mock_pending_decryption_service);
+        let response_model = use_case.interact(request_model()).await;
+        assert!(response_model.is_err());
+    }
+}
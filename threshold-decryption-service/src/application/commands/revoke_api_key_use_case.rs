@@ -0,0 +1,105 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+use thiserror::Error;
+use crate::domain::{ entities::api_key::ApiKey, services::api_key_service::ApiKeyService };
+
+pub struct RevokeApiKeyRequestModel {
+    pub id: String,
+}
+
+pub struct RevokeApiKeyResponseModel {
+    pub api_key: ApiKey,
+}
+
+#[derive(Error, Debug)]
+pub enum RevokeApiKeyError {
+    #[error("Unable to revoke API key in API Key Service. {0}")] ApiKeyServiceError(String),
+    #[error("API key `{0}` not found.")] ApiKeyNotFound(String),
+}
+
+pub struct RevokeApiKeyUseCase<'a> {
+    api_key_service: &'a dyn ApiKeyService,
+}
+
+impl<'a> RevokeApiKeyUseCase<'a> {
+    pub fn new(api_key_service: &'a dyn ApiKeyService) -> Self {
+        Self {
+            api_key_service,
+        }
+    }
+
+    pub async fn interact(
+        &self,
+        request_model: RevokeApiKeyRequestModel
+    ) -> Result<RevokeApiKeyResponseModel, RevokeApiKeyError> {
+        let mut api_key = self.api_key_service
+            .find(request_model.id.clone()).await
+            .map_err(|e| RevokeApiKeyError::ApiKeyServiceError(e.to_string()))?
+            .ok_or(RevokeApiKeyError::ApiKeyNotFound(request_model.id))?;
+        if !api_key.is_revoked() {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            api_key.revoked_at = Some(now);
+            self.api_key_service
+                .save(api_key.clone()).await
+                .map_err(|e| RevokeApiKeyError::ApiKeyServiceError(e.to_string()))?;
+        }
+        Ok(RevokeApiKeyResponseModel { api_key })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::api_key_service::MockApiKeyService;
+
+    #[tokio::test]
+    async fn should_revoke_api_key_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_find()
+            .times(1)
+            .returning(|id| {
+                Box::pin(async move {
+                    Ok(
+                        Some(ApiKey {
+                            id,
+                            name: "ci".to_string(),
+                            owner: "billing-team".to_string(),
+                            key_hash: "hash".to_string(),
+                            created_at: 1,
+                            expires_at: None,
+                            last_used_at: None,
+                            revoked_at: None,
+                            scopes: Vec::new(),
+                            tenant: None,
+                        })
+                    )
+                })
+            });
+        mock_api_key_service
+            .expect_save()
+            .withf(|api_key| api_key.is_revoked())
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
+
+        let use_case = RevokeApiKeyUseCase::new(&mock_api_key_service);
+        let request_model = RevokeApiKeyRequestModel { id: "0a1b".to_string() };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert!(response_model.api_key.is_revoked());
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_revoke_unknown_api_key_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_find()
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(None) }) });
+
+        let use_case = RevokeApiKeyUseCase::new(&mock_api_key_service);
+        let request_model = RevokeApiKeyRequestModel { id: "0a1b".to_string() };
+        let response_model = use_case.interact(request_model).await;
+        assert!(matches!(response_model, Err(RevokeApiKeyError::ApiKeyNotFound(_))));
+    }
+}
@@ -0,0 +1,113 @@
+use thiserror::Error;
+use crate::domain::{
+    entities::api_key::{ generate_api_key_secret, hash_api_key_secret, ApiKey },
+    services::api_key_service::ApiKeyService,
+};
+
+pub struct RotateApiKeyRequestModel {
+    pub id: String,
+}
+
+pub struct RotateApiKeyResponseModel {
+    pub api_key: ApiKey,
+    /// The only time the new secret is available, it is not stored.
+    pub secret: String,
+}
+
+#[derive(Error, Debug)]
+pub enum RotateApiKeyError {
+    #[error("Unable to rotate API key in API Key Service. {0}")] ApiKeyServiceError(String),
+    #[error("API key `{0}` not found.")] ApiKeyNotFound(String),
+    #[error("API key `{0}` is revoked.")] ApiKeyRevoked(String),
+}
+
+pub struct RotateApiKeyUseCase<'a> {
+    api_key_service: &'a dyn ApiKeyService,
+}
+
+impl<'a> RotateApiKeyUseCase<'a> {
+    pub fn new(api_key_service: &'a dyn ApiKeyService) -> Self {
+        Self {
+            api_key_service,
+        }
+    }
+
+    /// Replaces the secret of an API key, keeping its id, name, owner and expiry. The previous
+    /// secret stops working immediately.
+    pub async fn interact(
+        &self,
+        request_model: RotateApiKeyRequestModel
+    ) -> Result<RotateApiKeyResponseModel, RotateApiKeyError> {
+        let mut api_key = self.api_key_service
+            .find(request_model.id.clone()).await
+            .map_err(|e| RotateApiKeyError::ApiKeyServiceError(e.to_string()))?
+            .ok_or(RotateApiKeyError::ApiKeyNotFound(request_model.id))?;
+        if api_key.is_revoked() {
+            return Err(RotateApiKeyError::ApiKeyRevoked(api_key.id));
+        }
+        let secret = generate_api_key_secret();
+        api_key.key_hash = hash_api_key_secret(&secret);
+        api_key.last_used_at = None;
+        self.api_key_service
+            .save(api_key.clone()).await
+            .map_err(|e| RotateApiKeyError::ApiKeyServiceError(e.to_string()))?;
+        Ok(RotateApiKeyResponseModel { api_key, secret })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::api_key_service::MockApiKeyService;
+
+    fn stored_api_key(id: String, revoked_at: Option<u64>) -> ApiKey {
+        ApiKey {
+            id,
+            name: "ci".to_string(),
+            owner: "billing-team".to_string(),
+            key_hash: "hash".to_string(),
+            created_at: 1,
+            expires_at: None,
+            last_used_at: Some(2),
+            revoked_at,
+            scopes: Vec::new(),
+            tenant: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_rotate_api_key_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_find()
+            .times(1)
+            .returning(|id| { Box::pin(async move { Ok(Some(stored_api_key(id, None))) }) });
+        mock_api_key_service
+            .expect_save()
+            .withf(|api_key| api_key.key_hash != "hash")
+            .times(1)
+            .returning(|_| { Box::pin(async move { Ok(()) }) });
+
+        let use_case = RotateApiKeyUseCase::new(&mock_api_key_service);
+        let request_model = RotateApiKeyRequestModel { id: "0a1b".to_string() };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert_eq!(response_model.api_key.id, "0a1b");
+        assert_eq!(response_model.api_key.key_hash, hash_api_key_secret(&response_model.secret));
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_rotate_revoked_api_key_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_find()
+            .times(1)
+            .returning(|id| { Box::pin(async move { Ok(Some(stored_api_key(id, Some(3)))) }) });
+
+        let use_case = RotateApiKeyUseCase::new(&mock_api_key_service);
+        let request_model = RotateApiKeyRequestModel { id: "0a1b".to_string() };
+        let response_model = use_case.interact(request_model).await;
+        assert!(matches!(response_model, Err(RotateApiKeyError::ApiKeyRevoked(_))));
+    }
+}
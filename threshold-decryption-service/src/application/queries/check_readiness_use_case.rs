@@ -0,0 +1,131 @@
+use crate::domain::{
+    entities::readiness::Readiness,
+    services::cluster_health_service::ClusterHealthService,
+};
+
+pub struct CheckReadinessRequestModel {
+    pub threshold: usize,
+}
+
+pub struct CheckReadinessResponseModel {
+    pub readiness: Readiness,
+}
+
+pub struct CheckReadinessUseCase<'a> {
+    cluster_health_service: &'a dyn ClusterHealthService,
+}
+
+impl<'a> CheckReadinessUseCase<'a> {
+    pub fn new(cluster_health_service: &'a dyn ClusterHealthService) -> Self {
+        Self {
+            cluster_health_service,
+        }
+    }
+
+    pub async fn interact(
+        &self,
+        request_model: CheckReadinessRequestModel
+    ) -> CheckReadinessResponseModel {
+        let broker_connected = self.cluster_health_service.is_broker_connected().await;
+        let servers = self.cluster_health_service.probe_servers().await;
+        CheckReadinessResponseModel {
+            readiness: Readiness::assess(broker_connected, request_model.threshold, servers),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        entities::readiness::{ ReadinessStatus, ServerReadiness },
+        services::cluster_health_service::MockClusterHealthService,
+    };
+
+    fn server(id: usize, key_shares_loaded: bool) -> ServerReadiness {
+        ServerReadiness {
+            id,
+            reachable: true,
+            broker_connected: true,
+            key_shares_loaded,
+            stale_key_ids: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn cluster_health_service(
+        broker_connected: bool,
+        servers: Vec<ServerReadiness>
+    ) -> MockClusterHealthService {
+        let mut mock_cluster_health_service = MockClusterHealthService::new();
+        mock_cluster_health_service
+            .expect_is_broker_connected()
+            .times(1)
+            .returning(move || { Box::pin(async move { broker_connected }) });
+        mock_cluster_health_service
+            .expect_probe_servers()
+            .times(1)
+            .returning(move || {
+                let servers = servers.clone();
+                Box::pin(async move { servers })
+            });
+        mock_cluster_health_service
+    }
+
+    #[tokio::test]
+    async fn should_check_readiness_use_case() {
+        let servers = vec![server(0, true), server(1, true), server(2, true)];
+        let mock_cluster_health_service = cluster_health_service(true, servers);
+
+        let use_case = CheckReadinessUseCase::new(&mock_cluster_health_service);
+        let response_model = use_case.interact(CheckReadinessRequestModel { threshold: 1 }).await;
+        assert_eq!(response_model.readiness.status, ReadinessStatus::Ready);
+        assert_eq!(response_model.readiness.available_servers, 3);
+    }
+
+    #[tokio::test]
+    async fn should_check_degraded_readiness_use_case() {
+        let servers = vec![
+            server(0, true),
+            ServerReadiness::unreachable(1, "connection refused".to_string()),
+            server(2, true)
+        ];
+        let mock_cluster_health_service = cluster_health_service(true, servers);
+
+        let use_case = CheckReadinessUseCase::new(&mock_cluster_health_service);
+        let response_model = use_case.interact(CheckReadinessRequestModel { threshold: 1 }).await;
+        assert_eq!(response_model.readiness.status, ReadinessStatus::Degraded);
+        assert_eq!(response_model.readiness.required_servers, 2);
+    }
+
+    #[tokio::test]
+    async fn should_check_unavailable_readiness_use_case() {
+        let servers = vec![server(0, true), server(1, false), server(2, false)];
+        let mock_cluster_health_service = cluster_health_service(true, servers.clone());
+
+        let use_case = CheckReadinessUseCase::new(&mock_cluster_health_service);
+        let response_model = use_case.interact(CheckReadinessRequestModel { threshold: 1 }).await;
+        assert_eq!(response_model.readiness.status, ReadinessStatus::Unavailable);
+
+        let mock_cluster_health_service = cluster_health_service(false, servers);
+        let use_case = CheckReadinessUseCase::new(&mock_cluster_health_service);
+        let response_model = use_case.interact(CheckReadinessRequestModel { threshold: 0 }).await;
+        assert_eq!(response_model.readiness.status, ReadinessStatus::Unavailable);
+    }
+
+    #[tokio::test]
+    async fn should_not_count_servers_with_stale_key_shares() {
+        let stale_server = ServerReadiness {
+            stale_key_ids: vec!["6a8e0c2b9f1d4e37".to_string()],
+            ..server(1, true)
+        };
+        assert!(!stale_server.is_available());
+        let servers = vec![server(0, true), stale_server, server(2, false)];
+        let mock_cluster_health_service = cluster_health_service(true, servers);
+
+        let use_case = CheckReadinessUseCase::new(&mock_cluster_health_service);
+        let response_model = use_case.interact(CheckReadinessRequestModel { threshold: 1 }).await;
+        assert_eq!(response_model.readiness.status, ReadinessStatus::Unavailable);
+        assert_eq!(response_model.readiness.available_servers, 1);
+    }
+}
@@ -0,0 +1,139 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+use thiserror::Error;
+use crate::domain::{
+    entities::pending_decryption::PendingDecryption,
+    services::pending_decryption_service::PendingDecryptionService,
+};
+
+pub struct GetPendingDecryptionRequestModel {
+    pub id: String,
+    pub caller: String,
+    pub tenant: String,
+    /// Whether the caller may approve decryptions of its tenant.
+    pub is_approver: bool,
+}
+
+pub struct GetPendingDecryptionResponseModel {
+    /// The decrypted message is only ever returned to the requester.
+    pub pending_decryption: PendingDecryption,
+}
+
+#[derive(Error, Debug)]
+pub enum GetPendingDecryptionError {
+    #[error("Unable to get pending decryption. {0}")] PendingDecryptionServiceError(String),
+    #[error("Pending decryption `{0}` not found.")] PendingDecryptionNotFound(String),
+    #[error("Only the requester and approvers can read a pending decryption.")] Forbidden,
+}
+
+pub struct GetPendingDecryptionUseCase<'a> {
+    pending_decryption_service: &'a dyn PendingDecryptionService,
+}
+
+impl<'a> GetPendingDecryptionUseCase<'a> {
+    pub fn new(pending_decryption_service: &'a dyn PendingDecryptionService) -> Self {
+        Self {
+            pending_decryption_service,
+        }
+    }
+
+    pub async fn interact(
+        &self,
+        request_model: GetPendingDecryptionRequestModel
+    ) -> Result<GetPendingDecryptionResponseModel, GetPendingDecryptionError> {
+        let mut pending_decryption = self.pending_decryption_service
+            .find(request_model.id.clone()).await
+            .map_err(|e| GetPendingDecryptionError::PendingDecryptionServiceError(e.to_string()))?
+            .filter(|pending_decryption| pending_decryption.tenant == request_model.tenant)
+            .ok_or(GetPendingDecryptionError::PendingDecryptionNotFound(request_model.id))?;
+        let is_requester = pending_decryption.requester == request_model.caller;
+        if !is_requester && !request_model.is_approver {
+            return Err(GetPendingDecryptionError::Forbidden);
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        pending_decryption.status = pending_decryption.status_at(now);
+        if !is_requester {
+            pending_decryption.decrypted_message = None;
+        }
+        Ok(GetPendingDecryptionResponseModel { pending_decryption })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        entities::pending_decryption::PendingDecryptionStatus,
+        services::pending_decryption_service::MockPendingDecryptionService,
+    };
+
+    fn finding_pending_decryption_service() -> MockPendingDecryptionService {
+        let mut mock_pending_decryption_service = MockPendingDecryptionService::new();
+        mock_pending_decryption_service
+            .expect_find()
+            .times(1)
+            .returning(|id| {
+                Box::pin(async move {
+                    Ok(
+                        Some(PendingDecryption {
+                            id,
+                            tenant: "billing".to_string(),
+                            requester: "billing-service".to_string(),
+                            message: b"ciphertext".to_vec(),
+                            associated_data: None,
+                            reason: None,
+                            required_approvals: 2,
+                            approvers: vec!["alice".to_string(), "bob".to_string()],
+                            denied_by: None,
+                            created_at: 1000,
+                            expires_at: u64::MAX,
+                            status: PendingDecryptionStatus::Completed,
+                            decrypted_message: Some("Hello, World!".to_string()),
+                            error: None,
+                        })
+                    )
+                })
+            });
+        mock_pending_decryption_service
+    }
+
+    fn request_model(caller: &str, is_approver: bool) -> GetPendingDecryptionRequestModel {
+        GetPendingDecryptionRequestModel {
+            id: "0a1b".to_string(),
+            caller: caller.to_string(),
+            tenant: "billing".to_string(),
+            is_approver,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_get_pending_decryption_use_case() {
+        let mock_pending_decryption_service = finding_pending_decryption_service();
+
+        let use_case = GetPendingDecryptionUseCase::new(&mock_pending_decryption_service);
+        let response_model = use_case
+            .interact(request_model("billing-service", false)).await
+            .unwrap();
+        assert_eq!(
+            response_model.pending_decryption.decrypted_message,
+            Some("Hello, World!".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn should_hide_decrypted_message_from_approvers_get_pending_decryption_use_case() {
+        let mock_pending_decryption_service = finding_pending_decryption_service();
+
+        let use_case = GetPendingDecryptionUseCase::new(&mock_pending_decryption_service);
+        let response_model = use_case.interact(request_model("alice", true)).await.unwrap();
+        assert_eq!(response_model.pending_decryption.decrypted_message, None);
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_get_pending_decryption_of_other_callers_use_case() {
+        let mock_pending_decryption_service = finding_pending_decryption_service();
+
+        let use_case = GetPendingDecryptionUseCase::new(&mock_pending_decryption_service);
+        let response_model = use_case.interact(request_model("mallory", false)).await;
+        assert!(matches!(response_model, Err(GetPendingDecryptionError::Forbidden)));
+    }
+}
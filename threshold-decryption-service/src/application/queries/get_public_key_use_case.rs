@@ -1,6 +1,10 @@
 use thiserror::Error;
 use crate::domain::services::cryptography_service::CryptographyService;
 
+pub struct GetPublicKeyRequestModel {
+    pub tenant: String,
+}
+
 pub struct GetPublicKeyResponseModel {
     pub public_key: Vec<u8>,
 }
@@ -23,9 +27,12 @@ impl<'a> GetPublicKeyUseCase<'a> {
         }
     }
 
-    pub async fn interact(&self) -> Result<GetPublicKeyResponseModel, GetPublicKeyError> {
+    pub async fn interact(
+        &self,
+        request_model: GetPublicKeyRequestModel
+    ) -> Result<GetPublicKeyResponseModel, GetPublicKeyError> {
         let public_key = self.cryptography_service
-            .share_public_key().await
+            .share_public_key(request_model.tenant).await
             .map_err(|e| GetPublicKeyError::CryptographyServiceError(e.to_string()))?;
         Ok(GetPublicKeyResponseModel { public_key })
     }
@@ -45,11 +52,13 @@ mod tests {
 
         mock_cryptography_service
             .expect_share_public_key()
+            .withf(|tenant| tenant == "billing")
             .times(1)
-            .returning(|| { Box::pin(async move { Ok(vec![1, 2, 3]) }) });
+            .returning(|_| { Box::pin(async move { Ok(vec![1, 2, 3]) }) });
 
         let use_case = GetPublicKeyUseCase::new(&mock_cryptography_service);
-        let response_model = use_case.interact().await.unwrap();
+        let request_model = GetPublicKeyRequestModel { tenant: String::from("billing") };
+        let response_model = use_case.interact(request_model).await.unwrap();
         assert_eq!(response_model.public_key, vec![1, 2, 3]);
     }
 
@@ -60,14 +69,15 @@ mod tests {
         mock_cryptography_service
             .expect_share_public_key()
             .times(1)
-            .returning(||
+            .returning(|_|
                 Box::pin(async move {
                     Err(CryptographyServiceError::PublicKeySharingError("Error".to_string()))
                 })
             );
 
         let use_case = GetPublicKeyUseCase::new(&mock_cryptography_service);
-        let response_model = use_case.interact().await;
+        let request_model = GetPublicKeyRequestModel { tenant: String::from("billing") };
+        let response_model = use_case.interact(request_model).await;
         assert!(response_model.is_err());
     }
 }
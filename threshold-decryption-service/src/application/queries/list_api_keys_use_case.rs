@@ -0,0 +1,67 @@
+use thiserror::Error;
+use crate::domain::{ entities::api_key::ApiKey, services::api_key_service::ApiKeyService };
+
+pub struct ListApiKeysResponseModel {
+    pub api_keys: Vec<ApiKey>,
+}
+
+#[derive(Error, Debug)]
+pub enum ListApiKeysError {
+    #[error("Unable to list API keys from API Key Service. {0}")] ApiKeyServiceError(String),
+}
+
+pub struct ListApiKeysUseCase<'a> {
+    api_key_service: &'a dyn ApiKeyService,
+}
+
+impl<'a> ListApiKeysUseCase<'a> {
+    pub fn new(api_key_service: &'a dyn ApiKeyService) -> Self {
+        Self {
+            api_key_service,
+        }
+    }
+
+    pub async fn interact(&self) -> Result<ListApiKeysResponseModel, ListApiKeysError> {
+        let mut api_keys = self.api_key_service
+            .list().await
+            .map_err(|e| ListApiKeysError::ApiKeyServiceError(e.to_string()))?;
+        api_keys.sort_by_key(|api_key| api_key.created_at);
+        Ok(ListApiKeysResponseModel { api_keys })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::api_key_service::{ ApiKeyServiceError, MockApiKeyService };
+
+    #[tokio::test]
+    async fn should_list_api_keys_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_list()
+            .times(1)
+            .returning(|| { Box::pin(async move { Ok(Vec::new()) }) });
+
+        let use_case = ListApiKeysUseCase::new(&mock_api_key_service);
+        let response_model = use_case.interact().await.unwrap();
+        assert!(response_model.api_keys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_list_api_keys_use_case() {
+        let mut mock_api_key_service = MockApiKeyService::new();
+
+        mock_api_key_service
+            .expect_list()
+            .times(1)
+            .returning(|| {
+                Box::pin(async move { Err(ApiKeyServiceError::StorageError("Error".to_string())) })
+            });
+
+        let use_case = ListApiKeysUseCase::new(&mock_api_key_service);
+        let response_model = use_case.interact().await;
+        assert!(response_model.is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+use thiserror::Error;
+use crate::domain::{
+    entities::pending_decryption::{ PendingDecryption, PendingDecryptionStatus },
+    services::pending_decryption_service::PendingDecryptionService,
+};
+
+pub struct ListPendingDecryptionsRequestModel {
+    pub tenant: String,
+}
+
+pub struct ListPendingDecryptionsResponseModel {
+    /// Decryptions of the tenant still waiting for approvals, oldest first.
+    pub pending_decryptions: Vec<PendingDecryption>,
+}
+
+#[derive(Error, Debug)]
+pub enum ListPendingDecryptionsError {
+    #[error("Unable to list pending decryptions. {0}")] PendingDecryptionServiceError(String),
+}
+
+pub struct ListPendingDecryptionsUseCase<'a> {
+    pending_decryption_service: &'a dyn PendingDecryptionService,
+}
+
+impl<'a> ListPendingDecryptionsUseCase<'a> {
+    pub fn new(pending_decryption_service: &'a dyn PendingDecryptionService) -> Self {
+        Self {
+            pending_decryption_service,
+        }
+    }
+
+    pub async fn interact(
+        &self,
+        request_model: ListPendingDecryptionsRequestModel
+    ) -> Result<ListPendingDecryptionsResponseModel, ListPendingDecryptionsError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut pending_decryptions: Vec<PendingDecryption> = self.pending_decryption_service
+            .list(request_model.tenant).await
+            .map_err(|e| ListPendingDecryptionsError::PendingDecryptionServiceError(e.to_string()))?
+            .into_iter()
+            .filter(|pending_decryption| {
+                pending_decryption.status_at(now) == PendingDecryptionStatus::Pending
+            })
+            .collect();
+        pending_decryptions.sort_by_key(|pending_decryption| pending_decryption.created_at);
+        Ok(ListPendingDecryptionsResponseModel { pending_decryptions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::pending_decryption_service::{
+        MockPendingDecryptionService,
+        PendingDecryptionServiceError,
+    };
+
+    fn pending_decryption(id: &str, expires_at: u64) -> PendingDecryption {
+        PendingDecryption {
+            id: id.to_string(),
+            tenant: "billing".to_string(),
+            requester: "billing-service".to_string(),
+            message: b"ciphertext".to_vec(),
+            associated_data: None,
+            reason: None,
+            required_approvals: 2,
+            approvers: Vec::new(),
+            denied_by: None,
+            created_at: 1000,
+            expires_at,
+            status: PendingDecryptionStatus::Pending,
+            decrypted_message: None,
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_list_pending_decryptions_use_case() {
+        let mut mock_pending_decryption_service = MockPendingDecryptionService::new();
+
+        mock_pending_decryption_service
+            .expect_list()
+            .withf(|tenant| tenant == "billing")
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move {
+                    Ok(vec![pending_decryption("0a1b", u64::MAX), pending_decryption("2c3d", 2000)])
+                })
+            });
+
+        let use_case = ListPendingDecryptionsUseCase::new(&mock_pending_decryption_service);
+        let request_model = ListPendingDecryptionsRequestModel { tenant: "billing".to_string() };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert_eq!(response_model.pending_decryptions.len(), 1);
+        assert_eq!(response_model.pending_decryptions[0].id, "0a1b");
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_list_pending_decryptions_use_case() {
+        let mut mock_pending_decryption_service = MockPendingDecryptionService::new();
+
+        mock_pending_decryption_service
+            .expect_list()
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move {
+                    Err(PendingDecryptionServiceError::StorageError("Error".to_string()))
+                })
+            });
+
+        let use_case = ListPendingDecryptionsUseCase::new(&mock_pending_decryption_service);
+        let request_model = ListPendingDecryptionsRequestModel { tenant: "billing".to_string() };
+        assert!(use_case.interact(request_model).await.is_err());
+    }
+}
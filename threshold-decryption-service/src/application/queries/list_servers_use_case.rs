@@ -0,0 +1,142 @@
+use thiserror::Error;
+use crate::domain::{
+    entities::decryption_server::{ KeyFingerprint, ServerState },
+    services::server_registry_service::ServerRegistryService,
+};
+
+pub struct ListServersRequestModel {
+    /// Fingerprints of the key shares each server should hold, by server id.
+    pub expected_key_fingerprints: Vec<Vec<KeyFingerprint>>,
+    pub now: u64,
+    pub silent_after_secs: u64,
+}
+
+pub struct ListServersResponseModel {
+    pub servers: Vec<ServerState>,
+}
+
+#[derive(Error, Debug)]
+pub enum ListServersError {
+    #[error("Unable to list servers from Server Registry Service. {0}")] ServerRegistryServiceError(
+        String,
+    ),
+}
+
+pub struct ListServersUseCase<'a> {
+    server_registry_service: &'a dyn ServerRegistryService,
+}
+
+impl<'a> ListServersUseCase<'a> {
+    pub fn new(server_registry_service: &'a dyn ServerRegistryService) -> Self {
+        Self {
+            server_registry_service,
+        }
+    }
+
+    /// State of every server of the cluster, by id, servers never seen included.
+    pub async fn interact(
+        &self,
+        request_model: ListServersRequestModel
+    ) -> Result<ListServersResponseModel, ListServersError> {
+        let mut registered_servers = self.server_registry_service
+            .list().await
+            .map_err(|e| ListServersError::ServerRegistryServiceError(e.to_string()))?;
+        let servers = request_model.expected_key_fingerprints
+            .iter()
+            .enumerate()
+            .map(|(id, expected_key_fingerprints)| {
+                let registration = registered_servers
+                    .iter()
+                    .position(|server| server.heartbeat.id == id)
+                    .map(|index| registered_servers.swap_remove(index));
+                ServerState::assess(
+                    id,
+                    registration,
+                    expected_key_fingerprints,
+                    request_model.now,
+                    request_model.silent_after_secs
+                )
+            })
+            .collect();
+        Ok(ListServersResponseModel { servers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        entities::decryption_server::{
+            RegisteredServer,
+            ServerHeartbeat,
+            ServerKeyState,
+            ServerLiveness,
+        },
+        services::server_registry_service::MockServerRegistryService,
+    };
+
+    fn fingerprint(key_id: &str, fingerprint: &str) -> KeyFingerprint {
+        KeyFingerprint {
+            key_id: key_id.to_string(),
+            fingerprint: fingerprint.to_string(),
+            public_key_fingerprint: "9f3a".to_string(),
+        }
+    }
+
+    fn registered_server(
+        id: usize,
+        key_fingerprints: Vec<KeyFingerprint>,
+        last_seen_at: u64
+    ) -> RegisteredServer {
+        RegisteredServer {
+            heartbeat: ServerHeartbeat {
+                id,
+                version: "0.1.0".to_string(),
+                identity_key: vec![id as u8; 32],
+                key_fingerprints,
+                load: 0,
+                sent_at: last_seen_at,
+            },
+            last_seen_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_list_servers_use_case() {
+        let mut mock_server_registry_service = MockServerRegistryService::new();
+
+        mock_server_registry_service
+            .expect_list()
+            .times(1)
+            .returning(|| {
+                Box::pin(async move {
+                    Ok(
+                        vec![
+                            registered_server(2, vec![fingerprint("0a1b", "2222")], 100),
+                            registered_server(0, vec![fingerprint("0a1b", "0000")], 130)
+                        ]
+                    )
+                })
+            });
+
+        let use_case = ListServersUseCase::new(&mock_server_registry_service);
+        let request_model = ListServersRequestModel {
+            expected_key_fingerprints: vec![
+                vec![fingerprint("0a1b", "0000")],
+                vec![fingerprint("0a1b", "1111")],
+                vec![fingerprint("0a1b", "2223"), fingerprint("3c4d", "2224")]
+            ],
+            now: 135,
+            silent_after_secs: 15,
+        };
+        let servers = use_case.interact(request_model).await.unwrap().servers;
+        assert_eq!(servers.len(), 3);
+        assert_eq!(servers[0].liveness, ServerLiveness::Alive);
+        assert_eq!(servers[0].key_state, ServerKeyState::Synced);
+        assert_eq!(servers[1].liveness, ServerLiveness::Unknown);
+        assert_eq!(servers[1].key_state, ServerKeyState::Unknown);
+        assert_eq!(servers[2].liveness, ServerLiveness::Silent);
+        assert_eq!(servers[2].key_state, ServerKeyState::Stale);
+        assert_eq!(servers[2].stale_key_ids, vec!["0a1b".to_string(), "3c4d".to_string()]);
+    }
+}
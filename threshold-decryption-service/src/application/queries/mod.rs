@@ -1 +1,7 @@
 pub mod get_public_key_use_case;
+pub mod query_audit_events_use_case;
+pub mod list_api_keys_use_case;
+pub mod list_pending_decryptions_use_case;
+pub mod get_pending_decryption_use_case;
+pub mod check_readiness_use_case;
+pub mod list_servers_use_case;
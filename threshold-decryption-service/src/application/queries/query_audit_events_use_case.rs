@@ -0,0 +1,108 @@
+use thiserror::Error;
+use crate::domain::{
+    entities::audit_event::{ AuditEvent, AuditQuery },
+    services::audit_trail_service::AuditTrailService,
+};
+
+pub struct QueryAuditEventsRequestModel {
+    pub query: AuditQuery,
+}
+
+pub struct QueryAuditEventsResponseModel {
+    pub events: Vec<AuditEvent>,
+}
+
+#[derive(Error, Debug)]
+pub enum QueryAuditEventsError {
+    #[error("Unable to query events from Audit Trail Service. {0}")] AuditTrailServiceError(
+        String,
+    ),
+}
+
+pub struct QueryAuditEventsUseCase<'a> {
+    audit_trail_service: &'a dyn AuditTrailService,
+}
+
+impl<'a> QueryAuditEventsUseCase<'a> {
+    pub fn new(audit_trail_service: &'a dyn AuditTrailService) -> Self {
+        Self {
+            audit_trail_service,
+        }
+    }
+
+    pub async fn interact(
+        &self,
+        request_model: QueryAuditEventsRequestModel
+    ) -> Result<QueryAuditEventsResponseModel, QueryAuditEventsError> {
+        let events = self.audit_trail_service
+            .query(request_model.query).await
+            .map_err(|e| QueryAuditEventsError::AuditTrailServiceError(e.to_string()))?;
+        Ok(QueryAuditEventsResponseModel { events })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        entities::audit_event::{ AuditOperation, AuditOutcome },
+        services::audit_trail_service::{ AuditTrailServiceError, MockAuditTrailService },
+    };
+
+    #[tokio::test]
+    async fn should_query_audit_events_use_case() {
+        let mut mock_audit_trail_service = MockAuditTrailService::new();
+
+        mock_audit_trail_service
+            .expect_query()
+            .withf(|query| query.operation == Some(AuditOperation::Decrypt))
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move {
+                    Ok(
+                        vec![AuditEvent {
+                            timestamp: 1,
+                            operation: AuditOperation::Decrypt,
+                            requester: None,
+                            ciphertext_fingerprint: None,
+                            participants: vec![1, 2],
+                            outcome: AuditOutcome::Success,
+                            error: None,
+                            latency_ms: 5,
+                        }]
+                    )
+                })
+            });
+
+        let use_case = QueryAuditEventsUseCase::new(&mock_audit_trail_service);
+        let request_model = QueryAuditEventsRequestModel {
+            query: AuditQuery {
+                operation: Some(AuditOperation::Decrypt),
+                ..Default::default()
+            },
+        };
+        let response_model = use_case.interact(request_model).await.unwrap();
+        assert_eq!(response_model.events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_query_audit_events_use_case() {
+        let mut mock_audit_trail_service = MockAuditTrailService::new();
+
+        mock_audit_trail_service
+            .expect_query()
+            .times(1)
+            .returning(|_| {
+                Box::pin(async move {
+                    Err(AuditTrailServiceError::QueryError("Error".to_string()))
+                })
+            });
+
+        let use_case = QueryAuditEventsUseCase::new(&mock_audit_trail_service);
+        let request_model = QueryAuditEventsRequestModel {
+            query: AuditQuery::default(),
+        };
+        let response_model = use_case.interact(request_model).await;
+        assert!(response_model.is_err());
+    }
+}
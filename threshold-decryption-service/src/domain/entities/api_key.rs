@@ -0,0 +1,59 @@
+use rand::RngCore;
+use serde::{ Deserialize, Serialize };
+use tiny_keccak::{ Hasher, Sha3 };
+use hex_fmt::HexFmt;
+
+/// Prefix of every API key secret, which lets the authorization guard tell API keys from JWTs.
+pub const API_KEY_PREFIX: &str = "tds_";
+
+/// An API key as stored by the service. Only the hash of the secret is kept at rest.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    pub owner: String,
+    pub key_hash: String,
+    pub created_at: u64,
+    pub expires_at: Option<u64>,
+    pub last_used_at: Option<u64>,
+    pub revoked_at: Option<u64>,
+    /// Scopes granted to the key, e.g. `encrypt` or `decrypt:<key id>`.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Tenant of the callers authenticated by the key.
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
+impl ApiKey {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
+
+fn random_hex(length: usize) -> String {
+    let mut bytes = vec![0u8; length];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    HexFmt(&bytes).to_string()
+}
+
+pub fn generate_api_key_id() -> String {
+    random_hex(8)
+}
+
+pub fn generate_api_key_secret() -> String {
+    format!("{}{}", API_KEY_PREFIX, random_hex(32))
+}
+
+/// API key secrets carry 256 bits of entropy, so a fast hash is enough to protect them at rest.
+pub fn hash_api_key_secret(secret: &str) -> String {
+    let mut digest = [0u8; 32];
+    let mut hasher = Sha3::v256();
+    hasher.update(secret.as_bytes());
+    hasher.finalize(&mut digest);
+    HexFmt(&digest).to_string()
+}
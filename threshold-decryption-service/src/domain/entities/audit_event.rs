@@ -0,0 +1,100 @@
+use std::str::FromStr;
+use serde::{ Deserialize, Serialize };
+use thiserror::Error;
+use tiny_keccak::{ Hasher, Sha3 };
+use hex_fmt::HexFmt;
+
+#[derive(Error, Debug)]
+pub enum AuditEventError {
+    #[error("Unsupported operation `{0}`. Expected encrypt or decrypt.")] UnsupportedOperation(
+        String,
+    ),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOperation {
+    Encrypt,
+    Decrypt,
+}
+
+impl AuditOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOperation::Encrypt => "encrypt",
+            AuditOperation::Decrypt => "decrypt",
+        }
+    }
+}
+
+impl FromStr for AuditOperation {
+    type Err = AuditEventError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "encrypt" => Ok(AuditOperation::Encrypt),
+            "decrypt" => Ok(AuditOperation::Decrypt),
+            _ => Err(AuditEventError::UnsupportedOperation(value.to_string())),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+impl AuditOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Failure => "failure",
+        }
+    }
+}
+
+/// A single encrypt or decrypt call handled by the service. Plaintexts are never part of an
+/// event, ciphertexts are only referenced through their fingerprint.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    pub timestamp: u64,
+    pub operation: AuditOperation,
+    pub requester: Option<String>,
+    pub ciphertext_fingerprint: Option<String>,
+    pub participants: Vec<usize>,
+    pub outcome: AuditOutcome,
+    pub error: Option<String>,
+    pub latency_ms: u64,
+}
+
+/// Filters applied when reading the audit trail. Every filter is optional.
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub requester: Option<String>,
+    pub operation: Option<AuditOperation>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        self.requester
+            .as_ref()
+            .is_none_or(|requester| event.requester.as_ref() == Some(requester)) &&
+            self.operation.is_none_or(|operation| event.operation == operation) &&
+            self.since.is_none_or(|since| event.timestamp >= since) &&
+            self.until.is_none_or(|until| event.timestamp < until)
+    }
+}
+
+/// SHA3-256 fingerprint identifying a ciphertext in the audit trail.
+pub fn ciphertext_fingerprint(ciphertext: &[u8]) -> String {
+    let mut digest = [0u8; 32];
+    let mut hasher = Sha3::v256();
+    hasher.update(ciphertext);
+    hasher.finalize(&mut digest);
+    format!("sha3-256:{}", HexFmt(&digest))
+}
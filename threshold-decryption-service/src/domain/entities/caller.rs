@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+use serde_json::Value;
+use crate::domain::entities::scope::{ Grant, Scope };
+
+/// Tenant of callers that are not assigned to any, and the only tenant of single-tenant
+/// deployments.
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Verified identity of a caller, built from the claims of its access token.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Caller {
+    pub subject: String,
+    pub issuer: Option<String>,
+    pub audience: Vec<String>,
+    pub expires_at: Option<u64>,
+    /// Tenant whose key set the caller uses, [`DEFAULT_TENANT`] when unset.
+    pub tenant: Option<String>,
+    /// Scopes granted to the caller, directly or through its roles.
+    pub grants: Vec<Grant>,
+    /// Every claim of the token, including the registered ones above.
+    pub claims: BTreeMap<String, Value>,
+}
+
+impl Caller {
+    pub fn claim(&self, name: &str) -> Option<&Value> {
+        self.claims.get(name)
+    }
+
+    pub fn tenant(&self) -> &str {
+        self.tenant.as_deref().unwrap_or(DEFAULT_TENANT)
+    }
+
+    /// Whether the caller may use `scope`, on the key `key_id` for key-qualified scopes.
+    pub fn is_allowed(&self, scope: Scope, key_id: Option<&str>) -> bool {
+        self.grants.iter().any(|grant| grant.allows(scope, key_id))
+    }
+}
@@ -0,0 +1,219 @@
+use std::{ fmt, io::{ Cursor, Read } };
+use byteorder::{ BigEndian, ReadBytesExt, WriteBytesExt };
+use hex_fmt::HexFmt;
+use thiserror::Error;
+
+pub const ENVELOPE_MAGIC: &[u8; 4] = b"TDCE";
+pub const ENVELOPE_VERSION: u8 = 1;
+pub const LEGACY_ENVELOPE_VERSION: u8 = 0;
+const FLAG_ASSOCIATED_DATA: u8 = 0b0000_0001;
+
+#[derive(Error, Debug)]
+pub enum CiphertextEnvelopeError {
+    #[error("Unsupported ciphertext envelope version {0}.")] UnsupportedVersion(u8),
+    #[error("Unsupported ciphertext scheme {0}.")] UnsupportedScheme(u8),
+    #[error("Malformed ciphertext envelope. {0}")] MalformedEnvelope(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiphertextScheme {
+    /// Threshold public key encryption over BLS12-381 as implemented by `threshold_crypto`.
+    ThresholdBls12381,
+}
+
+impl CiphertextScheme {
+    pub fn id(&self) -> u8 {
+        match self {
+            CiphertextScheme::ThresholdBls12381 => 1,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Result<Self, CiphertextEnvelopeError> {
+        match id {
+            1 => Ok(CiphertextScheme::ThresholdBls12381),
+            _ => Err(CiphertextEnvelopeError::UnsupportedScheme(id)),
+        }
+    }
+}
+
+/// Short fingerprint of the public key a ciphertext was produced under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyId(pub [u8; 8]);
+
+impl fmt::Display for KeyId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", HexFmt(&self.0))
+    }
+}
+
+/// Self-describing container for ciphertexts returned by the service.
+///
+/// Layout (integers are big-endian):
+///
+/// | field           | size     | notes                                        |
+/// |-----------------|----------|----------------------------------------------|
+/// | magic           | 4        | `TDCE`                                       |
+/// | version         | 1        | `1`                                          |
+/// | scheme id       | 1        | `1` = threshold BLS12-381                    |
+/// | key id          | 8        | first 8 bytes of SHA3-256 of the public key  |
+/// | flags           | 1        | bit 0 set when associated data is present    |
+/// | ad length       | 4        | only when the associated data flag is set    |
+/// | associated data | variable | only when the associated data flag is set    |
+/// | payload         | rest     | bincode-serialized scheme ciphertext         |
+///
+/// Bytes that do not start with the magic are decoded as a legacy (version 0) envelope whose
+/// payload is the whole input, which is what the service returned before envelopes existed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CiphertextEnvelope {
+    pub version: u8,
+    pub scheme: CiphertextScheme,
+    pub key_id: Option<KeyId>,
+    pub associated_data: Option<Vec<u8>>,
+    pub payload: Vec<u8>,
+}
+
+impl CiphertextEnvelope {
+    pub fn new(
+        scheme: CiphertextScheme,
+        key_id: KeyId,
+        associated_data: Option<Vec<u8>>,
+        payload: Vec<u8>
+    ) -> Self {
+        Self {
+            version: ENVELOPE_VERSION,
+            scheme,
+            key_id: Some(key_id),
+            associated_data,
+            payload,
+        }
+    }
+
+    pub fn is_legacy(&self) -> bool {
+        self.version == LEGACY_ENVELOPE_VERSION
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, CiphertextEnvelopeError> {
+        let key_id = self.key_id.ok_or_else(|| {
+            CiphertextEnvelopeError::MalformedEnvelope("Missing key id.".to_string())
+        })?;
+        let mut bytes = Vec::with_capacity(19 + self.payload.len());
+        bytes.extend_from_slice(ENVELOPE_MAGIC);
+        bytes.push(ENVELOPE_VERSION);
+        bytes.push(self.scheme.id());
+        bytes.extend_from_slice(&key_id.0);
+        match &self.associated_data {
+            Some(associated_data) => {
+                bytes.push(FLAG_ASSOCIATED_DATA);
+                let length = u32
+                    ::try_from(associated_data.len())
+                    .map_err(|e| CiphertextEnvelopeError::MalformedEnvelope(e.to_string()))?;
+                bytes
+                    .write_u32::<BigEndian>(length)
+                    .map_err(|e| CiphertextEnvelopeError::MalformedEnvelope(e.to_string()))?;
+                bytes.extend_from_slice(associated_data);
+            }
+            None => bytes.push(0),
+        }
+        bytes.extend_from_slice(&self.payload);
+        Ok(bytes)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, CiphertextEnvelopeError> {
+        if !bytes.starts_with(ENVELOPE_MAGIC) {
+            return Ok(Self {
+                version: LEGACY_ENVELOPE_VERSION,
+                scheme: CiphertextScheme::ThresholdBls12381,
+                key_id: None,
+                associated_data: None,
+                payload: bytes.to_vec(),
+            });
+        }
+        let malformed = |e: std::io::Error| {
+            CiphertextEnvelopeError::MalformedEnvelope(e.to_string())
+        };
+        let mut cursor = Cursor::new(&bytes[ENVELOPE_MAGIC.len()..]);
+        let version = cursor.read_u8().map_err(malformed)?;
+        if version != ENVELOPE_VERSION {
+            return Err(CiphertextEnvelopeError::UnsupportedVersion(version));
+        }
+        let scheme = CiphertextScheme::from_id(cursor.read_u8().map_err(malformed)?)?;
+        let mut key_id = [0u8; 8];
+        cursor.read_exact(&mut key_id).map_err(malformed)?;
+        let flags = cursor.read_u8().map_err(malformed)?;
+        let associated_data = if flags & FLAG_ASSOCIATED_DATA != 0 {
+            let length = cursor.read_u32::<BigEndian>().map_err(malformed)? as usize;
+            let remaining = bytes.len() - ENVELOPE_MAGIC.len() - (cursor.position() as usize);
+            if length > remaining {
+                return Err(
+                    CiphertextEnvelopeError::MalformedEnvelope(
+                        "Associated data length exceeds envelope size.".to_string()
+                    )
+                );
+            }
+            let mut associated_data = vec![0u8; length];
+            cursor.read_exact(&mut associated_data).map_err(malformed)?;
+            Some(associated_data)
+        } else {
+            None
+        };
+        let mut payload = Vec::new();
+        cursor.read_to_end(&mut payload).map_err(malformed)?;
+        if payload.is_empty() {
+            return Err(CiphertextEnvelopeError::MalformedEnvelope("Empty payload.".to_string()));
+        }
+        Ok(Self {
+            version,
+            scheme,
+            key_id: Some(KeyId(key_id)),
+            associated_data,
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_encode_and_decode_envelope() {
+        let envelope = CiphertextEnvelope::new(
+            CiphertextScheme::ThresholdBls12381,
+            KeyId([1, 2, 3, 4, 5, 6, 7, 8]),
+            Some(b"tenant=acme".to_vec()),
+            vec![9, 9, 9]
+        );
+        let decoded = CiphertextEnvelope::decode(&envelope.encode().unwrap()).unwrap();
+        assert_eq!(decoded, envelope);
+        assert!(!decoded.is_legacy());
+    }
+
+    #[test]
+    fn should_decode_legacy_ciphertext() {
+        let decoded = CiphertextEnvelope::decode(&[42, 0, 0, 0, 1]).unwrap();
+        assert!(decoded.is_legacy());
+        assert_eq!(decoded.key_id, None);
+        assert_eq!(decoded.payload, vec![42, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn should_fail_to_decode_unsupported_version() {
+        let mut bytes = ENVELOPE_MAGIC.to_vec();
+        bytes.extend_from_slice(&[7, 1]);
+        let decoded = CiphertextEnvelope::decode(&bytes);
+        assert!(matches!(decoded, Err(CiphertextEnvelopeError::UnsupportedVersion(7))));
+    }
+
+    #[test]
+    fn should_fail_to_decode_truncated_envelope() {
+        let envelope = CiphertextEnvelope::new(
+            CiphertextScheme::ThresholdBls12381,
+            KeyId([0; 8]),
+            Some(vec![1; 32]),
+            vec![1]
+        );
+        let bytes = envelope.encode().unwrap();
+        let decoded = CiphertextEnvelope::decode(&bytes[..20]);
+        assert!(decoded.is_err());
+    }
+}
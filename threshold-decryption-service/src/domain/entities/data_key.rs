@@ -0,0 +1,81 @@
+use std::{ collections::BTreeMap, str::FromStr };
+use serde::{ Deserialize, Serialize };
+use thiserror::Error;
+
+pub type EncryptionContext = BTreeMap<String, String>;
+
+const ENCRYPTION_CONTEXT_PREFIX: &str = "encryption-context:";
+
+/// Associated data binding a data key to a non-empty `encryption_context`, for the Decryption
+/// Servers to refuse other contexts before producing any share. The context is encoded as JSON,
+/// sorted by key, after a prefix telling it apart from the associated data of messages, e.g.
+/// `encryption-context:{"tenant":"acme"}`.
+pub fn encryption_context_associated_data(
+    encryption_context: &EncryptionContext
+) -> Option<Vec<u8>> {
+    if encryption_context.is_empty() {
+        return None;
+    }
+    let mut associated_data = ENCRYPTION_CONTEXT_PREFIX.as_bytes().to_vec();
+    associated_data.extend(serde_json::to_vec(encryption_context).unwrap());
+    Some(associated_data)
+}
+
+#[derive(Error, Debug)]
+pub enum DataKeyError {
+    #[error("Unsupported key spec `{0}`. Expected AES_128 or AES_256.")] UnsupportedKeySpec(String),
+    #[error("Invalid data key encoding. {0}")] InvalidEncoding(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeySpec {
+    Aes128,
+    #[default]
+    Aes256,
+}
+
+impl KeySpec {
+    pub fn key_length(&self) -> usize {
+        match self {
+            KeySpec::Aes128 => 16,
+            KeySpec::Aes256 => 32,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeySpec::Aes128 => "AES_128",
+            KeySpec::Aes256 => "AES_256",
+        }
+    }
+}
+
+impl FromStr for KeySpec {
+    type Err = DataKeyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "AES_128" => Ok(KeySpec::Aes128),
+            "AES_256" => Ok(KeySpec::Aes256),
+            _ => Err(DataKeyError::UnsupportedKeySpec(value.to_string())),
+        }
+    }
+}
+
+/// The plaintext that is threshold-encrypted for a data key. The encryption context is sealed
+/// together with the key as well as bound to its ciphertext as associated data.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DataKey {
+    pub key: Vec<u8>,
+    pub encryption_context: EncryptionContext,
+}
+
+impl DataKey {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, DataKeyError> {
+        bincode::serialize(self).map_err(|e| DataKeyError::InvalidEncoding(e.to_string()))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DataKeyError> {
+        bincode::deserialize(bytes).map_err(|e| DataKeyError::InvalidEncoding(e.to_string()))
+    }
+}
@@ -0,0 +1,170 @@
+/// Fingerprint of the public key share a Decryption Server holds for a key set, the first 16 bytes
+/// of its SHA-256 in hex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyFingerprint {
+    pub key_id: String,
+    pub fingerprint: String,
+    /// Fingerprint of the public key of the key set the share was verified against.
+    pub public_key_fingerprint: String,
+}
+
+/// Key ids of `expected_key_fingerprints` whose share is missing from, or differs in,
+/// `key_fingerprints`.
+pub fn stale_key_ids(
+    expected_key_fingerprints: &[KeyFingerprint],
+    key_fingerprints: &[KeyFingerprint]
+) -> Vec<String> {
+    expected_key_fingerprints
+        .iter()
+        .filter(|expected| !key_fingerprints.contains(expected))
+        .map(|expected| expected.key_id.clone())
+        .collect()
+}
+
+/// Heartbeat a Decryption Server publishes periodically, signed with its identity key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerHeartbeat {
+    pub id: usize,
+    pub version: String,
+    /// Ed25519 public key the heartbeat is signed with.
+    pub identity_key: Vec<u8>,
+    pub key_fingerprints: Vec<KeyFingerprint>,
+    /// Messages the server consumed since its previous heartbeat.
+    pub load: u64,
+    pub sent_at: u64,
+}
+
+/// Confirmation from a Decryption Server that it loaded the key shares of a key sync, signed with
+/// its identity key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySyncAcknowledgement {
+    pub id: usize,
+    /// Id of the key sync acknowledged, `None` for syncs sent without one.
+    pub sync_id: Option<String>,
+    pub identity_key: Vec<u8>,
+    pub key_fingerprints: Vec<KeyFingerprint>,
+    /// Key sets whose share did not match their public key set, refusing the whole sync.
+    pub mismatched_key_ids: Vec<String>,
+    pub sent_at: u64,
+}
+
+/// Request of a Decryption Server started without key shares to be sent its shares, signed with
+/// its identity key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyShareRequest {
+    pub id: usize,
+    pub identity_key: Vec<u8>,
+    pub sent_at: u64,
+}
+
+/// Refusal of a Decryption Server to produce its share of a decryption, signed with its identity
+/// key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecryptionRefusal {
+    pub id: usize,
+    /// Id of the refused request, `None` when the request carried none.
+    pub request_id: Option<String>,
+    pub identity_key: Vec<u8>,
+    /// Machine-readable reason: `stale`, `policy`, `no_share`, `invalid_ciphertext` or
+    /// `not_approved`.
+    pub reason: String,
+    pub detail: String,
+}
+
+/// Latest heartbeat of a Decryption Server and when it was received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredServer {
+    pub heartbeat: ServerHeartbeat,
+    pub last_seen_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerLiveness {
+    Alive,
+    /// Seen before, but no heartbeat was received recently.
+    Silent,
+    /// Never seen since the service started.
+    Unknown,
+}
+
+impl ServerLiveness {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServerLiveness::Alive => "alive",
+            ServerLiveness::Silent => "silent",
+            ServerLiveness::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerKeyState {
+    Synced,
+    /// Some key shares are missing or differ from the key sets of the service.
+    Stale,
+    Unknown,
+}
+
+impl ServerKeyState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServerKeyState::Synced => "synced",
+            ServerKeyState::Stale => "stale",
+            ServerKeyState::Unknown => "unknown",
+        }
+    }
+}
+
+/// State of a Decryption Server, as known from its heartbeats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerState {
+    pub id: usize,
+    pub liveness: ServerLiveness,
+    pub key_state: ServerKeyState,
+    /// Key sets whose share the server is missing or holds an outdated version of.
+    pub stale_key_ids: Vec<String>,
+    pub registration: Option<RegisteredServer>,
+}
+
+impl ServerState {
+    /// Compares the last heartbeat of server `id` with the fingerprints of the shares it should
+    /// hold. Servers are silent once no heartbeat was received for `silent_after_secs`.
+    pub fn assess(
+        id: usize,
+        registration: Option<RegisteredServer>,
+        expected_key_fingerprints: &[KeyFingerprint],
+        now: u64,
+        silent_after_secs: u64
+    ) -> Self {
+        let Some(registration) = registration else {
+            return Self {
+                id,
+                liveness: ServerLiveness::Unknown,
+                key_state: ServerKeyState::Unknown,
+                stale_key_ids: Vec::new(),
+                registration: None,
+            };
+        };
+        let liveness = if now.saturating_sub(registration.last_seen_at) > silent_after_secs {
+            ServerLiveness::Silent
+        } else {
+            ServerLiveness::Alive
+        };
+        let stale_key_ids = stale_key_ids(
+            expected_key_fingerprints,
+            &registration.heartbeat.key_fingerprints
+        );
+        let key_state = if stale_key_ids.is_empty() {
+            ServerKeyState::Synced
+        } else {
+            ServerKeyState::Stale
+        };
+        Self {
+            id,
+            liveness,
+            key_state,
+            stale_key_ids,
+            registration: Some(registration),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod data_key;
+pub mod ciphertext_envelope;
+pub mod audit_event;
+pub mod caller;
+pub mod api_key;
+pub mod scope;
+pub mod pending_decryption;
+pub mod readiness;
+pub mod decryption_server;
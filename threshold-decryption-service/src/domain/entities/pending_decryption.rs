@@ -0,0 +1,200 @@
+use rand::RngCore;
+use serde::{ Deserialize, Serialize };
+use thiserror::Error;
+use hex_fmt::HexFmt;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ApprovalError {
+    #[error("Requesters cannot approve or deny their own decryptions.")] SelfApproval,
+    #[error("`{0}` already approved this decryption.")] AlreadyApproved(String),
+    #[error("The decryption is {0}, not pending.")] NotPending(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingDecryptionStatus {
+    Pending,
+    /// Approved by enough approvers and dispatched to the Decryption Servers.
+    Approved,
+    Completed,
+    Failed,
+    Denied,
+    Expired,
+}
+
+impl PendingDecryptionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PendingDecryptionStatus::Pending => "pending",
+            PendingDecryptionStatus::Approved => "approved",
+            PendingDecryptionStatus::Completed => "completed",
+            PendingDecryptionStatus::Failed => "failed",
+            PendingDecryptionStatus::Denied => "denied",
+            PendingDecryptionStatus::Expired => "expired",
+        }
+    }
+}
+
+/// A decision taken by an approver on a pending decryption.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    Approve(String),
+    Deny(String),
+}
+
+/// When decryptions must be approved, by how many approvers and for how long they wait.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalPolicy {
+    pub required_approvals: usize,
+    pub expires_in_secs: u64,
+    /// Tenants whose decryptions always wait for approval, whether requested or not.
+    pub mandatory_tenants: Vec<String>,
+}
+
+impl Default for ApprovalPolicy {
+    fn default() -> Self {
+        Self {
+            required_approvals: 2,
+            expires_in_secs: 3600,
+            mandatory_tenants: Vec::new(),
+        }
+    }
+}
+
+impl ApprovalPolicy {
+    pub fn requires_approval(&self, tenant: &str, requested: bool) -> bool {
+        requested ||
+            self.mandatory_tenants.iter().any(|mandatory_tenant| mandatory_tenant == tenant)
+    }
+}
+
+/// A decryption held back until `required_approvals` distinct approvers of its tenant approve it.
+/// The plaintext is kept for the requester until the request expires.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PendingDecryption {
+    pub id: String,
+    pub tenant: String,
+    pub requester: String,
+    pub message: Vec<u8>,
+    pub associated_data: Option<Vec<u8>>,
+    pub reason: Option<String>,
+    pub required_approvals: usize,
+    pub approvers: Vec<String>,
+    pub denied_by: Option<String>,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub status: PendingDecryptionStatus,
+    pub decrypted_message: Option<String>,
+    pub error: Option<String>,
+}
+
+impl PendingDecryption {
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Status at time `now`, pending requests past their expiry being expired.
+    pub fn status_at(&self, now: u64) -> PendingDecryptionStatus {
+        match self.status {
+            PendingDecryptionStatus::Pending if self.is_expired(now) => {
+                PendingDecryptionStatus::Expired
+            }
+            status => status,
+        }
+    }
+
+    /// Applies `decision`, moving the request to `Approved` once enough distinct approvers agreed.
+    pub fn decide(&mut self, decision: &ApprovalDecision, now: u64) -> Result<(), ApprovalError> {
+        let status = self.status_at(now);
+        if status != PendingDecryptionStatus::Pending {
+            self.status = status;
+            return Err(ApprovalError::NotPending(status.as_str().to_string()));
+        }
+        match decision {
+            ApprovalDecision::Approve(approver) | ApprovalDecision::Deny(approver) if
+                *approver == self.requester
+            => Err(ApprovalError::SelfApproval),
+            ApprovalDecision::Approve(approver) => {
+                if self.approvers.contains(approver) {
+                    return Err(ApprovalError::AlreadyApproved(approver.clone()));
+                }
+                self.approvers.push(approver.clone());
+                if self.approvers.len() >= self.required_approvals {
+                    self.status = PendingDecryptionStatus::Approved;
+                }
+                Ok(())
+            }
+            ApprovalDecision::Deny(approver) => {
+                self.denied_by = Some(approver.clone());
+                self.status = PendingDecryptionStatus::Denied;
+                Ok(())
+            }
+        }
+    }
+}
+
+pub fn generate_pending_decryption_id() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    HexFmt(&bytes).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending_decryption() -> PendingDecryption {
+        PendingDecryption {
+            id: generate_pending_decryption_id(),
+            tenant: "billing".to_string(),
+            requester: "billing-service".to_string(),
+            message: b"ciphertext".to_vec(),
+            associated_data: None,
+            reason: None,
+            required_approvals: 2,
+            approvers: Vec::new(),
+            denied_by: None,
+            created_at: 1000,
+            expires_at: 2000,
+            status: PendingDecryptionStatus::Pending,
+            decrypted_message: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn should_approve_once_enough_distinct_approvers_agree() {
+        let mut pending_decryption = pending_decryption();
+        let alice = ApprovalDecision::Approve("alice".to_string());
+
+        assert_eq!(
+            pending_decryption.decide(&ApprovalDecision::Approve("billing-service".into()), 1100),
+            Err(ApprovalError::SelfApproval)
+        );
+        assert_eq!(pending_decryption.decide(&alice, 1100), Ok(()));
+        assert_eq!(
+            pending_decryption.decide(&alice, 1100),
+            Err(ApprovalError::AlreadyApproved("alice".to_string()))
+        );
+        assert_eq!(pending_decryption.status, PendingDecryptionStatus::Pending);
+        let bob = ApprovalDecision::Approve("bob".to_string());
+        assert_eq!(pending_decryption.decide(&bob, 1100), Ok(()));
+        assert_eq!(pending_decryption.status, PendingDecryptionStatus::Approved);
+    }
+
+    #[test]
+    fn should_reject_decisions_on_expired_or_denied_decryptions() {
+        let mut pending_decryption = pending_decryption();
+        let alice = ApprovalDecision::Approve("alice".to_string());
+
+        assert!(pending_decryption.decide(&alice, 2000).is_err());
+        assert_eq!(pending_decryption.status, PendingDecryptionStatus::Expired);
+
+        let mut pending_decryption = self::pending_decryption();
+        assert_eq!(pending_decryption.decide(&ApprovalDecision::Deny("bob".into()), 1100), Ok(()));
+        assert_eq!(
+            pending_decryption.decide(&alice, 1100),
+            Err(ApprovalError::NotPending("denied".to_string()))
+        );
+    }
+}
@@ -0,0 +1,85 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadinessStatus {
+    Ready,
+    /// Enough Decryption Servers for a quorum, but not all of them.
+    Degraded,
+    Unavailable,
+}
+
+impl ReadinessStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadinessStatus::Ready => "ready",
+            ReadinessStatus::Degraded => "degraded",
+            ReadinessStatus::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// State of a Decryption Server, as reported by its status endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerReadiness {
+    pub id: usize,
+    pub reachable: bool,
+    pub broker_connected: bool,
+    pub key_shares_loaded: bool,
+    /// Key sets whose share the server is missing or holds an outdated version of, e.g. a share
+    /// from a previous run of the service, unable to produce shares that verify.
+    pub stale_key_ids: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl ServerReadiness {
+    pub fn unreachable(id: usize, error: String) -> Self {
+        Self {
+            id,
+            reachable: false,
+            broker_connected: false,
+            key_shares_loaded: false,
+            stale_key_ids: Vec::new(),
+            error: Some(error),
+        }
+    }
+
+    /// Whether the server can contribute a decryption share.
+    pub fn is_available(&self) -> bool {
+        self.reachable &&
+            self.broker_connected &&
+            self.key_shares_loaded &&
+            self.stale_key_ids.is_empty()
+    }
+}
+
+/// Whether decryptions can currently reach a quorum of threshold + 1 Decryption Servers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Readiness {
+    pub status: ReadinessStatus,
+    pub broker_connected: bool,
+    pub required_servers: usize,
+    pub available_servers: usize,
+    pub servers: Vec<ServerReadiness>,
+}
+
+impl Readiness {
+    pub fn assess(broker_connected: bool, threshold: usize, servers: Vec<ServerReadiness>) -> Self {
+        let required_servers = threshold + 1;
+        let available_servers = servers
+            .iter()
+            .filter(|server| server.is_available())
+            .count();
+        let status = if !broker_connected || available_servers < required_servers {
+            ReadinessStatus::Unavailable
+        } else if available_servers < servers.len() {
+            ReadinessStatus::Degraded
+        } else {
+            ReadinessStatus::Ready
+        };
+        Self {
+            status,
+            broker_connected,
+            required_servers,
+            available_servers,
+            servers,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{ fmt, str::FromStr };
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ScopeError {
+    #[error(
+        "Unsupported scope `{0}`. Expected encrypt[:<kid>], decrypt[:<kid>], keys:read, approve \
+         or admin."
+    )] UnsupportedScope(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Encrypt,
+    Decrypt,
+    KeysRead,
+    /// Approving or denying the pending decryptions of the caller's tenant.
+    Approve,
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Encrypt => "encrypt",
+            Scope::Decrypt => "decrypt",
+            Scope::KeysRead => "keys:read",
+            Scope::Approve => "approve",
+            Scope::Admin => "admin",
+        }
+    }
+
+    /// Whether the scope can be restricted to a single key, as in `encrypt:<key id>`.
+    pub fn is_key_qualified(&self) -> bool {
+        matches!(self, Scope::Encrypt | Scope::Decrypt)
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ScopeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "encrypt" => Ok(Scope::Encrypt),
+            "decrypt" => Ok(Scope::Decrypt),
+            "keys:read" => Ok(Scope::KeysRead),
+            "approve" => Ok(Scope::Approve),
+            "admin" => Ok(Scope::Admin),
+            _ => Err(ScopeError::UnsupportedScope(value.to_string())),
+        }
+    }
+}
+
+/// A scope granted to a caller, either for every key or for a single key id.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grant {
+    pub scope: Scope,
+    pub key_id: Option<String>,
+}
+
+impl Grant {
+    pub fn allows(&self, scope: Scope, key_id: Option<&str>) -> bool {
+        self.scope == scope &&
+            self.key_id.as_deref().is_none_or(|granted_key_id| Some(granted_key_id) == key_id)
+    }
+}
+
+impl FromStr for Grant {
+    type Err = ScopeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Ok(scope) = value.parse::<Scope>() {
+            return Ok(Grant { scope, key_id: None });
+        }
+        match value.rsplit_once(':') {
+            Some((scope, key_id)) if !key_id.is_empty() => {
+                let scope = scope.parse::<Scope>()?;
+                if !scope.is_key_qualified() {
+                    return Err(ScopeError::UnsupportedScope(value.to_string()));
+                }
+                Ok(Grant {
+                    scope,
+                    key_id: Some(key_id.to_string()),
+                })
+            }
+            _ => Err(ScopeError::UnsupportedScope(value.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Grant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.key_id {
+            Some(key_id) => write!(f, "{}:{}", self.scope, key_id),
+            None => write!(f, "{}", self.scope),
+        }
+    }
+}
+
+/// Scopes granted by the roles carried in a token.
+pub fn scopes_for_role(role: &str) -> &'static [Scope] {
+    match role {
+        "admin" => &[Scope::Admin, Scope::KeysRead],
+        "encryptor" => &[Scope::Encrypt, Scope::KeysRead],
+        "decryptor" => &[Scope::Encrypt, Scope::Decrypt, Scope::KeysRead],
+        "approver" => &[Scope::Approve],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_allow_key_qualified_grants_only_for_their_key() {
+        let grant: Grant = "encrypt:8c1f0b2d4e6a7c90".parse().unwrap();
+        assert!(grant.allows(Scope::Encrypt, Some("8c1f0b2d4e6a7c90")));
+        assert!(!grant.allows(Scope::Encrypt, Some("0000000000000000")));
+        assert!(!grant.allows(Scope::Decrypt, Some("8c1f0b2d4e6a7c90")));
+        assert!("decrypt".parse::<Grant>().unwrap().allows(Scope::Decrypt, Some("any")));
+    }
+
+    #[test]
+    fn should_reject_unknown_scopes() {
+        assert!("delete".parse::<Grant>().is_err());
+        assert!("admin:8c1f0b2d4e6a7c90".parse::<Grant>().is_err());
+        assert!("encrypt:".parse::<Grant>().is_err());
+    }
+}
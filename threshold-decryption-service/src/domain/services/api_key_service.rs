@@ -0,0 +1,19 @@
+use thiserror::Error;
+use mockall::automock;
+use async_trait::async_trait;
+use crate::domain::entities::api_key::ApiKey;
+
+#[derive(Error, Debug)]
+pub enum ApiKeyServiceError {
+    #[error("Unable to store API key. {0}")] StorageError(String),
+}
+
+#[async_trait]
+#[automock]
+pub trait ApiKeyService: Sync + Send {
+    /// Inserts the API key, or replaces the stored API key with the same id.
+    async fn save(&self, api_key: ApiKey) -> Result<(), ApiKeyServiceError>;
+    async fn find(&self, id: String) -> Result<Option<ApiKey>, ApiKeyServiceError>;
+    async fn find_by_hash(&self, key_hash: String) -> Result<Option<ApiKey>, ApiKeyServiceError>;
+    async fn list(&self) -> Result<Vec<ApiKey>, ApiKeyServiceError>;
+}
@@ -0,0 +1,17 @@
+use thiserror::Error;
+use mockall::automock;
+use async_trait::async_trait;
+use crate::domain::entities::audit_event::{ AuditEvent, AuditQuery };
+
+#[derive(Error, Debug)]
+pub enum AuditTrailServiceError {
+    #[error("Unable to record audit event. {0}")] RecordError(String),
+    #[error("Unable to query audit events. {0}")] QueryError(String),
+}
+
+#[async_trait]
+#[automock]
+pub trait AuditTrailService: Sync + Send {
+    async fn record(&self, event: AuditEvent) -> Result<(), AuditTrailServiceError>;
+    async fn query(&self, query: AuditQuery) -> Result<Vec<AuditEvent>, AuditTrailServiceError>;
+}
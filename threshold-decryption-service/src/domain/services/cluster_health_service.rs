@@ -0,0 +1,12 @@
+use mockall::automock;
+use async_trait::async_trait;
+use crate::domain::entities::readiness::ServerReadiness;
+
+#[async_trait]
+#[automock]
+pub trait ClusterHealthService: Sync + Send {
+    /// Whether the message broker is reachable, reconnecting to it when the connection was lost.
+    async fn is_broker_connected(&self) -> bool;
+    /// State of every Decryption Server, by id, unreachable servers included.
+    async fn probe_servers(&self) -> Vec<ServerReadiness>;
+}
@@ -7,12 +7,76 @@ pub enum CryptographyServiceError {
     #[error("Unable to share public key. {0}")] PublicKeySharingError(String),
     #[error("Unable to decrypt message. {0}")] DecryptionError(String),
     #[error("Unable to encrypt message. {0}")] EncryptionError(String),
+    #[error("Unable to generate data key. {0}")] DataKeyGenerationError(String),
+    #[error("Unknown tenant `{0}`.")] UnknownTenant(String),
+    #[error("Associated data does not match the ciphertext.")] AssociatedDataMismatch,
+    #[error(
+        "Not enough Decryption Servers can produce a share. {}",
+        ServerRefusal::describe_all(.0)
+    )] QuorumUnreachable(Vec<ServerRefusal>),
+}
+
+/// Refusal of a Decryption Server to produce its share of a decryption.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerRefusal {
+    pub server_id: usize,
+    /// Machine-readable reason of the refusal.
+    pub reason: String,
+    pub detail: String,
+}
+
+impl ServerRefusal {
+    fn describe_all(refusals: &[ServerRefusal]) -> String {
+        refusals
+            .iter()
+            .map(|refusal| {
+                format!(
+                    "Server {} refused ({}): {}.",
+                    refusal.server_id,
+                    refusal.reason,
+                    refusal.detail
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecryptionContext {
+    /// Tenant whose key set must have produced the ciphertext.
+    pub tenant: String,
+    pub associated_data: Option<Vec<u8>>,
+    pub requester: Option<String>,
+    pub reason: Option<String>,
+    /// Id of the HTTP request the decryption serves, propagated to the Decryption Servers.
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecryptedMessage {
+    pub plaintext: Vec<u8>,
+    /// Ids of the Decryption Servers whose shares were combined.
+    pub participants: Vec<usize>,
 }
 
 #[async_trait]
 #[automock]
 pub trait CryptographyService: Sync + Send {
-    async fn share_public_key(&self) -> Result<Vec<u8>, CryptographyServiceError>;
-    async fn decrypt_message(&self, message: Vec<u8>) -> Result<Vec<u8>, CryptographyServiceError>;
-    async fn encrypt_message(&self, message: String) -> Result<Vec<u8>, CryptographyServiceError>;
+    async fn share_public_key(&self, tenant: String) -> Result<Vec<u8>, CryptographyServiceError>;
+    async fn decrypt_message(
+        &self,
+        message: Vec<u8>,
+        context: DecryptionContext
+    ) -> Result<DecryptedMessage, CryptographyServiceError>;
+    async fn encrypt_message(
+        &self,
+        tenant: String,
+        message: Vec<u8>,
+        associated_data: Option<Vec<u8>>
+    ) -> Result<Vec<u8>, CryptographyServiceError>;
+    async fn generate_data_key(
+        &self,
+        key_length: usize
+    ) -> Result<Vec<u8>, CryptographyServiceError>;
 }
@@ -1 +1,6 @@
 pub mod cryptography_service;
+pub mod audit_trail_service;
+pub mod api_key_service;
+pub mod pending_decryption_service;
+pub mod cluster_health_service;
+pub mod server_registry_service;
@@ -0,0 +1,37 @@
+use thiserror::Error;
+use mockall::automock;
+use async_trait::async_trait;
+use crate::domain::entities::pending_decryption::{ ApprovalDecision, PendingDecryption };
+
+#[derive(Error, Debug)]
+pub enum PendingDecryptionServiceError {
+    #[error("Unable to store pending decryption. {0}")] StorageError(String),
+    #[error("Pending decryption `{0}` not found.")] NotFound(String),
+    #[error("{0}")] RejectedDecision(String),
+}
+
+#[async_trait]
+#[automock]
+pub trait PendingDecryptionService: Sync + Send {
+    /// Inserts the pending decryption, or replaces the stored one with the same id.
+    async fn save(
+        &self,
+        pending_decryption: PendingDecryption
+    ) -> Result<(), PendingDecryptionServiceError>;
+    async fn find(
+        &self,
+        id: String
+    ) -> Result<Option<PendingDecryption>, PendingDecryptionServiceError>;
+    async fn list(
+        &self,
+        tenant: String
+    ) -> Result<Vec<PendingDecryption>, PendingDecryptionServiceError>;
+    /// Applies `decision` to the stored pending decryption atomically, so that concurrent
+    /// approvals are neither lost nor dispatched twice, and returns the updated request.
+    async fn decide(
+        &self,
+        id: String,
+        decision: ApprovalDecision,
+        now: u64
+    ) -> Result<PendingDecryption, PendingDecryptionServiceError>;
+}
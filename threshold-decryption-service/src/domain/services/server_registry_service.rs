@@ -0,0 +1,25 @@
+use thiserror::Error;
+use mockall::automock;
+use async_trait::async_trait;
+use crate::domain::entities::decryption_server::RegisteredServer;
+
+#[derive(Error, Debug)]
+pub enum ServerRegistryServiceError {
+    #[error("Unable to store Decryption Server. {0}")] StorageError(String),
+}
+
+#[async_trait]
+#[automock]
+pub trait ServerRegistryService: Sync + Send {
+    /// Key the heartbeats of server `id` must be signed with, either enrolled by the operator or
+    /// pinned from the first heartbeat of the server.
+    async fn identity_key(&self, id: usize) -> Result<Option<Vec<u8>>, ServerRegistryServiceError>;
+    /// Key server `id` was enrolled with by the operator, ignoring keys pinned from heartbeats.
+    async fn enrolled_identity_key(
+        &self,
+        id: usize
+    ) -> Result<Option<Vec<u8>>, ServerRegistryServiceError>;
+    /// Inserts the server, or replaces the stored server with the same id.
+    async fn save(&self, server: RegisteredServer) -> Result<(), ServerRegistryServiceError>;
+    async fn list(&self) -> Result<Vec<RegisteredServer>, ServerRegistryServiceError>;
+}
@@ -0,0 +1,105 @@
+use rocket::request::{ Outcome, Request, FromRequest };
+use rocket::http::Status;
+use rocket_okapi::okapi::openapi3::{
+    Object,
+    SecurityRequirement,
+    SecurityScheme,
+    SecuritySchemeData,
+};
+use rocket_okapi::{ gen::OpenApiGenerator, request::{ OpenApiFromRequest, RequestHeaderInput } };
+use ring::constant_time::verify_slices_are_equal;
+use tiny_keccak::{ Hasher, Sha3 };
+use crate::{
+    domain::entities::scope::Scope,
+    infrastructure::guards::authorization_request_guard::AuthorizationHeader,
+};
+
+fn digest(token: &str) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    let mut hasher = Sha3::v256();
+    hasher.update(token.as_bytes());
+    hasher.finalize(&mut digest);
+    digest
+}
+
+/// The token protecting the admin routes, configured through `ADMIN_TOKEN`.
+pub struct AdminToken {
+    digest: [u8; 32],
+}
+
+impl AdminToken {
+    pub fn new(token: &str) -> Self {
+        Self { digest: digest(token) }
+    }
+
+    fn matches(&self, token: &str) -> bool {
+        verify_slices_are_equal(&self.digest, &digest(token)).is_ok()
+    }
+}
+
+/// Guard of every admin route, accepting the admin token as well as credentials granting the
+/// `admin` scope.
+#[derive(Debug)]
+pub struct AdminAuthorizationHeader;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminAuthorizationHeader {
+    type Error = (Status, String);
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let token = request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|token| token.strip_prefix("Bearer "));
+        let Some(token) = token else {
+            let error = String::from("No admin token provided");
+            return Outcome::Error((Status::Unauthorized, (Status::Unauthorized, error)));
+        };
+        let admin_token = request.rocket().state::<AdminToken>();
+        if admin_token.is_some_and(|admin_token| admin_token.matches(token)) {
+            return Outcome::Success(AdminAuthorizationHeader);
+        }
+
+        match request.guard::<AuthorizationHeader>().await {
+            Outcome::Success(authorization) if
+                authorization.caller.is_allowed(Scope::Admin, None)
+            => Outcome::Success(AdminAuthorizationHeader),
+            Outcome::Success(_) => {
+                let error = String::from("The `admin` scope is required");
+                Outcome::Error((Status::Forbidden, (Status::Forbidden, error)))
+            }
+            _ => {
+                let error = String::from("Invalid admin token");
+                Outcome::Error((Status::Unauthorized, (Status::Unauthorized, error)))
+            }
+        }
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for AdminAuthorizationHeader {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        let security_scheme = SecurityScheme {
+            description: Some(
+                "Requires the admin token, or a token granting the `admin` scope.".to_owned()
+            ),
+            data: SecuritySchemeData::Http {
+                scheme: "bearer".to_owned(),
+                bearer_format: Some("bearer".to_owned()),
+            },
+            extensions: Object::default(),
+        };
+        let mut security_req = SecurityRequirement::new();
+        security_req.insert("AdminAuthorizationHeader".to_owned(), vec!["admin".to_owned()]);
+        Ok(
+            RequestHeaderInput::Security(
+                "AdminAuthorizationHeader".to_owned(),
+                security_scheme,
+                security_req
+            )
+        )
+    }
+}
@@ -1,5 +1,6 @@
 use rocket::request::{ Outcome, Request, FromRequest };
 use rocket::http::Status;
+use rocket::mtls::Certificate;
 use rocket_okapi::okapi::openapi3::{
     Object,
     SecurityRequirement,
@@ -7,28 +8,131 @@ use rocket_okapi::okapi::openapi3::{
     SecuritySchemeData,
 };
 use rocket_okapi::{ gen::OpenApiGenerator, request::{ OpenApiFromRequest, RequestHeaderInput } };
+use serde_json::Value;
+use std::{ collections::BTreeMap, sync::Arc };
+use crate::{
+    application::commands::authenticate_api_key_use_case::{
+        AuthenticateApiKeyUseCase,
+        AuthenticateApiKeyRequestModel,
+        AuthenticateApiKeyError,
+    },
+    domain::entities::{ api_key::API_KEY_PREFIX, caller::Caller, scope::Grant },
+    infrastructure::services::{
+        file_api_key_service::FileApiKeyService,
+        jwt_verifier::JwtVerifier,
+    },
+};
 
 #[derive(Debug)]
 pub struct AuthorizationHeader {
-    pub token: String,
+    pub caller: Caller,
+}
+
+impl AuthorizationHeader {
+    /// Stable identity of the caller forwarded to the Decryption Servers, the `sub` claim of its
+    /// verified access token, the owner of its API key or the subject of its client certificate.
+    pub fn identity(&self) -> String {
+        self.caller.subject.clone()
+    }
+}
+
+/// Scopes granted to callers authenticated by a client certificate. Only managed when mutual TLS
+/// is enabled.
+pub struct ClientCertificateGrants(pub Vec<Grant>);
+
+fn certificate_caller(certificate: &Certificate<'_>, grants: &[Grant]) -> Caller {
+    let subject = certificate.subject().to_string();
+    let issuer = certificate.issuer().to_string();
+    let mut claims = BTreeMap::new();
+    claims.insert("x509_subject".to_string(), Value::String(subject.clone()));
+    claims.insert("x509_issuer".to_string(), Value::String(issuer.clone()));
+    claims.insert(
+        "x509_serial".to_string(),
+        Value::String(certificate.serial().to_str_radix(16))
+    );
+    Caller {
+        subject: certificate.subject().common_name().map(str::to_string).unwrap_or(subject),
+        issuer: Some(issuer),
+        audience: Vec::new(),
+        expires_at: u64::try_from(certificate.validity().not_after.timestamp()).ok(),
+        tenant: certificate
+            .subject()
+            .iter_organization()
+            .next()
+            .and_then(|organization| organization.as_str().ok())
+            .map(str::to_string),
+        grants: grants.to_vec(),
+        claims,
+    }
+}
+
+/// Authenticates a caller without `Authorization` header by the client certificate verified
+/// during the TLS handshake.
+async fn authenticate_client_certificate(
+    request: &Request<'_>
+) -> Result<Caller, (Status, String)> {
+    let no_credentials = (Status::Unauthorized, String::from("No access token provided"));
+    let Some(ClientCertificateGrants(grants)) = request.rocket().state() else {
+        return Err(no_credentials);
+    };
+    match request.guard::<Certificate<'_>>().await {
+        Outcome::Success(certificate) => Ok(certificate_caller(&certificate, grants)),
+        Outcome::Error((status, e)) => Err((status, format!("Invalid client certificate, {}", e))),
+        Outcome::Forward(_) => Err(no_credentials),
+    }
+}
+
+async fn authenticate_api_key(
+    request: &Request<'_>,
+    secret: &str
+) -> Result<Caller, (Status, String)> {
+    let Some(api_key_service) = request.rocket().state::<Arc<FileApiKeyService>>() else {
+        return Err((Status::Unauthorized, String::from("API keys are not accepted")));
+    };
+    let use_case = AuthenticateApiKeyUseCase::new(api_key_service.as_ref());
+    let response_model = use_case.interact(AuthenticateApiKeyRequestModel {
+        secret: secret.to_string(),
+    }).await;
+    match response_model {
+        Ok(response_model) => Ok(response_model.caller),
+        Err(e @ AuthenticateApiKeyError::ApiKeyServiceError(_)) =>
+            Err((Status::InternalServerError, e.to_string())),
+        Err(e) => Err((Status::Unauthorized, e.to_string())),
+    }
 }
 
+async fn authenticate(request: &Request<'_>) -> Result<Caller, (Status, String)> {
+    let Some(token) = request.headers().get_one("Authorization") else {
+        return authenticate_client_certificate(request).await;
+    };
+    let Some(token) = token.strip_prefix("Bearer ") else {
+        return Err((Status::Unauthorized, String::from("Expected a Bearer token")));
+    };
+    if token.starts_with(API_KEY_PREFIX) {
+        return authenticate_api_key(request, token).await;
+    }
+    let Some(jwt_verifier) = request.rocket().state::<JwtVerifier>() else {
+        let error = String::from("Token verification is not configured");
+        return Err((Status::InternalServerError, error));
+    };
+    jwt_verifier.verify(token).map_err(|e| (Status::Unauthorized, e.to_string()))
+}
+
+/// Outcome of authenticating a request, cached so that every guard relying on the caller, such
+/// as the scope and rate limiter guards, shares a single verification.
+struct Authentication(Result<Caller, (Status, String)>);
+
 #[rocket::async_trait]
 impl<'r> FromRequest<'r> for AuthorizationHeader {
     type Error = String;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        let token = request.headers().get_one("Authorization");
-
-        match token {
-            Some(token) => {
-                Outcome::Success(AuthorizationHeader {
-                    token: token.to_string(),
-                })
-            }
-            None => {
-                Outcome::Error((Status::Unauthorized, String::from("No access token provided")))
-            }
+        let authentication = request.local_cache_async(async {
+            Authentication(authenticate(request).await)
+        }).await;
+        match &authentication.0 {
+            Ok(caller) => Outcome::Success(AuthorizationHeader { caller: caller.clone() }),
+            Err((status, error)) => Outcome::Error((*status, error.clone())),
         }
     }
 }
@@ -41,11 +145,11 @@ impl<'a> OpenApiFromRequest<'a> for AuthorizationHeader {
     ) -> rocket_okapi::Result<RequestHeaderInput> {
         let security_scheme = SecurityScheme {
             description: Some(
-                "Requires an Bearer token to access, token is: `mytoken`.".to_owned()
+                "Requires a Bearer JWT, an API key or a mutual TLS client certificate.".to_owned()
             ),
             data: SecuritySchemeData::Http {
                 scheme: "bearer".to_owned(),
-                bearer_format: Some("bearer".to_owned()),
+                bearer_format: Some("JWT".to_owned()),
             },
             extensions: Object::default(),
         };
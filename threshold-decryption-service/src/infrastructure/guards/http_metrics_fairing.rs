@@ -0,0 +1,41 @@
+use std::{ sync::Arc, time::Instant };
+use rocket::fairing::{ Fairing, Info, Kind };
+use rocket::{ Data, Request, Response };
+use crate::infrastructure::services::service_metrics::ServiceMetrics;
+
+/// Counts requests by route, method and status, and records their latency.
+pub struct HttpMetrics;
+
+struct RequestStart(Option<Instant>);
+
+#[rocket::async_trait]
+impl Fairing for HttpMetrics {
+    fn info(&self) -> Info {
+        Info {
+            name: "HTTP metrics",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _data: &mut Data<'_>) {
+        request.local_cache(|| RequestStart(Some(Instant::now())));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let Some(metrics) = request.rocket().state::<Arc<ServiceMetrics>>() else {
+            return;
+        };
+        // Unmatched requests are grouped together so that scanners cannot create unbounded labels.
+        let route = request
+            .route()
+            .and_then(|route| route.name.as_deref())
+            .unwrap_or("unmatched");
+        let status = response.status().code.to_string();
+        metrics.http_requests.with_label_values(&[route, request.method().as_str(), &status]).inc();
+        if let RequestStart(Some(started_at)) = request.local_cache(|| RequestStart(None)) {
+            metrics.http_request_duration
+                .with_label_values(&[route])
+                .observe(started_at.elapsed().as_secs_f64());
+        }
+    }
+}
@@ -1,2 +1,7 @@
 pub mod authorization_request_guard;
 pub mod rate_limiter_request_guard;
+pub mod admin_authorization_request_guard;
+pub mod scope_request_guard;
+pub mod signed_json_data_guard;
+pub mod http_metrics_fairing;
+pub mod request_id_fairing;
@@ -1,50 +1,258 @@
 use rocket::request::{ self, FromRequest, Request };
+use rocket::fairing::{ Fairing, Info, Kind };
+use rocket::Response;
 use rocket_okapi::request::OpenApiFromRequest;
 use rocket::outcome::Outcome;
-use rocket::http::Status;
+use rocket::http::{ Header, Status };
 use governor::{ Quota, RateLimiter as GovernorRateLimiter };
+use governor::clock::{ Clock, DefaultClock };
+use governor::middleware::StateInformationMiddleware;
 use governor::state::keyed::DefaultKeyedStateStore;
-use governor::clock::DefaultClock;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::net::IpAddr;
 use std::num::NonZeroU32;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{ Hash, Hasher };
+use std::sync::Arc;
+use std::time::Duration;
+use crate::infrastructure::{
+    guards::authorization_request_guard::AuthorizationHeader,
+    services::service_metrics::ServiceMetrics,
+};
 
-#[derive(OpenApiFromRequest)]
+type KeyedRateLimiter = GovernorRateLimiter<
+    String,
+    DefaultKeyedStateStore<String>,
+    DefaultClock,
+    StateInformationMiddleware
+>;
+
+/// Result of checking a request against the quota of its route, reported in the `RateLimit-*`
+/// response headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the quota is fully replenished.
+    pub reset_in_secs: u64,
+    /// Seconds to wait before retrying, set when the request was rejected.
+    pub retry_after_secs: Option<u64>,
+}
+
+/// Per-minute quotas for each route, counted separately for every caller. Callers are identified
+/// by their authenticated identity, or by their address when they are not authenticated.
+#[derive(Clone)]
 pub struct RateLimiter {
-    limiter: Arc<GovernorRateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock>>,
+    default_quota: Quota,
+    default_limiter: Arc<KeyedRateLimiter>,
+    route_limiters: HashMap<String, (Quota, Arc<KeyedRateLimiter>)>,
+    trusted_proxies: Vec<IpAddr>,
+}
+
+fn per_minute(max_requests: u32) -> Quota {
+    Quota::per_minute(NonZeroU32::new(max_requests).unwrap_or(NonZeroU32::MIN))
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// Address of the client, taken from `X-Forwarded-For` only when the peer is a trusted proxy. The
+/// right-most forwarded address that is not a trusted proxy is used, as every address on its left
+/// may have been set by the client itself.
+pub fn client_address(
+    remote: Option<IpAddr>,
+    forwarded_for: &[&str],
+    trusted_proxies: &[IpAddr]
+) -> Option<IpAddr> {
+    let remote = remote?;
+    if !trusted_proxies.contains(&remote) {
+        return Some(remote);
+    }
+    forwarded_for
+        .iter()
+        .flat_map(|header| header.split(','))
+        .filter_map(|address| address.trim().parse::<IpAddr>().ok())
+        .rev()
+        .find(|address| !trusted_proxies.contains(address))
+        .or(Some(remote))
 }
 
 impl RateLimiter {
-    pub fn new(max_requests: u32) -> Self {
-        let quota = Quota::per_minute(NonZeroU32::new(max_requests).unwrap());
-        let limiter = GovernorRateLimiter::keyed(quota);
+    pub fn new(max_requests_per_minute: u32) -> Self {
+        let default_quota = per_minute(max_requests_per_minute);
         RateLimiter {
-            limiter: Arc::new(limiter),
+            default_quota,
+            default_limiter: Arc::new(
+                GovernorRateLimiter::keyed(default_quota).with_middleware()
+            ),
+            route_limiters: HashMap::new(),
+            trusted_proxies: Vec::new(),
         }
     }
 
-    fn get_key(&self, request: &rocket::Request) -> String {
-        let mut hasher = DefaultHasher::new();
-        request.client_ip().unwrap().to_string().hash(&mut hasher);
-        hasher.finish().to_string()
+    /// Overrides the quota of the route named `route`, the name of its handler function.
+    pub fn with_route_limit(mut self, route: &str, max_requests_per_minute: u32) -> Self {
+        let quota = per_minute(max_requests_per_minute);
+        let limiter = Arc::new(GovernorRateLimiter::keyed(quota).with_middleware());
+        self.route_limiters.insert(route.to_string(), (quota, limiter));
+        self
+    }
+
+    /// Proxies whose `X-Forwarded-For` header is trusted to carry the client address.
+    pub fn with_trusted_proxies(mut self, trusted_proxies: Vec<IpAddr>) -> Self {
+        self.trusted_proxies = trusted_proxies;
+        self
+    }
+
+    /// Counts a request of `caller` on `route` against the quota of the route.
+    pub fn check(&self, route: &str, caller: &str) -> RateLimitDecision {
+        let (quota, limiter, key) = match self.route_limiters.get(route) {
+            Some((quota, limiter)) => (*quota, limiter, caller.to_string()),
+            None => (self.default_quota, &self.default_limiter, format!("{}|{}", route, caller)),
+        };
+        let limit = quota.burst_size().get();
+        let replenish_interval = quota.replenish_interval();
+        match limiter.check_key(&key) {
+            Ok(snapshot) => {
+                let remaining = snapshot.remaining_burst_capacity();
+                RateLimitDecision {
+                    limit,
+                    remaining,
+                    reset_in_secs: ceil_secs(replenish_interval * (limit - remaining)),
+                    retry_after_secs: None,
+                }
+            }
+            Err(not_until) => {
+                let wait_time = not_until.wait_time_from(DefaultClock::default().now());
+                RateLimitDecision {
+                    limit,
+                    remaining: 0,
+                    reset_in_secs: ceil_secs(replenish_interval * limit),
+                    retry_after_secs: Some(ceil_secs(wait_time).max(1)),
+                }
+            }
+        }
+    }
+
+    /// Periodically drops the state of callers whose quota is fully replenished, so memory does
+    /// not grow with every caller ever seen.
+    pub fn spawn_pruning(&self, interval: Duration) {
+        let mut limiters = vec![Arc::clone(&self.default_limiter)];
+        limiters.extend(self.route_limiters.values().map(|(_, limiter)| Arc::clone(limiter)));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                for limiter in &limiters {
+                    limiter.retain_recent();
+                    limiter.shrink_to_fit();
+                }
+            }
+        });
+    }
+
+    async fn caller_key(&self, request: &Request<'_>) -> String {
+        if let Outcome::Success(authorization) = request.guard::<AuthorizationHeader>().await {
+            return format!("identity:{}", authorization.identity());
+        }
+        let forwarded_for: Vec<&str> = request.headers().get("X-Forwarded-For").collect();
+        let remote = request.remote().map(|remote| remote.ip());
+        match client_address(remote, &forwarded_for, &self.trusted_proxies) {
+            Some(address) => format!("address:{}", address),
+            None => String::from("address:unknown"),
+        }
     }
 }
 
+/// Rate limiting guard, rejecting the request with `429 Too Many Requests` once the caller has
+/// exhausted the quota of the route.
+#[derive(OpenApiFromRequest)]
+pub struct RateLimit {
+    pub decision: RateLimitDecision,
+}
+
 #[rocket::async_trait]
-impl<'r> FromRequest<'r> for RateLimiter {
+impl<'r> FromRequest<'r> for RateLimit {
     type Error = ();
 
     async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
-        let rate_limiter = request.rocket().state::<RateLimiter>().unwrap();
-        let key = rate_limiter.get_key(request);
-
-        match rate_limiter.limiter.check_key(&key) {
-            Ok(_) =>
-                Outcome::Success(RateLimiter {
-                    limiter: Arc::clone(&rate_limiter.limiter),
-                }),
-            Err(_) => Outcome::Error((Status::TooManyRequests, ())),
+        let Some(rate_limiter) = request.rocket().state::<RateLimiter>() else {
+            return Outcome::Error((Status::InternalServerError, ()));
+        };
+        let route = request
+            .route()
+            .and_then(|route| route.name.as_deref())
+            .unwrap_or("unknown");
+        let caller_key = rate_limiter.caller_key(request).await;
+        let decision = rate_limiter.check(route, &caller_key);
+        request.local_cache(|| Some(decision));
+
+        match decision.retry_after_secs {
+            None => Outcome::Success(RateLimit { decision }),
+            Some(_) => {
+                if let Some(metrics) = request.rocket().state::<Arc<ServiceMetrics>>() {
+                    metrics.rate_limited_requests.with_label_values(&[route]).inc();
+                }
+                Outcome::Error((Status::TooManyRequests, ()))
+            }
+        }
+    }
+}
+
+/// Adds the `RateLimit-*` headers, and `Retry-After` on rejections, to responses of rate limited
+/// routes.
+pub struct RateLimitHeaders;
+
+#[rocket::async_trait]
+impl Fairing for RateLimitHeaders {
+    fn info(&self) -> Info {
+        Info {
+            name: "Rate limit headers",
+            kind: Kind::Response,
         }
     }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let Some(decision) = request.local_cache(|| None::<RateLimitDecision>) else {
+            return;
+        };
+        response.set_header(Header::new("RateLimit-Limit", decision.limit.to_string()));
+        response.set_header(Header::new("RateLimit-Remaining", decision.remaining.to_string()));
+        response.set_header(Header::new("RateLimit-Reset", decision.reset_in_secs.to_string()));
+        if let Some(retry_after_secs) = decision.retry_after_secs {
+            response.set_header(Header::new("Retry-After", retry_after_secs.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_limit_routes_and_callers_separately() {
+        let rate_limiter = RateLimiter::new(1).with_route_limit("decrypt_message", 2);
+
+        assert_eq!(rate_limiter.check("encrypt_message", "identity:a").remaining, 0);
+        assert!(rate_limiter.check("encrypt_message", "identity:a").retry_after_secs.is_some());
+        assert!(rate_limiter.check("generate_data_key", "identity:a").retry_after_secs.is_none());
+        assert!(rate_limiter.check("encrypt_message", "identity:b").retry_after_secs.is_none());
+
+        let decision = rate_limiter.check("decrypt_message", "identity:a");
+        assert_eq!((decision.limit, decision.remaining, decision.reset_in_secs), (2, 1, 30));
+        assert!(rate_limiter.check("decrypt_message", "identity:a").retry_after_secs.is_none());
+        let decision = rate_limiter.check("decrypt_message", "identity:a");
+        assert!(decision.retry_after_secs.is_some_and(|retry_after| retry_after <= 30));
+    }
+
+    #[test]
+    fn should_only_trust_forwarded_for_from_trusted_proxies() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let forwarded_for = ["198.51.100.1, 203.0.113.7", "10.0.0.1"];
+
+        assert_eq!(client_address(Some(client), &forwarded_for, &[proxy]), Some(client));
+        assert_eq!(client_address(Some(proxy), &forwarded_for, &[proxy]), Some(client));
+        assert_eq!(client_address(Some(proxy), &[], &[proxy]), Some(proxy));
+        assert_eq!(client_address(None, &forwarded_for, &[proxy]), None);
+    }
 }
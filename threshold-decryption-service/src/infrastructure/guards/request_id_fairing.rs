@@ -0,0 +1,101 @@
+use std::convert::Infallible;
+use rand::RngCore;
+use hex_fmt::HexFmt;
+use rocket::fairing::{ Fairing, Info, Kind };
+use rocket::request::{ self, FromRequest, Request };
+use rocket::outcome::Outcome;
+use rocket::http::Header;
+use rocket::{ Data, Response };
+use rocket_okapi::request::OpenApiFromRequest;
+
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Request ids taken from clients are logged and forwarded to the Decryption Servers, so only
+/// short ids made of unreserved characters are accepted.
+fn is_valid_request_id(request_id: &str) -> bool {
+    !request_id.is_empty() &&
+        request_id.len() <= 64 &&
+        request_id
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || "-_.".contains(character))
+}
+
+pub fn generate_request_id() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    HexFmt(&bytes).to_string()
+}
+
+/// Id of the request, taken from its `X-Request-Id` header when valid and generated otherwise.
+#[derive(OpenApiFromRequest, Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    fn of(request: &Request<'_>) -> Self {
+        request
+            .local_cache(|| {
+                let request_id = request
+                    .headers()
+                    .get_one(REQUEST_ID_HEADER)
+                    .filter(|request_id| is_valid_request_id(request_id))
+                    .map(str::to_string)
+                    .unwrap_or_else(generate_request_id);
+                RequestId(request_id)
+            })
+            .clone()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestId {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        Outcome::Success(RequestId::of(request))
+    }
+}
+
+/// Assigns every request its id, echoes it in the `X-Request-Id` response header and logs the
+/// request once handled.
+pub struct RequestIds;
+
+#[rocket::async_trait]
+impl Fairing for RequestIds {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request ids",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _data: &mut Data<'_>) {
+        RequestId::of(request);
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let RequestId(request_id) = RequestId::of(request);
+        tracing::info!(
+            request_id = %request_id,
+            method = %request.method(),
+            path = %request.uri().path(),
+            status = response.status().code,
+            "Request handled"
+        );
+        response.set_header(Header::new(REQUEST_ID_HEADER, request_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_only_accept_short_unreserved_request_ids() {
+        assert!(is_valid_request_id("3f2a9c1e-checkout.42_b"));
+        assert!(is_valid_request_id(&generate_request_id()));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("id with spaces"));
+        assert!(!is_valid_request_id("id\n{\"forged\":true}"));
+        assert!(!is_valid_request_id(&"a".repeat(65)));
+    }
+}
@@ -0,0 +1,124 @@
+use std::{ marker::PhantomData, sync::Arc };
+use rocket::request::{ Outcome, Request, FromRequest };
+use rocket::http::Status;
+use rocket_okapi::okapi::openapi3::{
+    Object,
+    SecurityRequirement,
+    SecurityScheme,
+    SecuritySchemeData,
+};
+use rocket_okapi::{ gen::OpenApiGenerator, request::{ OpenApiFromRequest, RequestHeaderInput } };
+use crate::{
+    domain::entities::{ caller::Caller, scope::Scope },
+    infrastructure::{
+        guards::authorization_request_guard::AuthorizationHeader,
+        services::pairing_cryptography_service::PairingCryptographyService,
+    },
+};
+
+pub trait RequiredScope: Send + Sync + 'static {
+    const SCOPE: Scope;
+}
+
+pub struct EncryptScope;
+pub struct DecryptScope;
+pub struct KeysReadScope;
+pub struct ApproveScope;
+
+impl RequiredScope for EncryptScope {
+    const SCOPE: Scope = Scope::Encrypt;
+}
+
+impl RequiredScope for DecryptScope {
+    const SCOPE: Scope = Scope::Decrypt;
+}
+
+impl RequiredScope for KeysReadScope {
+    const SCOPE: Scope = Scope::KeysRead;
+}
+
+impl RequiredScope for ApproveScope {
+    const SCOPE: Scope = Scope::Approve;
+}
+
+/// An authenticated caller holding the scope `S`. Key-qualified scopes are checked against the
+/// key id of the service.
+pub struct Scoped<S: RequiredScope> {
+    pub authorization: AuthorizationHeader,
+    scope: PhantomData<S>,
+}
+
+impl<S: RequiredScope> Scoped<S> {
+    pub fn caller(&self) -> &Caller {
+        &self.authorization.caller
+    }
+
+    pub fn identity(&self) -> String {
+        self.authorization.identity()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, S: RequiredScope> FromRequest<'r> for Scoped<S> {
+    type Error = (Status, String);
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let authorization = match request.guard::<AuthorizationHeader>().await {
+            Outcome::Success(authorization) => authorization,
+            Outcome::Error((status, error)) => {
+                return Outcome::Error((status, (status, error)));
+            }
+            Outcome::Forward(status) => {
+                return Outcome::Forward(status);
+            }
+        };
+        let tenant = authorization.caller.tenant();
+        let key_id = match request.rocket().state::<Arc<PairingCryptographyService>>() {
+            Some(cryptography_service) =>
+                match cryptography_service.key_id(tenant) {
+                    Some(key_id) => Some(key_id.to_string()),
+                    None => {
+                        let error = format!("Unknown tenant `{}`", tenant);
+                        return Outcome::Error((Status::Forbidden, (Status::Forbidden, error)));
+                    }
+                }
+            None => None,
+        };
+        if !authorization.caller.is_allowed(S::SCOPE, key_id.as_deref()) {
+            let error = format!("The `{}` scope is required", S::SCOPE);
+            return Outcome::Error((Status::Forbidden, (Status::Forbidden, error)));
+        }
+        Outcome::Success(Scoped {
+            authorization,
+            scope: PhantomData,
+        })
+    }
+}
+
+impl<'a, S: RequiredScope> OpenApiFromRequest<'a> for Scoped<S> {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        let security_scheme = SecurityScheme {
+            description: Some(
+                "Requires credentials granting the scopes listed by the operation.".to_owned()
+            ),
+            data: SecuritySchemeData::Http {
+                scheme: "bearer".to_owned(),
+                bearer_format: Some("JWT".to_owned()),
+            },
+            extensions: Object::default(),
+        };
+        let mut security_req = SecurityRequirement::new();
+        security_req.insert("AuthorizationHeader".to_owned(), vec![S::SCOPE.as_str().to_owned()]);
+        Ok(
+            RequestHeaderInput::Security(
+                "AuthorizationHeader".to_owned(),
+                security_scheme,
+                security_req
+            )
+        )
+    }
+}
@@ -0,0 +1,125 @@
+use std::{ ops::Deref, time::{ SystemTime, UNIX_EPOCH } };
+use rocket::data::{ self, Data, FromData, ToByteUnit };
+use rocket::http::Status;
+use rocket::outcome::Outcome;
+use rocket::request::Request;
+use rocket::serde::json::Json;
+use rocket_okapi::okapi::openapi3::RequestBody;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use rocket_okapi::{ gen::OpenApiGenerator, request::OpenApiFromData };
+use serde::de::DeserializeOwned;
+use crate::infrastructure::{
+    guards::authorization_request_guard::AuthorizationHeader,
+    services::request_signature_verifier::{ RequestSignatureVerifier, SignedRequest },
+};
+
+pub const CLIENT_ID_HEADER: &str = "X-Client-Id";
+pub const TIMESTAMP_HEADER: &str = "X-Timestamp";
+pub const NONCE_HEADER: &str = "X-Nonce";
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// A JSON body whose request is verified against its HMAC signature, when signing is configured,
+/// before the route runs. Callers with a registered signing client must sign every request.
+pub struct SignedJson<T>(pub T);
+
+impl<T> SignedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for SignedJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+async fn verify_signature(request: &Request<'_>, body: &[u8]) -> Result<(), (Status, String)> {
+    let Some(verifier) = request.rocket().state::<RequestSignatureVerifier>() else {
+        return Ok(());
+    };
+    // Unauthenticated requests are rejected by the authorization guards of the route.
+    let Outcome::Success(authorization) = request.guard::<AuthorizationHeader>().await else {
+        return Ok(());
+    };
+    let identity = authorization.identity();
+    let headers = request.headers();
+    let Some(signature) = headers.get_one(SIGNATURE_HEADER) else {
+        if verifier.requires_signature(&identity) {
+            return Err((Status::Unauthorized, String::from("Requests must be signed")));
+        }
+        return Ok(());
+    };
+    let (Some(client_id), Some(timestamp), Some(nonce)) = (
+        headers.get_one(CLIENT_ID_HEADER),
+        headers.get_one(TIMESTAMP_HEADER),
+        headers.get_one(NONCE_HEADER),
+    ) else {
+        let error = format!(
+            "Signed requests require the {}, {} and {} headers",
+            CLIENT_ID_HEADER,
+            TIMESTAMP_HEADER,
+            NONCE_HEADER
+        );
+        return Err((Status::Unauthorized, error));
+    };
+    let timestamp = timestamp
+        .parse()
+        .map_err(|_| (Status::Unauthorized, format!("Invalid {} header", TIMESTAMP_HEADER)))?;
+    let path = request.uri().to_string();
+    let signed_request = SignedRequest {
+        client_id,
+        method: request.method().as_str(),
+        path: &path,
+        timestamp,
+        nonce,
+        body,
+        signature,
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let subject = verifier
+        .verify(&signed_request, now)
+        .map_err(|e| (Status::Unauthorized, e.to_string()))?;
+    if subject != identity {
+        return Err((Status::Unauthorized, String::from("Request signed for another caller")));
+    }
+    Ok(())
+}
+
+#[rocket::async_trait]
+impl<'r, T: DeserializeOwned> FromData<'r> for SignedJson<T> {
+    type Error = (Status, String);
+
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let limit = request.limits().get("json").unwrap_or((1).mebibytes());
+        let body = match data.open(limit).into_bytes().await {
+            Ok(body) if body.is_complete() => body.into_inner(),
+            Ok(_) => {
+                let error = String::from("Request body is too large");
+                return Outcome::Error((Status::PayloadTooLarge, (Status::PayloadTooLarge, error)));
+            }
+            Err(e) => {
+                return Outcome::Error((Status::BadRequest, (Status::BadRequest, e.to_string())));
+            }
+        };
+        if let Err((status, error)) = verify_signature(request, &body).await {
+            return Outcome::Error((status, (status, error)));
+        }
+
+        match serde_json::from_slice(&body) {
+            Ok(value) => Outcome::Success(SignedJson(value)),
+            Err(e) => {
+                let status = Status::UnprocessableEntity;
+                Outcome::Error((status, (status, e.to_string())))
+            }
+        }
+    }
+}
+
+impl<'r, T: JsonSchema + DeserializeOwned> OpenApiFromData<'r> for SignedJson<T> {
+    fn request_body(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<RequestBody> {
+        Json::<T>::request_body(gen)
+    }
+}
@@ -0,0 +1,43 @@
+use serde::Serialize;
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use crate::domain::entities::api_key::ApiKey;
+
+/// API key metadata. Neither the secret nor its hash are ever returned.
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyResponse {
+    pub id: String,
+    pub name: String,
+    pub owner: String,
+    pub scopes: Vec<String>,
+    pub tenant: Option<String>,
+    pub created_at: u64,
+    pub expires_at: Option<u64>,
+    pub last_used_at: Option<u64>,
+    pub revoked_at: Option<u64>,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.id,
+            name: api_key.name,
+            owner: api_key.owner,
+            scopes: api_key.scopes,
+            tenant: api_key.tenant,
+            created_at: api_key.created_at,
+            expires_at: api_key.expires_at,
+            last_used_at: api_key.last_used_at,
+            revoked_at: api_key.revoked_at,
+        }
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeySecretResponse {
+    /// Shown only once, store it safely.
+    pub api_key: String,
+    pub metadata: ApiKeyResponse,
+}
@@ -0,0 +1,36 @@
+use serde::Serialize;
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use crate::domain::entities::audit_event::AuditEvent;
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEventResponse {
+    /// Unix timestamp (seconds) at which the call completed.
+    pub timestamp: u64,
+    /// Either `encrypt` or `decrypt`.
+    pub operation: String,
+    pub requester: Option<String>,
+    pub ciphertext_fingerprint: Option<String>,
+    /// Ids of the Decryption Servers whose shares were combined.
+    pub participants: Vec<usize>,
+    /// Either `success` or `failure`.
+    pub outcome: String,
+    pub error: Option<String>,
+    pub latency_ms: u64,
+}
+
+impl From<AuditEvent> for AuditEventResponse {
+    fn from(event: AuditEvent) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            operation: event.operation.as_str().to_string(),
+            requester: event.requester,
+            ciphertext_fingerprint: event.ciphertext_fingerprint,
+            participants: event.participants,
+            outcome: event.outcome.as_str().to_string(),
+            error: event.error,
+            latency_ms: event.latency_ms,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use rocket_okapi::openapi;
+use serde::Deserialize;
+use std::sync::Arc;
+use crate::{
+    application::commands::create_api_key_use_case::{
+        CreateApiKeyUseCase,
+        CreateApiKeyRequestModel,
+        CreateApiKeyError,
+    },
+    infrastructure::{
+        guards::admin_authorization_request_guard::AdminAuthorizationHeader,
+        routes::{
+            api_key_response::{ ApiKeyResponse, ApiKeySecretResponse },
+            http_error_response::HttpErrorResponse,
+        },
+        services::file_api_key_service::FileApiKeyService,
+    },
+};
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeyRequest {
+    name: String,
+    /// Identity of the caller authenticated by the key.
+    owner: String,
+    /// The key never expires when unset.
+    expires_in_secs: Option<u64>,
+    /// Scopes granted to the key, such as `decrypt` or `encrypt:<key id>`.
+    scopes: Option<Vec<String>>,
+    /// Tenant of the callers authenticated by the key, the default tenant when unset.
+    tenant: Option<String>,
+}
+
+#[openapi]
+#[post("/api-keys", format = "json", data = "<request>")]
+pub async fn create_api_key(
+    api_key_service_state: &State<Arc<FileApiKeyService>>,
+    authorization: Result<AdminAuthorizationHeader, (Status, String)>,
+    request: Json<CreateApiKeyRequest>
+) -> Result<status::Custom<Json<ApiKeySecretResponse>>, status::Custom<Json<HttpErrorResponse>>> {
+    let _authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let request = request.into_inner();
+    let use_case = CreateApiKeyUseCase::new(api_key_service_state.as_ref());
+    let response_model = use_case
+        .interact(CreateApiKeyRequestModel {
+            name: request.name,
+            owner: request.owner,
+            expires_in_secs: request.expires_in_secs,
+            scopes: request.scopes.unwrap_or_default(),
+            tenant: request.tenant,
+        }).await
+        .map_err(|e| {
+            let status = match e {
+                CreateApiKeyError::InvalidRequest(_) => Status::BadRequest,
+                _ => Status::InternalServerError,
+            };
+            status::Custom(
+                status,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
+    Ok(
+        status::Custom(
+            Status::Created,
+            Json(ApiKeySecretResponse {
+                api_key: response_model.secret,
+                metadata: ApiKeyResponse::from(response_model.api_key),
+            })
+        )
+    )
+}
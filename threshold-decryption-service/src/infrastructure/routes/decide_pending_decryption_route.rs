@@ -0,0 +1,135 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::openapi;
+use std::sync::Arc;
+use crate::{
+    application::commands::decide_pending_decryption_use_case::{
+        DecidePendingDecryptionUseCase,
+        DecidePendingDecryptionRequestModel,
+        DecidePendingDecryptionError,
+    },
+    domain::entities::pending_decryption::ApprovalDecision,
+    infrastructure::{
+        guards::{
+            scope_request_guard::{ Scoped, ApproveScope },
+            rate_limiter_request_guard::RateLimit,
+            request_id_fairing::RequestId,
+        },
+        routes::{
+            http_error_response::HttpErrorResponse,
+            pending_decryption_response::PendingDecryptionResponse,
+        },
+        services::{
+            file_audit_trail_service::FileAuditTrailService,
+            in_memory_pending_decryption_service::InMemoryPendingDecryptionService,
+            pairing_cryptography_service::PairingCryptographyService,
+        },
+    },
+};
+
+type DecisionResult = Result<
+    status::Custom<Json<PendingDecryptionResponse>>,
+    status::Custom<Json<HttpErrorResponse>>
+>;
+
+async fn decide(
+    pending_decryption_service: &InMemoryPendingDecryptionService,
+    cryptography_service: &PairingCryptographyService,
+    audit_trail_service: &FileAuditTrailService,
+    tenant: String,
+    id: String,
+    decision: ApprovalDecision,
+    request_id: RequestId
+) -> DecisionResult {
+    let use_case = DecidePendingDecryptionUseCase::new(
+        pending_decryption_service,
+        cryptography_service,
+        audit_trail_service
+    );
+    let response_model = use_case
+        .interact(DecidePendingDecryptionRequestModel {
+            id,
+            decision,
+            tenant,
+            request_id: Some(request_id.0),
+        }).await
+        .map_err(|e| {
+            let status = match e {
+                DecidePendingDecryptionError::PendingDecryptionNotFound(_) => Status::NotFound,
+                DecidePendingDecryptionError::DecisionRejected(_) => Status::Conflict,
+                _ => Status::InternalServerError,
+            };
+            status::Custom(
+                status,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
+    let mut pending_decryption = response_model.pending_decryption;
+    // The plaintext is kept for the requester, approvers never see it.
+    pending_decryption.decrypted_message = None;
+    Ok(status::Custom(Status::Ok, Json(PendingDecryptionResponse::from(pending_decryption))))
+}
+
+/// Approves a pending decryption of the caller's tenant. The last approval required dispatches
+/// the decryption to the Decryption Servers.
+#[openapi]
+#[post("/pending-decryptions/<id>/approve")]
+#[tracing::instrument(
+    name = "POST /pending-decryptions/<id>/approve",
+    skip_all,
+    fields(request_id = %request_id.0)
+)]
+pub async fn approve_pending_decryption(
+    pending_decryption_service_state: &State<Arc<InMemoryPendingDecryptionService>>,
+    cryptography_service_state: &State<Arc<PairingCryptographyService>>,
+    audit_trail_service_state: &State<Arc<FileAuditTrailService>>,
+    _rate_limit: RateLimit,
+    request_id: RequestId,
+    authorization: Result<Scoped<ApproveScope>, (Status, String)>,
+    id: String
+) -> DecisionResult {
+    let authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    decide(
+        pending_decryption_service_state.as_ref(),
+        cryptography_service_state.as_ref(),
+        audit_trail_service_state.as_ref(),
+        authorization.caller().tenant().to_string(),
+        id,
+        ApprovalDecision::Approve(authorization.identity()),
+        request_id
+    ).await
+}
+
+/// Denies a pending decryption of the caller's tenant, which is then never dispatched.
+#[openapi]
+#[post("/pending-decryptions/<id>/deny")]
+#[tracing::instrument(
+    name = "POST /pending-decryptions/<id>/deny",
+    skip_all,
+    fields(request_id = %request_id.0)
+)]
+pub async fn deny_pending_decryption(
+    pending_decryption_service_state: &State<Arc<InMemoryPendingDecryptionService>>,
+    cryptography_service_state: &State<Arc<PairingCryptographyService>>,
+    audit_trail_service_state: &State<Arc<FileAuditTrailService>>,
+    _rate_limit: RateLimit,
+    request_id: RequestId,
+    authorization: Result<Scoped<ApproveScope>, (Status, String)>,
+    id: String
+) -> DecisionResult {
+    let authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    decide(
+        pending_decryption_service_state.as_ref(),
+        cryptography_service_state.as_ref(),
+        audit_trail_service_state.as_ref(),
+        authorization.caller().tenant().to_string(),
+        id,
+        ApprovalDecision::Deny(authorization.identity()),
+        request_id
+    ).await
+}
@@ -0,0 +1,105 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use rocket_okapi::openapi;
+use serde::{ Serialize, Deserialize };
+use std::sync::Arc;
+use base64::{ engine::general_purpose, Engine };
+use crate::{
+    application::commands::decrypt_data_key_use_case::{
+        DecryptDataKeyUseCase,
+        DecryptDataKeyRequestModel,
+        DecryptDataKeyError,
+    },
+    domain::entities::data_key::EncryptionContext,
+    infrastructure::{
+        guards::{
+            scope_request_guard::{ Scoped, DecryptScope },
+            rate_limiter_request_guard::RateLimit,
+            request_id_fairing::RequestId,
+            signed_json_data_guard::SignedJson,
+        },
+        routes::http_error_response::HttpErrorResponse,
+        services::pairing_cryptography_service::PairingCryptographyService,
+    },
+};
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptDataKeyRequest {
+    encrypted_data_key: String,
+    encryption_context: Option<EncryptionContext>,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptDataKeyResponse {
+    plaintext_data_key: String,
+}
+
+#[openapi]
+#[post("/decrypt-data-key", format = "json", data = "<request>")]
+#[tracing::instrument(
+    name = "POST /decrypt-data-key",
+    skip_all,
+    fields(request_id = %request_id.0)
+)]
+pub async fn decrypt_data_key(
+    cryptography_service_state: &State<Arc<PairingCryptographyService>>,
+    _rate_limit: RateLimit,
+    request_id: RequestId,
+    authorization: Result<Scoped<DecryptScope>, (Status, String)>,
+    request: Result<SignedJson<DecryptDataKeyRequest>, (Status, String)>
+) -> Result<
+    status::Custom<Json<DecryptDataKeyResponse>>,
+    status::Custom<Json<HttpErrorResponse>>
+> {
+    let authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let request = request.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let request = request.into_inner();
+    let encrypted_data_key = general_purpose::STANDARD
+        .decode(&request.encrypted_data_key)
+        .map_err(|e| {
+            status::Custom(
+                Status::BadRequest,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
+    let use_case = DecryptDataKeyUseCase::new(cryptography_service_state.as_ref());
+    let response_model = use_case
+        .interact(DecryptDataKeyRequestModel {
+            encrypted_data_key,
+            encryption_context: request.encryption_context.unwrap_or_default(),
+            requester: Some(authorization.identity()),
+            tenant: authorization.caller().tenant().to_string(),
+            request_id: Some(request_id.0),
+        }).await
+        .map_err(|e| {
+            let status = match e {
+                DecryptDataKeyError::EncryptionContextMismatch => Status::BadRequest,
+                _ => Status::InternalServerError,
+            };
+            status::Custom(
+                status,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
+    Ok(
+        status::Custom(
+            Status::Ok,
+            Json(DecryptDataKeyResponse {
+                plaintext_data_key: general_purpose::STANDARD.encode(
+                    response_model.plaintext_data_key
+                ),
+            })
+        )
+    )
+}
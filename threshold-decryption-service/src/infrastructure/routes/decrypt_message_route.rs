@@ -6,43 +6,75 @@ use serde::{ Serialize, Deserialize };
 use std::sync::Arc;
 use base64::{ engine::general_purpose, Engine };
 use crate::{
-    application::commands::decrypt_message_use_case::{
-        DecryptMessageUseCase,
-        DecryptMessageRequestModel,
+    application::commands::{
+        decrypt_message_use_case::{ DecryptMessageUseCase, DecryptMessageRequestModel },
+        request_decryption_approval_use_case::{
+            RequestDecryptionApprovalUseCase,
+            RequestDecryptionApprovalRequestModel,
+        },
     },
+    domain::entities::pending_decryption::ApprovalPolicy,
     infrastructure::{
         guards::{
-            authorization_request_guard::AuthorizationHeader,
-            rate_limiter_request_guard::RateLimiter,
+            scope_request_guard::{ Scoped, DecryptScope },
+            rate_limiter_request_guard::RateLimit,
+            request_id_fairing::RequestId,
+            signed_json_data_guard::SignedJson,
+        },
+        routes::{
+            http_error_response::HttpErrorResponse,
+            pending_decryption_response::PendingDecryptionResponse,
+        },
+        services::{
+            file_audit_trail_service::FileAuditTrailService,
+            in_memory_pending_decryption_service::InMemoryPendingDecryptionService,
+            pairing_cryptography_service::PairingCryptographyService,
         },
-        routes::http_error_response::HttpErrorResponse,
-        services::pairing_cryptography_service::PairingCryptographyService,
     },
 };
 
 #[derive(Deserialize, FromForm, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct DecryptMessageRequest {
     message: String,
+    /// Context (e.g. tenant id, record id, purpose) cryptographically bound to the ciphertext.
+    associated_data: Option<String>,
+    /// Justification shown to operators of Decryption Servers running in approval mode.
+    reason: Option<String>,
+    /// Holds the decryption until enough approvers approve it, see `/pending-decryptions`.
+    require_approval: Option<bool>,
 }
 
 #[derive(Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DecryptMessageResponse {
-    decrypted_message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decrypted_message: Option<String>,
+    /// Returned with `202 Accepted` instead of the decrypted message when approval is required.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pending_decryption: Option<PendingDecryptionResponse>,
 }
 
 #[openapi]
+#[allow(clippy::too_many_arguments)]
 #[post("/decrypt-message", format = "json", data = "<request>")]
+#[tracing::instrument(name = "POST /decrypt-message", skip_all, fields(request_id = %request_id.0))]
 pub async fn decrypt_message(
     cryptography_service_state: &State<Arc<PairingCryptographyService>>,
-    _rate_limiter: RateLimiter,
-    authorization: Result<AuthorizationHeader, String>,
-    request: Json<DecryptMessageRequest>
+    audit_trail_service_state: &State<Arc<FileAuditTrailService>>,
+    pending_decryption_service_state: &State<Arc<InMemoryPendingDecryptionService>>,
+    approval_policy_state: &State<ApprovalPolicy>,
+    _rate_limit: RateLimit,
+    request_id: RequestId,
+    authorization: Result<Scoped<DecryptScope>, (Status, String)>,
+    request: Result<SignedJson<DecryptMessageRequest>, (Status, String)>
 ) -> Result<status::Custom<Json<DecryptMessageResponse>>, status::Custom<Json<HttpErrorResponse>>> {
-    let _authorization = authorization.map_err(|error| {
-        status::Custom(Status::Unauthorized, Json(HttpErrorResponse { error }))
+    let authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let request = request.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
     })?;
-    let use_case = DecryptMessageUseCase::new(cryptography_service_state.as_ref());
     let message_bytes = general_purpose::STANDARD.decode(&request.message).map_err(|e| {
         status::Custom(
             Status::BadRequest,
@@ -51,9 +83,59 @@ pub async fn decrypt_message(
             })
         )
     })?;
+    let tenant = authorization.caller().tenant().to_string();
+    let approval_policy = approval_policy_state.inner();
+    if approval_policy.requires_approval(&tenant, request.require_approval.unwrap_or(false)) {
+        let use_case = RequestDecryptionApprovalUseCase::new(
+            pending_decryption_service_state.as_ref()
+        );
+        let response_model = use_case
+            .interact(RequestDecryptionApprovalRequestModel {
+                message: message_bytes,
+                associated_data: request.associated_data.clone().map(String::into_bytes),
+                requester: authorization.identity(),
+                reason: request.reason.clone(),
+                tenant,
+                required_approvals: approval_policy.required_approvals,
+                expires_in_secs: approval_policy.expires_in_secs,
+            }).await
+            .map_err(|e| {
+                status::Custom(
+                    Status::InternalServerError,
+                    Json(HttpErrorResponse {
+                        error: e.to_string(),
+                    })
+                )
+            })?;
+        tracing::info!(
+            request_id = %request_id.0,
+            pending_decryption_id = %response_model.pending_decryption.id,
+            "Decryption held for approval"
+        );
+        return Ok(
+            status::Custom(
+                Status::Accepted,
+                Json(DecryptMessageResponse {
+                    decrypted_message: None,
+                    pending_decryption: Some(
+                        PendingDecryptionResponse::from(response_model.pending_decryption)
+                    ),
+                })
+            )
+        );
+    }
+    let use_case = DecryptMessageUseCase::new(
+        cryptography_service_state.as_ref(),
+        audit_trail_service_state.as_ref()
+    );
     let response_model = use_case
         .interact(DecryptMessageRequestModel {
             message: message_bytes,
+            associated_data: request.associated_data.clone().map(String::into_bytes),
+            requester: Some(authorization.identity()),
+            reason: request.reason.clone(),
+            tenant,
+            request_id: Some(request_id.0),
         }).await
         .map_err(|e| {
             status::Custom(
@@ -67,7 +149,8 @@ pub async fn decrypt_message(
         status::Custom(
             Status::Ok,
             Json(DecryptMessageResponse {
-                decrypted_message: response_model.decrypted_message,
+                decrypted_message: Some(response_model.decrypted_message),
+                pending_decryption: None,
             })
         )
     )
@@ -12,17 +12,24 @@ use crate::{
     },
     infrastructure::{
         guards::{
-            authorization_request_guard::AuthorizationHeader,
-            rate_limiter_request_guard::RateLimiter,
+            scope_request_guard::{ Scoped, EncryptScope },
+            rate_limiter_request_guard::RateLimit,
+            signed_json_data_guard::SignedJson,
         },
         routes::http_error_response::HttpErrorResponse,
-        services::pairing_cryptography_service::PairingCryptographyService,
+        services::{
+            file_audit_trail_service::FileAuditTrailService,
+            pairing_cryptography_service::PairingCryptographyService,
+        },
     },
 };
 
 #[derive(Deserialize, FromForm, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct EncryptMessageRequest {
     message: String,
+    /// Context (e.g. tenant id, record id, purpose) cryptographically bound to the ciphertext.
+    associated_data: Option<String>,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -35,17 +42,27 @@ pub struct EncryptMessageResponse {
 #[post("/encrypt-message", format = "json", data = "<request>")]
 pub async fn encrypt_message(
     cryptography_service_state: &State<Arc<PairingCryptographyService>>,
-    _rate_limiter: RateLimiter,
-    authorization: Result<AuthorizationHeader, String>,
-    request: Json<EncryptMessageRequest>
+    audit_trail_service_state: &State<Arc<FileAuditTrailService>>,
+    _rate_limit: RateLimit,
+    authorization: Result<Scoped<EncryptScope>, (Status, String)>,
+    request: Result<SignedJson<EncryptMessageRequest>, (Status, String)>
 ) -> Result<status::Custom<Json<EncryptMessageResponse>>, status::Custom<Json<HttpErrorResponse>>> {
-    let _authorization = authorization.map_err(|error| {
-        status::Custom(Status::Unauthorized, Json(HttpErrorResponse { error }))
+    let authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let request = request.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
     })?;
-    let use_case = EncryptMessageUseCase::new(cryptography_service_state.as_ref());
+    let use_case = EncryptMessageUseCase::new(
+        cryptography_service_state.as_ref(),
+        audit_trail_service_state.as_ref()
+    );
     let response_model = use_case
         .interact(EncryptMessageRequestModel {
             message: request.message.clone(),
+            associated_data: request.associated_data.clone().map(String::into_bytes),
+            requester: Some(authorization.identity()),
+            tenant: authorization.caller().tenant().to_string(),
         }).await
         .map_err(|e| {
             status::Custom(
@@ -0,0 +1,58 @@
+use rocket::{ State, http::{ ContentType, Status }, response::status, serde::json::Json };
+use rocket_okapi::openapi;
+use std::sync::Arc;
+use crate::{
+    application::queries::query_audit_events_use_case::{
+        QueryAuditEventsUseCase,
+        QueryAuditEventsRequestModel,
+    },
+    infrastructure::{
+        guards::admin_authorization_request_guard::AdminAuthorizationHeader,
+        routes::{
+            audit_event_response::AuditEventResponse,
+            get_audit_events_route::AuditEventsQuery,
+            http_error_response::HttpErrorResponse,
+        },
+        services::file_audit_trail_service::FileAuditTrailService,
+    },
+};
+
+/// Exports the audit trail as JSON lines (`application/x-ndjson`), one event per line.
+#[openapi]
+#[get("/audit-events/export?<query..>")]
+pub async fn export_audit_events(
+    audit_trail_service_state: &State<Arc<FileAuditTrailService>>,
+    authorization: Result<AdminAuthorizationHeader, (Status, String)>,
+    query: AuditEventsQuery
+) -> Result<status::Custom<(ContentType, String)>, status::Custom<Json<HttpErrorResponse>>> {
+    let _authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let use_case = QueryAuditEventsUseCase::new(audit_trail_service_state.as_ref());
+    let response_model = use_case
+        .interact(QueryAuditEventsRequestModel {
+            query: query.to_audit_query()?,
+        }).await
+        .map_err(|e| {
+            status::Custom(
+                Status::InternalServerError,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
+    let mut export = String::new();
+    for event in response_model.events {
+        let line = serde_json::to_string(&AuditEventResponse::from(event)).map_err(|e| {
+            status::Custom(
+                Status::InternalServerError,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
+        export.push_str(&line);
+        export.push('\n');
+    }
+    Ok(status::Custom(Status::Ok, (ContentType::new("application", "x-ndjson"), export)))
+}
@@ -0,0 +1,100 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use rocket_okapi::openapi;
+use serde::{ Serialize, Deserialize };
+use std::sync::Arc;
+use base64::{ engine::general_purpose, Engine };
+use crate::{
+    application::commands::generate_data_key_use_case::{
+        GenerateDataKeyUseCase,
+        GenerateDataKeyRequestModel,
+    },
+    domain::entities::data_key::{ EncryptionContext, KeySpec },
+    infrastructure::{
+        guards::{
+            scope_request_guard::{ Scoped, EncryptScope },
+            rate_limiter_request_guard::RateLimit,
+            signed_json_data_guard::SignedJson,
+        },
+        routes::http_error_response::HttpErrorResponse,
+        services::pairing_cryptography_service::PairingCryptographyService,
+    },
+};
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateDataKeyRequest {
+    /// Either `AES_128` or `AES_256` (default).
+    key_spec: Option<String>,
+    encryption_context: Option<EncryptionContext>,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateDataKeyResponse {
+    key_spec: String,
+    plaintext_data_key: String,
+    encrypted_data_key: String,
+}
+
+#[openapi]
+#[post("/generate-data-key", format = "json", data = "<request>")]
+pub async fn generate_data_key(
+    cryptography_service_state: &State<Arc<PairingCryptographyService>>,
+    _rate_limit: RateLimit,
+    authorization: Result<Scoped<EncryptScope>, (Status, String)>,
+    request: Result<SignedJson<GenerateDataKeyRequest>, (Status, String)>
+) -> Result<
+    status::Custom<Json<GenerateDataKeyResponse>>,
+    status::Custom<Json<HttpErrorResponse>>
+> {
+    let authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let request = request.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let request = request.into_inner();
+    let key_spec = match request.key_spec {
+        Some(key_spec) =>
+            key_spec.parse::<KeySpec>().map_err(|e| {
+                status::Custom(
+                    Status::BadRequest,
+                    Json(HttpErrorResponse {
+                        error: e.to_string(),
+                    })
+                )
+            })?,
+        None => KeySpec::default(),
+    };
+    let use_case = GenerateDataKeyUseCase::new(cryptography_service_state.as_ref());
+    let response_model = use_case
+        .interact(GenerateDataKeyRequestModel {
+            key_spec,
+            encryption_context: request.encryption_context.unwrap_or_default(),
+            tenant: authorization.caller().tenant().to_string(),
+        }).await
+        .map_err(|e| {
+            status::Custom(
+                Status::InternalServerError,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
+    Ok(
+        status::Custom(
+            Status::Ok,
+            Json(GenerateDataKeyResponse {
+                key_spec: response_model.key_spec.as_str().to_string(),
+                plaintext_data_key: general_purpose::STANDARD.encode(
+                    response_model.plaintext_data_key
+                ),
+                encrypted_data_key: general_purpose::STANDARD.encode(
+                    response_model.encrypted_data_key
+                ),
+            })
+        )
+    )
+}
@@ -0,0 +1,98 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use rocket_okapi::openapi;
+use serde::Serialize;
+use std::sync::Arc;
+use crate::{
+    application::queries::query_audit_events_use_case::{
+        QueryAuditEventsUseCase,
+        QueryAuditEventsRequestModel,
+    },
+    domain::entities::audit_event::{ AuditOperation, AuditQuery },
+    infrastructure::{
+        guards::admin_authorization_request_guard::AdminAuthorizationHeader,
+        routes::{
+            audit_event_response::AuditEventResponse,
+            http_error_response::HttpErrorResponse,
+        },
+        services::file_audit_trail_service::FileAuditTrailService,
+    },
+};
+
+#[derive(FromForm, JsonSchema)]
+pub struct AuditEventsQuery {
+    requester: Option<String>,
+    /// Either `encrypt` or `decrypt`.
+    operation: Option<String>,
+    /// Unix timestamp (seconds), inclusive.
+    since: Option<u64>,
+    /// Unix timestamp (seconds), exclusive.
+    until: Option<u64>,
+    limit: Option<usize>,
+}
+
+impl AuditEventsQuery {
+    pub fn to_audit_query(&self) -> Result<AuditQuery, status::Custom<Json<HttpErrorResponse>>> {
+        let operation = match &self.operation {
+            Some(operation) =>
+                Some(
+                    operation.parse::<AuditOperation>().map_err(|e| {
+                        status::Custom(
+                            Status::BadRequest,
+                            Json(HttpErrorResponse {
+                                error: e.to_string(),
+                            })
+                        )
+                    })?
+                ),
+            None => None,
+        };
+        Ok(AuditQuery {
+            requester: self.requester.clone(),
+            operation,
+            since: self.since,
+            until: self.until,
+            limit: self.limit,
+        })
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAuditEventsResponse {
+    events: Vec<AuditEventResponse>,
+}
+
+#[openapi]
+#[get("/audit-events?<query..>")]
+pub async fn get_audit_events(
+    audit_trail_service_state: &State<Arc<FileAuditTrailService>>,
+    authorization: Result<AdminAuthorizationHeader, (Status, String)>,
+    query: AuditEventsQuery
+) -> Result<status::Custom<Json<GetAuditEventsResponse>>, status::Custom<Json<HttpErrorResponse>>> {
+    let _authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let use_case = QueryAuditEventsUseCase::new(audit_trail_service_state.as_ref());
+    let response_model = use_case
+        .interact(QueryAuditEventsRequestModel {
+            query: query.to_audit_query()?,
+        }).await
+        .map_err(|e| {
+            status::Custom(
+                Status::InternalServerError,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
+    Ok(
+        status::Custom(
+            Status::Ok,
+            Json(GetAuditEventsResponse {
+                events: response_model.events.into_iter().map(AuditEventResponse::from).collect(),
+            })
+        )
+    )
+}
@@ -0,0 +1,67 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::openapi;
+use std::sync::Arc;
+use crate::{
+    application::queries::get_pending_decryption_use_case::{
+        GetPendingDecryptionUseCase,
+        GetPendingDecryptionRequestModel,
+        GetPendingDecryptionError,
+    },
+    domain::entities::scope::Scope,
+    infrastructure::{
+        guards::{
+            authorization_request_guard::AuthorizationHeader,
+            rate_limiter_request_guard::RateLimit,
+        },
+        routes::{
+            http_error_response::HttpErrorResponse,
+            pending_decryption_response::PendingDecryptionResponse,
+        },
+        services::in_memory_pending_decryption_service::InMemoryPendingDecryptionService,
+    },
+};
+
+/// Status of a pending decryption for approvers, and its decrypted message for the requester once
+/// completed.
+#[openapi]
+#[get("/pending-decryptions/<id>")]
+pub async fn get_pending_decryption(
+    pending_decryption_service_state: &State<Arc<InMemoryPendingDecryptionService>>,
+    _rate_limit: RateLimit,
+    authorization: Result<AuthorizationHeader, String>,
+    id: String
+) -> Result<
+    status::Custom<Json<PendingDecryptionResponse>>,
+    status::Custom<Json<HttpErrorResponse>>
+> {
+    let authorization = authorization.map_err(|error| {
+        status::Custom(Status::Unauthorized, Json(HttpErrorResponse { error }))
+    })?;
+    let use_case = GetPendingDecryptionUseCase::new(pending_decryption_service_state.as_ref());
+    let response_model = use_case
+        .interact(GetPendingDecryptionRequestModel {
+            id,
+            caller: authorization.identity(),
+            tenant: authorization.caller.tenant().to_string(),
+            is_approver: authorization.caller.is_allowed(Scope::Approve, None),
+        }).await
+        .map_err(|e| {
+            let status = match e {
+                GetPendingDecryptionError::PendingDecryptionNotFound(_) => Status::NotFound,
+                GetPendingDecryptionError::Forbidden => Status::Forbidden,
+                _ => Status::InternalServerError,
+            };
+            status::Custom(
+                status,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
+    Ok(
+        status::Custom(
+            Status::Ok,
+            Json(PendingDecryptionResponse::from(response_model.pending_decryption))
+        )
+    )
+}
@@ -6,8 +6,12 @@ use serde::Serialize;
 use std::sync::Arc;
 
 use crate::{
-    application::queries::get_public_key_use_case::GetPublicKeyUseCase,
+    application::queries::get_public_key_use_case::{
+        GetPublicKeyUseCase,
+        GetPublicKeyRequestModel,
+    },
     infrastructure::{
+        guards::scope_request_guard::{ Scoped, KeysReadScope },
         routes::http_error_response::HttpErrorResponse,
         services::pairing_cryptography_service::PairingCryptographyService,
     },
@@ -22,17 +26,25 @@ pub struct GetPublicKeyResponse {
 #[openapi]
 #[get("/public-key")]
 pub async fn get_public_key(
-    cryptography_service_state: &State<Arc<PairingCryptographyService>>
+    cryptography_service_state: &State<Arc<PairingCryptographyService>>,
+    authorization: Result<Scoped<KeysReadScope>, (Status, String)>
 ) -> Result<status::Custom<Json<GetPublicKeyResponse>>, status::Custom<Json<HttpErrorResponse>>> {
-    let use_case = GetPublicKeyUseCase::new(cryptography_service_state.as_ref());
-    let response_model = use_case.interact().await.map_err(|e| {
-        status::Custom(
-            Status::InternalServerError,
-            Json(HttpErrorResponse {
-                error: e.to_string(),
-            })
-        )
+    let authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
     })?;
+    let use_case = GetPublicKeyUseCase::new(cryptography_service_state.as_ref());
+    let response_model = use_case
+        .interact(GetPublicKeyRequestModel {
+            tenant: authorization.caller().tenant().to_string(),
+        }).await
+        .map_err(|e| {
+            status::Custom(
+                Status::InternalServerError,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
     Ok(
         status::Custom(
             Status::Ok,
@@ -0,0 +1,48 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use rocket_okapi::openapi;
+use serde::Serialize;
+use std::sync::Arc;
+use crate::{
+    application::queries::list_api_keys_use_case::ListApiKeysUseCase,
+    infrastructure::{
+        guards::admin_authorization_request_guard::AdminAuthorizationHeader,
+        routes::{ api_key_response::ApiKeyResponse, http_error_response::HttpErrorResponse },
+        services::file_api_key_service::FileApiKeyService,
+    },
+};
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListApiKeysResponse {
+    api_keys: Vec<ApiKeyResponse>,
+}
+
+#[openapi]
+#[get("/api-keys")]
+pub async fn list_api_keys(
+    api_key_service_state: &State<Arc<FileApiKeyService>>,
+    authorization: Result<AdminAuthorizationHeader, (Status, String)>
+) -> Result<status::Custom<Json<ListApiKeysResponse>>, status::Custom<Json<HttpErrorResponse>>> {
+    let _authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let use_case = ListApiKeysUseCase::new(api_key_service_state.as_ref());
+    let response_model = use_case.interact().await.map_err(|e| {
+        status::Custom(
+            Status::InternalServerError,
+            Json(HttpErrorResponse {
+                error: e.to_string(),
+            })
+        )
+    })?;
+    Ok(
+        status::Custom(
+            Status::Ok,
+            Json(ListApiKeysResponse {
+                api_keys: response_model.api_keys.into_iter().map(ApiKeyResponse::from).collect(),
+            })
+        )
+    )
+}
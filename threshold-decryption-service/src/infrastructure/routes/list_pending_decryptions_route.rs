@@ -0,0 +1,68 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use rocket_okapi::openapi;
+use serde::Serialize;
+use std::sync::Arc;
+use crate::{
+    application::queries::list_pending_decryptions_use_case::{
+        ListPendingDecryptionsUseCase,
+        ListPendingDecryptionsRequestModel,
+    },
+    infrastructure::{
+        guards::{
+            scope_request_guard::{ Scoped, ApproveScope },
+            rate_limiter_request_guard::RateLimit,
+        },
+        routes::{
+            http_error_response::HttpErrorResponse,
+            pending_decryption_response::PendingDecryptionResponse,
+        },
+        services::in_memory_pending_decryption_service::InMemoryPendingDecryptionService,
+    },
+};
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPendingDecryptionsResponse {
+    pending_decryptions: Vec<PendingDecryptionResponse>,
+}
+
+#[openapi]
+#[get("/pending-decryptions")]
+pub async fn list_pending_decryptions(
+    pending_decryption_service_state: &State<Arc<InMemoryPendingDecryptionService>>,
+    _rate_limit: RateLimit,
+    authorization: Result<Scoped<ApproveScope>, (Status, String)>
+) -> Result<
+    status::Custom<Json<ListPendingDecryptionsResponse>>,
+    status::Custom<Json<HttpErrorResponse>>
+> {
+    let authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let use_case = ListPendingDecryptionsUseCase::new(pending_decryption_service_state.as_ref());
+    let response_model = use_case
+        .interact(ListPendingDecryptionsRequestModel {
+            tenant: authorization.caller().tenant().to_string(),
+        }).await
+        .map_err(|e| {
+            status::Custom(
+                Status::InternalServerError,
+                Json(HttpErrorResponse {
+                    error: e.to_string(),
+                })
+            )
+        })?;
+    Ok(
+        status::Custom(
+            Status::Ok,
+            Json(ListPendingDecryptionsResponse {
+                pending_decryptions: response_model.pending_decryptions
+                    .into_iter()
+                    .map(PendingDecryptionResponse::from)
+                    .collect(),
+            })
+        )
+    )
+}
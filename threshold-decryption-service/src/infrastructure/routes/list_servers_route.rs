@@ -0,0 +1,133 @@
+use std::{ sync::Arc, time::{ SystemTime, UNIX_EPOCH } };
+use hex_fmt::HexFmt;
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use rocket_okapi::openapi;
+use serde::Serialize;
+use crate::{
+    application::queries::list_servers_use_case::{ ListServersUseCase, ListServersRequestModel },
+    domain::entities::decryption_server::{ KeyFingerprint, ServerState },
+    infrastructure::{
+        guards::admin_authorization_request_guard::AdminAuthorizationHeader,
+        routes::http_error_response::HttpErrorResponse,
+        services::{
+            in_memory_server_registry_service::InMemoryServerRegistryService,
+            pairing_cryptography_service::PairingCryptographyService,
+        },
+    },
+};
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyFingerprintResponse {
+    key_id: String,
+    /// Fingerprint of the public key share the server holds.
+    fingerprint: String,
+    /// Fingerprint of the public key the share was verified against.
+    public_key_fingerprint: String,
+}
+
+impl From<KeyFingerprint> for KeyFingerprintResponse {
+    fn from(key_fingerprint: KeyFingerprint) -> Self {
+        Self {
+            key_id: key_fingerprint.key_id,
+            fingerprint: key_fingerprint.fingerprint,
+            public_key_fingerprint: key_fingerprint.public_key_fingerprint,
+        }
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerResponse {
+    id: usize,
+    /// `alive`, `silent` (no recent heartbeat) or `unknown` (never seen).
+    status: String,
+    /// `synced`, `stale` (missing or outdated key shares) or `unknown`.
+    key_state: String,
+    stale_key_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    /// Hex-encoded Ed25519 public key the heartbeats are signed with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identity_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_seen_at: Option<u64>,
+    /// Messages consumed between the last two heartbeats.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    load: Option<u64>,
+    key_fingerprints: Vec<KeyFingerprintResponse>,
+}
+
+impl From<ServerState> for ServerResponse {
+    fn from(server: ServerState) -> Self {
+        let mut response = Self {
+            id: server.id,
+            status: server.liveness.as_str().to_string(),
+            key_state: server.key_state.as_str().to_string(),
+            stale_key_ids: server.stale_key_ids,
+            version: None,
+            identity_key: None,
+            last_seen_at: None,
+            load: None,
+            key_fingerprints: Vec::new(),
+        };
+        if let Some(registration) = server.registration {
+            let heartbeat = registration.heartbeat;
+            response.version = Some(heartbeat.version);
+            response.identity_key = Some(HexFmt(&heartbeat.identity_key).to_string());
+            response.last_seen_at = Some(registration.last_seen_at);
+            response.load = Some(heartbeat.load);
+            response.key_fingerprints = heartbeat.key_fingerprints
+                .into_iter()
+                .map(KeyFingerprintResponse::from)
+                .collect();
+        }
+        response
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListServersResponse {
+    servers: Vec<ServerResponse>,
+}
+
+/// Decryption Servers of the cluster, as known from their heartbeats.
+#[openapi]
+#[get("/servers")]
+pub async fn list_servers(
+    server_registry_service_state: &State<Arc<InMemoryServerRegistryService>>,
+    cryptography_service_state: &State<Arc<PairingCryptographyService>>,
+    authorization: Result<AdminAuthorizationHeader, (Status, String)>
+) -> Result<status::Custom<Json<ListServersResponse>>, status::Custom<Json<HttpErrorResponse>>> {
+    let _authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let cryptography_service = cryptography_service_state.as_ref();
+    let request_model = ListServersRequestModel {
+        expected_key_fingerprints: (0..cryptography_service.n_servers())
+            .map(|id| cryptography_service.key_share_fingerprints(id))
+            .collect(),
+        now: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+        silent_after_secs: cryptography_service.heartbeat_timeout().as_secs(),
+    };
+    let use_case = ListServersUseCase::new(server_registry_service_state.as_ref());
+    let response_model = use_case.interact(request_model).await.map_err(|e| {
+        status::Custom(
+            Status::InternalServerError,
+            Json(HttpErrorResponse {
+                error: e.to_string(),
+            })
+        )
+    })?;
+    Ok(
+        status::Custom(
+            Status::Ok,
+            Json(ListServersResponse {
+                servers: response_model.servers.into_iter().map(ServerResponse::from).collect(),
+            })
+        )
+    )
+}
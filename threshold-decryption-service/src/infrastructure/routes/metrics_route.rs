@@ -0,0 +1,12 @@
+use rocket::{ State, http::ContentType };
+use rocket_okapi::openapi;
+use std::sync::Arc;
+use crate::infrastructure::services::service_metrics::ServiceMetrics;
+
+/// Metrics in the Prometheus text exposition format.
+#[openapi]
+#[get("/metrics")]
+pub fn metrics(metrics_state: &State<Arc<ServiceMetrics>>) -> (ContentType, String) {
+    let content_type = ContentType::new("text", "plain").with_params(("version", "0.0.4"));
+    (content_type, metrics_state.render())
+}
@@ -3,3 +3,20 @@ pub mod http_error_response;
 pub mod get_public_key_route;
 pub mod decrypt_message_route;
 pub mod encrypt_message_route;
+pub mod generate_data_key_route;
+pub mod decrypt_data_key_route;
+pub mod audit_event_response;
+pub mod get_audit_events_route;
+pub mod export_audit_events_route;
+pub mod api_key_response;
+pub mod create_api_key_route;
+pub mod list_api_keys_route;
+pub mod revoke_api_key_route;
+pub mod rotate_api_key_route;
+pub mod pending_decryption_response;
+pub mod list_pending_decryptions_route;
+pub mod get_pending_decryption_route;
+pub mod decide_pending_decryption_route;
+pub mod metrics_route;
+pub mod readyz_route;
+pub mod list_servers_route;
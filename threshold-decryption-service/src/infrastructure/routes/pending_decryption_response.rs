@@ -0,0 +1,50 @@
+use serde::Serialize;
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use crate::domain::entities::{
+    audit_event::ciphertext_fingerprint,
+    pending_decryption::PendingDecryption,
+};
+
+/// A decryption waiting for, or done after, the approval of its tenant's approvers. The ciphertext
+/// is only referenced through its fingerprint.
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingDecryptionResponse {
+    pub id: String,
+    pub tenant: String,
+    pub requester: String,
+    pub reason: Option<String>,
+    pub ciphertext_fingerprint: String,
+    /// One of `pending`, `approved`, `completed`, `failed`, `denied` or `expired`.
+    pub status: String,
+    pub required_approvals: usize,
+    pub approvers: Vec<String>,
+    pub denied_by: Option<String>,
+    pub created_at: u64,
+    pub expires_at: u64,
+    /// Only returned to the requester, once completed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decrypted_message: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<PendingDecryption> for PendingDecryptionResponse {
+    fn from(pending_decryption: PendingDecryption) -> Self {
+        Self {
+            ciphertext_fingerprint: ciphertext_fingerprint(&pending_decryption.message),
+            status: pending_decryption.status.as_str().to_string(),
+            id: pending_decryption.id,
+            tenant: pending_decryption.tenant,
+            requester: pending_decryption.requester,
+            reason: pending_decryption.reason,
+            required_approvals: pending_decryption.required_approvals,
+            approvers: pending_decryption.approvers,
+            denied_by: pending_decryption.denied_by,
+            created_at: pending_decryption.created_at,
+            expires_at: pending_decryption.expires_at,
+            decrypted_message: pending_decryption.decrypted_message,
+            error: pending_decryption.error,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::openapi;
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::Arc;
+use crate::{
+    application::queries::check_readiness_use_case::{
+        CheckReadinessUseCase,
+        CheckReadinessRequestModel,
+    },
+    domain::entities::readiness::{ ReadinessStatus, ServerReadiness },
+    infrastructure::services::{
+        http_cluster_health_service::HttpClusterHealthService,
+        pairing_cryptography_service::PairingCryptographyService,
+    },
+};
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerReadinessResponse {
+    id: usize,
+    available: bool,
+    reachable: bool,
+    broker_connected: bool,
+    key_shares_loaded: bool,
+    /// Key ids whose share is missing or outdated, making the server unavailable.
+    stale_key_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl From<ServerReadiness> for ServerReadinessResponse {
+    fn from(server: ServerReadiness) -> Self {
+        Self {
+            id: server.id,
+            available: server.is_available(),
+            reachable: server.reachable,
+            broker_connected: server.broker_connected,
+            key_shares_loaded: server.key_shares_loaded,
+            stale_key_ids: server.stale_key_ids,
+            error: server.error,
+        }
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessResponse {
+    /// `ready`, `degraded` (quorum reachable with some servers down) or `unavailable`.
+    status: String,
+    broker_connected: bool,
+    /// Threshold + 1 servers, the quorum a decryption needs.
+    required_servers: usize,
+    available_servers: usize,
+    servers: Vec<ServerReadinessResponse>,
+}
+
+/// Answers `200 OK` while decryptions can reach a quorum, degraded or not, and
+/// `503 Service Unavailable` otherwise.
+#[openapi]
+#[get("/readyz")]
+pub async fn readyz(
+    cluster_health_service_state: &State<Arc<HttpClusterHealthService>>,
+    cryptography_service_state: &State<Arc<PairingCryptographyService>>
+) -> status::Custom<Json<ReadinessResponse>> {
+    let use_case = CheckReadinessUseCase::new(cluster_health_service_state.as_ref());
+    let readiness = use_case
+        .interact(CheckReadinessRequestModel {
+            threshold: cryptography_service_state.threshold(),
+        }).await.readiness;
+    let status = match readiness.status {
+        ReadinessStatus::Unavailable => Status::ServiceUnavailable,
+        _ => Status::Ok,
+    };
+    status::Custom(
+        status,
+        Json(ReadinessResponse {
+            status: readiness.status.as_str().to_string(),
+            broker_connected: readiness.broker_connected,
+            required_servers: readiness.required_servers,
+            available_servers: readiness.available_servers,
+            servers: readiness.servers.into_iter().map(ServerReadinessResponse::from).collect(),
+        })
+    )
+}
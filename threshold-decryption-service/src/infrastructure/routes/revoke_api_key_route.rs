@@ -0,0 +1,41 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::openapi;
+use std::sync::Arc;
+use crate::{
+    application::commands::revoke_api_key_use_case::{
+        RevokeApiKeyUseCase,
+        RevokeApiKeyRequestModel,
+        RevokeApiKeyError,
+    },
+    infrastructure::{
+        guards::admin_authorization_request_guard::AdminAuthorizationHeader,
+        routes::{ api_key_response::ApiKeyResponse, http_error_response::HttpErrorResponse },
+        services::file_api_key_service::FileApiKeyService,
+    },
+};
+
+#[openapi]
+#[delete("/api-keys/<id>")]
+pub async fn revoke_api_key(
+    api_key_service_state: &State<Arc<FileApiKeyService>>,
+    authorization: Result<AdminAuthorizationHeader, (Status, String)>,
+    id: String
+) -> Result<status::Custom<Json<ApiKeyResponse>>, status::Custom<Json<HttpErrorResponse>>> {
+    let _authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let use_case = RevokeApiKeyUseCase::new(api_key_service_state.as_ref());
+    let response_model = use_case.interact(RevokeApiKeyRequestModel { id }).await.map_err(|e| {
+        let status = match e {
+            RevokeApiKeyError::ApiKeyNotFound(_) => Status::NotFound,
+            _ => Status::InternalServerError,
+        };
+        status::Custom(
+            status,
+            Json(HttpErrorResponse {
+                error: e.to_string(),
+            })
+        )
+    })?;
+    Ok(status::Custom(Status::Ok, Json(ApiKeyResponse::from(response_model.api_key))))
+}
@@ -0,0 +1,53 @@
+use rocket::{ State, http::Status, response::status, serde::json::Json };
+use rocket_okapi::openapi;
+use std::sync::Arc;
+use crate::{
+    application::commands::rotate_api_key_use_case::{
+        RotateApiKeyUseCase,
+        RotateApiKeyRequestModel,
+        RotateApiKeyError,
+    },
+    infrastructure::{
+        guards::admin_authorization_request_guard::AdminAuthorizationHeader,
+        routes::{
+            api_key_response::{ ApiKeyResponse, ApiKeySecretResponse },
+            http_error_response::HttpErrorResponse,
+        },
+        services::file_api_key_service::FileApiKeyService,
+    },
+};
+
+#[openapi]
+#[post("/api-keys/<id>/rotate")]
+pub async fn rotate_api_key(
+    api_key_service_state: &State<Arc<FileApiKeyService>>,
+    authorization: Result<AdminAuthorizationHeader, (Status, String)>,
+    id: String
+) -> Result<status::Custom<Json<ApiKeySecretResponse>>, status::Custom<Json<HttpErrorResponse>>> {
+    let _authorization = authorization.map_err(|(status, error)| {
+        status::Custom(status, Json(HttpErrorResponse { error }))
+    })?;
+    let use_case = RotateApiKeyUseCase::new(api_key_service_state.as_ref());
+    let response_model = use_case.interact(RotateApiKeyRequestModel { id }).await.map_err(|e| {
+        let status = match e {
+            RotateApiKeyError::ApiKeyNotFound(_) => Status::NotFound,
+            RotateApiKeyError::ApiKeyRevoked(_) => Status::Conflict,
+            _ => Status::InternalServerError,
+        };
+        status::Custom(
+            status,
+            Json(HttpErrorResponse {
+                error: e.to_string(),
+            })
+        )
+    })?;
+    Ok(
+        status::Custom(
+            Status::Ok,
+            Json(ApiKeySecretResponse {
+                api_key: response_model.secret,
+                metadata: ApiKeyResponse::from(response_model.api_key),
+            })
+        )
+    )
+}
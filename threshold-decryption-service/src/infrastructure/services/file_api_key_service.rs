@@ -0,0 +1,94 @@
+use std::{ collections::HashMap, path::PathBuf };
+use async_trait::async_trait;
+use thiserror::Error;
+use tokio::{ fs, sync::RwLock };
+use crate::domain::{
+    entities::api_key::ApiKey,
+    services::api_key_service::{ ApiKeyService, ApiKeyServiceError },
+};
+
+#[derive(Error, Debug)]
+pub enum FileApiKeyServiceError {
+    #[error("Unable to load API keys file. {0}")] InvalidInitialization(String),
+}
+
+/// API keys kept in memory and persisted as a JSON file, rewritten atomically on every change.
+pub struct FileApiKeyService {
+    path: PathBuf,
+    api_keys: RwLock<HashMap<String, ApiKey>>,
+}
+
+impl FileApiKeyService {
+    pub async fn new(path: impl Into<PathBuf>) -> Result<Self, FileApiKeyServiceError> {
+        let path = path.into();
+        let api_keys: Vec<ApiKey> = match fs::read_to_string(&path).await {
+            Ok(content) =>
+                serde_json
+                    ::from_str(&content)
+                    .map_err(|e| FileApiKeyServiceError::InvalidInitialization(e.to_string()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(FileApiKeyServiceError::InvalidInitialization(e.to_string()));
+            }
+        };
+        Ok(Self {
+            path,
+            api_keys: RwLock::new(
+                api_keys
+                    .into_iter()
+                    .map(|api_key| (api_key.id.clone(), api_key))
+                    .collect()
+            ),
+        })
+    }
+
+    async fn persist(&self, api_keys: &HashMap<String, ApiKey>) -> Result<(), ApiKeyServiceError> {
+        let mut api_keys: Vec<&ApiKey> = api_keys.values().collect();
+        api_keys.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        let content = serde_json
+            ::to_vec_pretty(&api_keys)
+            .map_err(|e| ApiKeyServiceError::StorageError(e.to_string()))?;
+        let mut temporary_path = self.path.clone().into_os_string();
+        temporary_path.push(".tmp");
+        fs
+            ::write(&temporary_path, content).await
+            .map_err(|e| ApiKeyServiceError::StorageError(e.to_string()))?;
+        fs
+            ::rename(&temporary_path, &self.path).await
+            .map_err(|e| ApiKeyServiceError::StorageError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl ApiKeyService for FileApiKeyService {
+    async fn save(&self, api_key: ApiKey) -> Result<(), ApiKeyServiceError> {
+        let mut api_keys = self.api_keys.write().await;
+        let previous_api_key = api_keys.insert(api_key.id.clone(), api_key.clone());
+        if let Err(e) = self.persist(&api_keys).await {
+            match previous_api_key {
+                Some(previous_api_key) => api_keys.insert(api_key.id, previous_api_key),
+                None => api_keys.remove(&api_key.id),
+            };
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    async fn find(&self, id: String) -> Result<Option<ApiKey>, ApiKeyServiceError> {
+        Ok(self.api_keys.read().await.get(&id).cloned())
+    }
+
+    async fn find_by_hash(&self, key_hash: String) -> Result<Option<ApiKey>, ApiKeyServiceError> {
+        Ok(
+            self.api_keys
+                .read().await
+                .values()
+                .find(|api_key| api_key.key_hash == key_hash)
+                .cloned()
+        )
+    }
+
+    async fn list(&self) -> Result<Vec<ApiKey>, ApiKeyServiceError> {
+        Ok(self.api_keys.read().await.values().cloned().collect())
+    }
+}
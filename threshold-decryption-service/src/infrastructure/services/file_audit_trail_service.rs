@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+use async_trait::async_trait;
+use thiserror::Error;
+use tokio::{ fs::{ self, File, OpenOptions }, io::AsyncWriteExt, sync::Mutex };
+use crate::domain::{
+    entities::audit_event::{ AuditEvent, AuditQuery },
+    services::audit_trail_service::{ AuditTrailService, AuditTrailServiceError },
+};
+
+#[derive(Error, Debug)]
+pub enum FileAuditTrailServiceError {
+    #[error("Unable to open audit trail file. {0}")] InvalidInitialization(String),
+}
+
+/// Append-only JSON-lines audit trail, one event per line, which SIEMs can ingest as is.
+pub struct FileAuditTrailService {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl FileAuditTrailService {
+    pub async fn new(path: impl Into<PathBuf>) -> Result<Self, FileAuditTrailServiceError> {
+        let path = path.into();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path).await
+            .map_err(|e| FileAuditTrailServiceError::InvalidInitialization(e.to_string()))?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait]
+impl AuditTrailService for FileAuditTrailService {
+    async fn record(&self, event: AuditEvent) -> Result<(), AuditTrailServiceError> {
+        let mut line = serde_json
+            ::to_vec(&event)
+            .map_err(|e| AuditTrailServiceError::RecordError(e.to_string()))?;
+        line.push(b'\n');
+        let mut file = self.file.lock().await;
+        file
+            .write_all(&line).await
+            .map_err(|e| AuditTrailServiceError::RecordError(e.to_string()))?;
+        file.sync_data().await.map_err(|e| AuditTrailServiceError::RecordError(e.to_string()))
+    }
+
+    async fn query(&self, query: AuditQuery) -> Result<Vec<AuditEvent>, AuditTrailServiceError> {
+        let content = {
+            // Holding the lock guarantees no event is read while half written.
+            let _file = self.file.lock().await;
+            fs
+                ::read_to_string(&self.path).await
+                .map_err(|e| AuditTrailServiceError::QueryError(e.to_string()))?
+        };
+        let mut events = Vec::new();
+        for line in content.lines().filter(|line| !line.is_empty()) {
+            let event: AuditEvent = serde_json
+                ::from_str(line)
+                .map_err(|e| AuditTrailServiceError::QueryError(e.to_string()))?;
+            if query.matches(&event) {
+                events.push(event);
+            }
+            if query.limit.is_some_and(|limit| events.len() >= limit) {
+                break;
+            }
+        }
+        Ok(events)
+    }
+}
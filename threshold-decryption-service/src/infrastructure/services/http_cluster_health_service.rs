@@ -0,0 +1,107 @@
+use std::{ sync::Arc, time::Duration };
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::task::JoinSet;
+use crate::{
+    domain::{
+        entities::{
+            decryption_server::{ stale_key_ids, KeyFingerprint },
+            readiness::ServerReadiness,
+        },
+        services::cluster_health_service::ClusterHealthService,
+    },
+    infrastructure::services::pairing_cryptography_service::{
+        PairingCryptographyService,
+        ServedKey,
+    },
+};
+
+/// Body of the `GET /readyz` end-point of the Decryption Servers.
+#[derive(Deserialize)]
+struct ServerReadyz {
+    key_shares_loaded: bool,
+    broker_connected: bool,
+    keys: Vec<ServedKey>,
+}
+
+/// Probes the status end-point of every Decryption Server, the URL of server `id` being
+/// `status_urls[id]`.
+pub struct HttpClusterHealthService {
+    client: reqwest::Client,
+    status_urls: Vec<String>,
+    cryptography_service: Arc<PairingCryptographyService>,
+}
+
+impl HttpClusterHealthService {
+    pub fn new(
+        cryptography_service: Arc<PairingCryptographyService>,
+        status_urls: Vec<String>,
+        probe_timeout: Duration
+    ) -> Self {
+        Self {
+            client: reqwest::Client::builder().timeout(probe_timeout).build().unwrap(),
+            status_urls,
+            cryptography_service,
+        }
+    }
+
+    /// Probes server `id`, comparing the key shares it serves with `expected_key_fingerprints`.
+    async fn probe_server(
+        client: reqwest::Client,
+        id: usize,
+        status_url: String,
+        expected_key_fingerprints: Vec<KeyFingerprint>
+    ) -> ServerReadiness {
+        let readyz_url = format!("{}/readyz", status_url.trim_end_matches('/'));
+        let response = client.get(readyz_url).send().await;
+        // Not ready servers answer `503 Service Unavailable` with the same body.
+        let readyz = match response {
+            Ok(response) => response.json::<ServerReadyz>().await,
+            Err(e) => Err(e),
+        };
+        match readyz {
+            Ok(readyz) => {
+                let key_fingerprints: Vec<KeyFingerprint> = readyz.keys
+                    .into_iter()
+                    .map(KeyFingerprint::from)
+                    .collect();
+                ServerReadiness {
+                    id,
+                    reachable: true,
+                    broker_connected: readyz.broker_connected,
+                    key_shares_loaded: readyz.key_shares_loaded,
+                    stale_key_ids: stale_key_ids(&expected_key_fingerprints, &key_fingerprints),
+                    error: None,
+                }
+            }
+            Err(e) => ServerReadiness::unreachable(id, e.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl ClusterHealthService for HttpClusterHealthService {
+    async fn is_broker_connected(&self) -> bool {
+        self.cryptography_service.is_broker_connected().await
+    }
+
+    async fn probe_servers(&self) -> Vec<ServerReadiness> {
+        let mut probes = JoinSet::new();
+        for (id, status_url) in self.status_urls.iter().enumerate() {
+            probes.spawn(
+                Self::probe_server(
+                    self.client.clone(),
+                    id,
+                    status_url.clone(),
+                    self.cryptography_service.key_share_fingerprints(id)
+                )
+            );
+        }
+        let mut servers = Vec::new();
+        while let Some(server) = probes.join_next().await {
+            servers.push(server.unwrap());
+        }
+        servers.sort_by_key(|server| server.id);
+        servers
+    }
+}
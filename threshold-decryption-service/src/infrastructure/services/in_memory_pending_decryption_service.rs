@@ -0,0 +1,74 @@
+use std::{ collections::HashMap, time::{ SystemTime, UNIX_EPOCH } };
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+use crate::domain::{
+    entities::pending_decryption::{ ApprovalDecision, PendingDecryption },
+    services::pending_decryption_service::{
+        PendingDecryptionService,
+        PendingDecryptionServiceError,
+    },
+};
+
+/// Pending decryptions kept in memory only, since they hold ciphertexts and, once completed,
+/// plaintexts. Requests are forgotten once expired, and lost on restart.
+#[derive(Default)]
+pub struct InMemoryPendingDecryptionService {
+    pending_decryptions: RwLock<HashMap<String, PendingDecryption>>,
+}
+
+impl InMemoryPendingDecryptionService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PendingDecryptionService for InMemoryPendingDecryptionService {
+    async fn save(
+        &self,
+        pending_decryption: PendingDecryption
+    ) -> Result<(), PendingDecryptionServiceError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut pending_decryptions = self.pending_decryptions.write().await;
+        pending_decryptions.retain(|_, pending_decryption| !pending_decryption.is_expired(now));
+        pending_decryptions.insert(pending_decryption.id.clone(), pending_decryption);
+        Ok(())
+    }
+
+    async fn find(
+        &self,
+        id: String
+    ) -> Result<Option<PendingDecryption>, PendingDecryptionServiceError> {
+        Ok(self.pending_decryptions.read().await.get(&id).cloned())
+    }
+
+    async fn list(
+        &self,
+        tenant: String
+    ) -> Result<Vec<PendingDecryption>, PendingDecryptionServiceError> {
+        Ok(
+            self.pending_decryptions
+                .read().await
+                .values()
+                .filter(|pending_decryption| pending_decryption.tenant == tenant)
+                .cloned()
+                .collect()
+        )
+    }
+
+    async fn decide(
+        &self,
+        id: String,
+        decision: ApprovalDecision,
+        now: u64
+    ) -> Result<PendingDecryption, PendingDecryptionServiceError> {
+        let mut pending_decryptions = self.pending_decryptions.write().await;
+        let pending_decryption = pending_decryptions
+            .get_mut(&id)
+            .ok_or(PendingDecryptionServiceError::NotFound(id))?;
+        pending_decryption
+            .decide(&decision, now)
+            .map_err(|e| PendingDecryptionServiceError::RejectedDecision(e.to_string()))?;
+        Ok(pending_decryption.clone())
+    }
+}
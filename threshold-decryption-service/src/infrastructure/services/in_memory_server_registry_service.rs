@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+use crate::domain::{
+    entities::decryption_server::RegisteredServer,
+    services::server_registry_service::{ ServerRegistryService, ServerRegistryServiceError },
+};
+
+/// Decryption Servers as last seen by this instance of the service, rebuilt from heartbeats after
+/// a restart. Unless enrolled, identity keys are trusted on first use.
+#[derive(Default)]
+pub struct InMemoryServerRegistryService {
+    enrolled_identity_keys: HashMap<usize, Vec<u8>>,
+    servers: RwLock<HashMap<usize, RegisteredServer>>,
+}
+
+impl InMemoryServerRegistryService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Identity keys of the servers, by server id, rejecting heartbeats signed with any other key.
+    pub fn with_enrolled_identity_keys(mut self, identity_keys: Vec<Vec<u8>>) -> Self {
+        self.enrolled_identity_keys = identity_keys.into_iter().enumerate().collect();
+        self
+    }
+}
+
+#[async_trait]
+impl ServerRegistryService for InMemoryServerRegistryService {
+    async fn identity_key(&self, id: usize) -> Result<Option<Vec<u8>>, ServerRegistryServiceError> {
+        if let Some(identity_key) = self.enrolled_identity_keys.get(&id) {
+            return Ok(Some(identity_key.clone()));
+        }
+        Ok(
+            self.servers
+                .read().await
+                .get(&id)
+                .map(|server| server.heartbeat.identity_key.clone())
+        )
+    }
+
+    async fn enrolled_identity_key(
+        &self,
+        id: usize
+    ) -> Result<Option<Vec<u8>>, ServerRegistryServiceError> {
+        Ok(self.enrolled_identity_keys.get(&id).cloned())
+    }
+
+    async fn save(&self, server: RegisteredServer) -> Result<(), ServerRegistryServiceError> {
+        self.servers.write().await.insert(server.heartbeat.id, server);
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<RegisteredServer>, ServerRegistryServiceError> {
+        let mut servers: Vec<RegisteredServer> = self.servers
+            .read().await
+            .values()
+            .cloned()
+            .collect();
+        servers.sort_by_key(|server| server.heartbeat.id);
+        Ok(servers)
+    }
+}
@@ -0,0 +1,272 @@
+use std::{ collections::BTreeMap, fs };
+use jsonwebtoken::{ decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation };
+use serde_json::Value;
+use thiserror::Error;
+use crate::domain::entities::{ caller::Caller, scope::{ scopes_for_role, Grant } };
+
+#[derive(Error, Debug)]
+pub enum JwtVerifierError {
+    #[error("Invalid JWT verifier configuration. {0}")] InvalidConfiguration(String),
+    #[error("Invalid access token. {0}")] InvalidToken(String),
+}
+
+fn string_list(claim: Option<&Value>) -> Vec<&str> {
+    match claim {
+        Some(Value::String(value)) => value.split_whitespace().collect(),
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Grants carried by the `scope`/`scp` claims and by the roles of the `roles` claim. Scopes this
+/// service does not know are ignored, as tokens may also be meant for other services.
+fn grants(claims: &BTreeMap<String, Value>) -> Vec<Grant> {
+    let mut grants: Vec<Grant> = string_list(claims.get("scope"))
+        .into_iter()
+        .chain(string_list(claims.get("scp")))
+        .filter_map(|scope| scope.parse().ok())
+        .collect();
+    for role in string_list(claims.get("roles")) {
+        grants.extend(
+            scopes_for_role(role)
+                .iter()
+                .map(|scope| Grant { scope: *scope, key_id: None })
+        );
+    }
+    grants
+}
+
+/// Verifies bearer JWTs signed with HS256 (shared secret) or with RS256/EdDSA keys from a local
+/// JWKS file, and checks their `exp`, `nbf`, `aud` and `iss` claims.
+pub struct JwtVerifier {
+    hs256_secret: Option<DecodingKey>,
+    jwks: JwkSet,
+    audience: Option<Vec<String>>,
+    issuer: Option<Vec<String>>,
+    leeway_in_secs: u64,
+}
+
+impl Default for JwtVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JwtVerifier {
+    pub fn new() -> Self {
+        Self {
+            hs256_secret: None,
+            jwks: JwkSet { keys: Vec::new() },
+            audience: None,
+            issuer: None,
+            leeway_in_secs: 0,
+        }
+    }
+
+    pub fn with_hs256_secret(mut self, secret: &[u8]) -> Self {
+        self.hs256_secret = Some(DecodingKey::from_secret(secret));
+        self
+    }
+
+    pub fn with_jwks(mut self, jwks: JwkSet) -> Self {
+        self.jwks = jwks;
+        self
+    }
+
+    pub fn with_jwks_file(self, path: &str) -> Result<Self, JwtVerifierError> {
+        let content = fs
+            ::read_to_string(path)
+            .map_err(|e| JwtVerifierError::InvalidConfiguration(e.to_string()))?;
+        let jwks: JwkSet = serde_json
+            ::from_str(&content)
+            .map_err(|e| JwtVerifierError::InvalidConfiguration(e.to_string()))?;
+        Ok(self.with_jwks(jwks))
+    }
+
+    pub fn with_audience(mut self, audience: Vec<String>) -> Self {
+        self.audience = Some(audience);
+        self
+    }
+
+    pub fn with_issuer(mut self, issuer: Vec<String>) -> Self {
+        self.issuer = Some(issuer);
+        self
+    }
+
+    pub fn with_leeway(mut self, leeway_in_secs: u64) -> Self {
+        self.leeway_in_secs = leeway_in_secs;
+        self
+    }
+
+    pub fn has_keys(&self) -> bool {
+        self.hs256_secret.is_some() || !self.jwks.keys.is_empty()
+    }
+
+    fn decoding_key(&self, algorithm: Algorithm, kid: Option<&str>) -> Result<DecodingKey, String> {
+        match algorithm {
+            Algorithm::HS256 =>
+                self.hs256_secret.clone().ok_or("HS256 tokens are not accepted".to_string()),
+            Algorithm::RS256 | Algorithm::EdDSA => {
+                let jwk = match kid {
+                    Some(kid) => self.jwks.find(kid),
+                    None if self.jwks.keys.len() == 1 => self.jwks.keys.first(),
+                    None => None,
+                };
+                let jwk = jwk.ok_or("Unknown signing key".to_string())?;
+                DecodingKey::from_jwk(jwk).map_err(|e| e.to_string())
+            }
+            algorithm => Err(format!("Unsupported algorithm {:?}", algorithm)),
+        }
+    }
+
+    pub fn verify(&self, token: &str) -> Result<Caller, JwtVerifierError> {
+        let header = decode_header(token).map_err(|e|
+            JwtVerifierError::InvalidToken(e.to_string())
+        )?;
+        let decoding_key = self
+            .decoding_key(header.alg, header.kid.as_deref())
+            .map_err(JwtVerifierError::InvalidToken)?;
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = self.leeway_in_secs;
+        validation.validate_nbf = true;
+        let mut required_claims = vec!["exp", "sub"];
+        match &self.audience {
+            Some(audience) => {
+                validation.set_audience(audience);
+                required_claims.push("aud");
+            }
+            None => {
+                validation.validate_aud = false;
+            }
+        }
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(issuer);
+            required_claims.push("iss");
+        }
+        validation.set_required_spec_claims(&required_claims);
+        let claims = decode::<BTreeMap<String, Value>>(token, &decoding_key, &validation)
+            .map_err(|e| JwtVerifierError::InvalidToken(e.to_string()))?.claims;
+        Ok(Caller {
+            subject: claims
+                .get("sub")
+                .and_then(Value::as_str)
+                .ok_or(JwtVerifierError::InvalidToken("Invalid `sub` claim".to_string()))?
+                .to_string(),
+            issuer: claims.get("iss").and_then(Value::as_str).map(str::to_string),
+            audience: match claims.get("aud") {
+                Some(Value::String(audience)) => vec![audience.clone()],
+                Some(Value::Array(audience)) =>
+                    audience.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+                _ => Vec::new(),
+            },
+            expires_at: claims.get("exp").and_then(Value::as_u64),
+            tenant: claims.get("tenant").and_then(Value::as_str).map(str::to_string),
+            grants: grants(&claims),
+            claims,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{ SystemTime, UNIX_EPOCH };
+    use base64::{ engine::general_purpose, Engine };
+    use jsonwebtoken::{ encode, EncodingKey, Header };
+    use ring::{ rand::SystemRandom, signature::{ Ed25519KeyPair, KeyPair } };
+    use serde_json::json;
+    use crate::domain::entities::scope::Scope;
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    fn hs256_token(claims: Value) -> String {
+        let encoding_key = EncodingKey::from_secret(b"secret");
+        encode(&Header::new(Algorithm::HS256), &claims, &encoding_key).unwrap()
+    }
+
+    #[test]
+    fn should_verify_hs256_token() {
+        let verifier = JwtVerifier::new()
+            .with_hs256_secret(b"secret")
+            .with_audience(vec!["threshold-decryption-service".to_string()])
+            .with_issuer(vec!["https://issuer.example".to_string()]);
+        let token = hs256_token(
+            json!({
+                "sub": "billing-service",
+                "aud": "threshold-decryption-service",
+                "iss": "https://issuer.example",
+                "exp": now() + 60,
+                "tenant": "acme",
+                "scope": "encrypt:8c1f0b2d4e6a7c90 other:scope",
+                "roles": ["encryptor"],
+            })
+        );
+        let caller = verifier.verify(&token).unwrap();
+        assert!(caller.is_allowed(Scope::Encrypt, Some("8c1f0b2d4e6a7c90")));
+        assert!(caller.is_allowed(Scope::KeysRead, None));
+        assert!(!caller.is_allowed(Scope::Decrypt, Some("8c1f0b2d4e6a7c90")));
+        assert_eq!(caller.subject, "billing-service");
+        assert_eq!(caller.audience, vec!["threshold-decryption-service".to_string()]);
+        assert_eq!(caller.tenant(), "acme");
+    }
+
+    #[test]
+    fn should_reject_expired_or_misaddressed_tokens() {
+        let verifier = JwtVerifier::new()
+            .with_hs256_secret(b"secret")
+            .with_audience(vec!["threshold-decryption-service".to_string()]);
+        let expired_token = hs256_token(
+            json!({ "sub": "a", "aud": "threshold-decryption-service", "exp": now() - 120 })
+        );
+        let other_audience_token = hs256_token(
+            json!({ "sub": "a", "aud": "another-service", "exp": now() + 60 })
+        );
+        let missing_audience_token = hs256_token(json!({ "sub": "a", "exp": now() + 60 }));
+        let immature_token = hs256_token(
+            json!({
+                "sub": "a",
+                "aud": "threshold-decryption-service",
+                "exp": now() + 600,
+                "nbf": now() + 300,
+            })
+        );
+        assert!(verifier.verify(&expired_token).is_err());
+        assert!(verifier.verify(&other_audience_token).is_err());
+        assert!(verifier.verify(&missing_audience_token).is_err());
+        assert!(verifier.verify(&immature_token).is_err());
+        assert!(verifier.verify("not-a-jwt").is_err());
+    }
+
+    #[test]
+    fn should_verify_eddsa_token_with_jwks() {
+        let pkcs8_bytes = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8_bytes.as_ref()).unwrap();
+        let jwks: JwkSet = serde_json
+            ::from_value(
+                json!({
+                    "keys": [{
+                        "kty": "OKP",
+                        "crv": "Ed25519",
+                        "kid": "key-1",
+                        "alg": "EdDSA",
+                        "x": general_purpose::URL_SAFE_NO_PAD.encode(key_pair.public_key()),
+                    }]
+                })
+            )
+            .unwrap();
+        let verifier = JwtVerifier::new().with_jwks(jwks);
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some("key-1".to_string());
+        let claims = json!({ "sub": "billing-service", "exp": now() + 60 });
+        let encoding_key = EncodingKey::from_ed_der(pkcs8_bytes.as_ref());
+        let token = encode(&header, &claims, &encoding_key).unwrap();
+        assert_eq!(verifier.verify(&token).unwrap().subject, "billing-service");
+
+        header.kid = Some("key-2".to_string());
+        let unknown_key_token = encode(&header, &claims, &encoding_key).unwrap();
+        assert!(verifier.verify(&unknown_key_token).is_err());
+        assert!(verifier.verify(&hs256_token(claims)).is_err());
+    }
+}
@@ -1 +1,10 @@
 pub mod pairing_cryptography_service;
+pub mod file_audit_trail_service;
+pub mod jwt_verifier;
+pub mod file_api_key_service;
+pub mod request_signature_verifier;
+pub mod in_memory_pending_decryption_service;
+pub mod service_metrics;
+pub mod trace_context;
+pub mod http_cluster_health_service;
+pub mod in_memory_server_registry_service;
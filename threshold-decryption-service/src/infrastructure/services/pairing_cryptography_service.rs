@@ -1,8 +1,13 @@
-use std::{ error::Error, time::{ SystemTime, UNIX_EPOCH } };
-use ring::signature::{ Ed25519KeyPair, KeyPair };
+use std::{ error::Error, sync::{ Arc, Mutex }, time::{ Instant, SystemTime, UNIX_EPOCH } };
+use ring::{
+    digest,
+    rand::SecureRandom,
+    signature::{ Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519 },
+};
 use amqprs::{
     callbacks::{ DefaultChannelCallback, DefaultConnectionCallback },
     channel::{
+        Channel,
         BasicConsumeArguments,
         BasicPublishArguments,
         ExchangeDeclareArguments,
@@ -12,11 +17,20 @@ use amqprs::{
     connection::{ Connection, OpenConnectionArguments },
     consumer::AsyncConsumer,
     BasicProperties,
+    FieldTable,
+    FieldValue,
 };
 use tokio::time::{ timeout, Duration };
-use serde::{ Deserialize, Serialize };
-use tokio::sync::mpsc::{ Sender, Receiver, channel as tokio_channel };
-use std::collections::HashMap;
+use tiny_keccak::{ Hasher, Sha3 };
+use hex_fmt::HexFmt;
+use serde::{ de::DeserializeOwned, Deserialize, Serialize };
+use tokio::sync::{
+    mpsc::{ Receiver, Sender, channel as tokio_channel },
+    Mutex as AsyncMutex,
+    RwLock,
+};
+use tracing::{ info, info_span, warn, Instrument };
+use std::collections::{ BTreeSet, HashMap };
 use async_trait::async_trait;
 use thiserror::Error;
 use threshold_crypto::{
@@ -27,9 +41,58 @@ use threshold_crypto::{
     SecretKeySet,
     SecretKeyShare,
 };
-use crate::domain::services::cryptography_service::{
-    CryptographyService,
-    CryptographyServiceError,
+use crate::application::commands::{
+    accept_decryption_refusal_use_case::{
+        AcceptDecryptionRefusalUseCase,
+        AcceptDecryptionRefusalRequestModel,
+        AcceptDecryptionRefusalError,
+    },
+    acknowledge_key_sync_use_case::{
+        AcknowledgeKeySyncUseCase,
+        AcknowledgeKeySyncRequestModel,
+        AcknowledgeKeySyncError,
+    },
+    authorize_key_share_request_use_case::{
+        AuthorizeKeyShareRequestUseCase,
+        AuthorizeKeyShareRequestRequestModel,
+        AuthorizeKeyShareRequestError,
+    },
+    record_server_heartbeat_use_case::{
+        RecordServerHeartbeatUseCase,
+        RecordServerHeartbeatRequestModel,
+        RecordServerHeartbeatError,
+    },
+};
+use crate::domain::{
+    entities::{
+        caller::DEFAULT_TENANT,
+        ciphertext_envelope::{ CiphertextEnvelope, CiphertextScheme, KeyId },
+        decryption_server::{
+            DecryptionRefusal,
+            KeyFingerprint,
+            KeyShareRequest,
+            KeySyncAcknowledgement,
+            ServerHeartbeat,
+        },
+    },
+    services::{
+        cryptography_service::{
+            CryptographyService,
+            CryptographyServiceError,
+            DecryptedMessage,
+            DecryptionContext,
+            ServerRefusal,
+        },
+        server_registry_service::ServerRegistryService,
+    },
+};
+use crate::infrastructure::{
+    guards::request_id_fairing::generate_request_id,
+    services::{
+        in_memory_server_registry_service::InMemoryServerRegistryService,
+        service_metrics::ServiceMetrics,
+        trace_context,
+    },
 };
 
 #[derive(Error, Debug)]
@@ -37,14 +100,70 @@ pub enum PairingCryptographyServiceError {
     #[error("Unable to initialize instance. {0}")] InvalidInitialization(String),
 }
 
+const SHARES_QUEUE: &str = "decryption_service";
+const HEARTBEATS_EXCHANGE: &str = "heartbeats_exchange";
+const HEARTBEATS_QUEUE: &str = "decryption_service_heartbeats";
+const KEY_SYNC_EXCHANGE: &str = "key_sync_exchange";
+const KEY_SYNC_QUEUE: &str = "decryption_service_key_sync";
+const KEY_REQUESTS_EXCHANGE: &str = "key_requests_exchange";
+const KEY_REQUESTS_QUEUE: &str = "decryption_service_key_requests";
+const SECRETS_EXCHANGE: &str = "secrets_exchange";
+const REFUSALS_EXCHANGE: &str = "refusals_exchange";
+const REFUSALS_QUEUE: &str = "decryption_service_refusals";
+
 #[derive(Serialize, Deserialize, Debug)]
 struct PartialDecryption {
     id: usize,
     decryption_share: DecryptionShare,
+    /// Id of the request the share answers, copied from the `request_id` header.
+    request_id: Option<String>,
+}
+
+/// Senders awaiting the replies of the Decryption Servers to in-flight requests, by request id.
+type Awaited<T> = Arc<Mutex<HashMap<String, Sender<T>>>>;
+
+/// Reply of a Decryption Server to a decryption request.
+enum ServerReply {
+    Share(DecryptionShare),
+    Refusal(ServerRefusal),
 }
 
+type AwaitedReplies = Awaited<(usize, ServerReply)>;
+
+/// Stops awaiting the replies to a request once it completes, fails or times out.
+struct AwaitedEntry<'a, T> {
+    awaited: &'a Awaited<T>,
+    request_id: String,
+}
+
+impl<'a, T> AwaitedEntry<'a, T> {
+    /// Starts awaiting the replies to `request_id`, `None` when a request with the same id is
+    /// already in flight, whose replies must not be diverted.
+    fn register(
+        awaited: &'a Awaited<T>,
+        request_id: String,
+        capacity: usize
+    ) -> Option<(Self, Receiver<T>)> {
+        let mut awaited_requests = awaited.lock().unwrap();
+        if awaited_requests.contains_key(&request_id) {
+            return None;
+        }
+        let (sender, receiver) = tokio_channel(capacity);
+        awaited_requests.insert(request_id.clone(), sender);
+        Some((Self { awaited, request_id }, receiver))
+    }
+}
+
+impl<T> Drop for AwaitedEntry<'_, T> {
+    fn drop(&mut self) {
+        self.awaited.lock().unwrap().remove(&self.request_id);
+    }
+}
+
+/// Single consumer of the shares queue, handing every share to the decryption awaiting it.
 struct DecryptionConsumer {
-    sender: Sender<(usize, DecryptionShare)>,
+    awaited_replies: AwaitedReplies,
+    metrics: Arc<ServiceMetrics>,
 }
 
 #[async_trait::async_trait]
@@ -56,32 +175,600 @@ impl AsyncConsumer for DecryptionConsumer {
         _basic_properties: BasicProperties,
         content: Vec<u8>
     ) {
-        let message: PartialDecryption = bincode::deserialize(&content).unwrap();
-        self.sender.send((message.id, message.decryption_share)).await.unwrap();
+        let Ok(message) = bincode::deserialize::<PartialDecryption>(&content) else {
+            self.metrics.rejected_decryption_shares.with_label_values(&["malformed"]).inc();
+            warn!("Malformed decryption share received");
+            return;
+        };
+        let sender = message.request_id
+            .as_ref()
+            .and_then(|request_id| self.awaited_replies.lock().unwrap().get(request_id).cloned());
+        let is_awaited = match sender {
+            Some(sender) => {
+                let reply = ServerReply::Share(message.decryption_share);
+                sender.send((message.id, reply)).await.is_ok()
+            }
+            None => false,
+        };
+        if !is_awaited {
+            self.metrics.late_decryption_shares.inc();
+            info!(
+                dispatch_id = message.request_id.as_deref().unwrap_or_default(),
+                server_id = message.id,
+                "Decryption share received after its decryption was over"
+            );
+        }
+    }
+}
+
+/// Refusal of a Decryption Server to produce its share, signed with its identity key.
+#[derive(Serialize, Deserialize, Debug)]
+struct Refusal {
+    id: usize,
+    /// Id of the refused request, copied from its `request_id` header.
+    request_id: Option<String>,
+    identity_key: Vec<u8>,
+    reason: String,
+    detail: String,
+}
+
+impl From<Refusal> for DecryptionRefusal {
+    fn from(refusal: Refusal) -> Self {
+        Self {
+            id: refusal.id,
+            request_id: refusal.request_id,
+            identity_key: refusal.identity_key,
+            reason: refusal.reason,
+            detail: refusal.detail,
+        }
+    }
+}
+
+/// Consumer of the refusals queue, handing every authenticated refusal to the decryption it
+/// refuses.
+struct RefusalConsumer {
+    awaited_replies: AwaitedReplies,
+    server_registry_service: Arc<dyn ServerRegistryService>,
+    n_servers: usize,
+    metrics: Arc<ServiceMetrics>,
+}
+
+#[async_trait::async_trait]
+impl AsyncConsumer for RefusalConsumer {
+    async fn consume(
+        &mut self,
+        _channel: &amqprs::channel::Channel,
+        _deliver: amqprs::Deliver,
+        _basic_properties: BasicProperties,
+        content: Vec<u8>
+    ) {
+        let refusal = match open_signed(&content, |refusal: &Refusal| &refusal.identity_key) {
+            Ok(refusal) => refusal,
+            Err(reason) => {
+                self.metrics.rejected_decryption_refusals.with_label_values(&[reason]).inc();
+                warn!(reason, "Decryption refusal rejected");
+                return;
+            }
+        };
+        let id = refusal.id;
+        let use_case = AcceptDecryptionRefusalUseCase::new(self.server_registry_service.as_ref());
+        let accepted = use_case.interact(AcceptDecryptionRefusalRequestModel {
+            refusal: refusal.into(),
+            n_servers: self.n_servers,
+        }).await;
+        let response_model = match accepted {
+            Ok(response_model) => response_model,
+            Err(e) => {
+                let reason = match e {
+                    AcceptDecryptionRefusalError::UnknownServer(_) => "unknown_server",
+                    AcceptDecryptionRefusalError::UnenrolledServer(_) => "unenrolled_server",
+                    AcceptDecryptionRefusalError::UnknownIdentityKey(_) => "unknown_identity_key",
+                    AcceptDecryptionRefusalError::ServerRegistryServiceError(_) => "registry_error",
+                };
+                self.metrics.rejected_decryption_refusals.with_label_values(&[reason]).inc();
+                warn!(server_id = id, error = %e, "Decryption refusal rejected");
+                return;
+            }
+        };
+        let refusal = response_model.refusal;
+        self.metrics.decryption_refusals.with_label_values(&[&refusal.reason]).inc();
+        info!(
+            dispatch_id = response_model.request_id.as_deref().unwrap_or_default(),
+            server_id = id,
+            reason = refusal.reason,
+            detail = refusal.detail,
+            "Decryption refused by Decryption Server"
+        );
+        let sender = response_model.request_id
+            .as_ref()
+            .and_then(|request_id| self.awaited_replies.lock().unwrap().get(request_id).cloned());
+        if let Some(sender) = sender {
+            let _ = sender.send((id, ServerReply::Refusal(refusal))).await;
+        }
+    }
+}
+
+/// A key share held by a Decryption Server, identified by the fingerprint of its public key share.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ServedKey {
+    key_id: String,
+    public_key_share_fingerprint: String,
+    public_key_fingerprint: String,
+}
+
+impl From<ServedKey> for KeyFingerprint {
+    fn from(served_key: ServedKey) -> Self {
+        Self {
+            key_id: served_key.key_id,
+            fingerprint: served_key.public_key_share_fingerprint,
+            public_key_fingerprint: served_key.public_key_fingerprint,
+        }
+    }
+}
+
+/// Deserializes a message a Decryption Server signed with its identity key, sent as the Ed25519
+/// signature followed by the serialized message, which carries the public key.
+fn open_signed<T: DeserializeOwned>(
+    content: &[u8],
+    identity_key: fn(&T) -> &[u8]
+) -> Result<T, &'static str> {
+    if content.len() < 64 {
+        return Err("malformed");
+    }
+    let (signature, signed_message) = content.split_at(64);
+    let message: T = bincode::deserialize(signed_message).map_err(|_| "malformed")?;
+    UnparsedPublicKey::new(&ED25519, identity_key(&message))
+        .verify(signed_message, signature)
+        .map_err(|_| "invalid_signature")?;
+    Ok(message)
+}
+
+/// Heartbeat of a Decryption Server, published signed with its identity key.
+#[derive(Serialize, Deserialize, Debug)]
+struct Heartbeat {
+    id: usize,
+    version: String,
+    identity_key: Vec<u8>,
+    key_fingerprints: Vec<ServedKey>,
+    load: u64,
+    sent_at: u64,
+}
+
+impl From<Heartbeat> for ServerHeartbeat {
+    fn from(heartbeat: Heartbeat) -> Self {
+        Self {
+            id: heartbeat.id,
+            version: heartbeat.version,
+            identity_key: heartbeat.identity_key,
+            key_fingerprints: heartbeat.key_fingerprints
+                .into_iter()
+                .map(KeyFingerprint::from)
+                .collect(),
+            load: heartbeat.load,
+            sent_at: heartbeat.sent_at,
+        }
+    }
+}
+
+/// Consumer of the heartbeats queue, recording every correctly signed heartbeat in the registry.
+struct HeartbeatConsumer {
+    server_registry_service: Arc<dyn ServerRegistryService>,
+    n_servers: usize,
+    heartbeat_timeout: Duration,
+    metrics: Arc<ServiceMetrics>,
+}
+
+impl HeartbeatConsumer {
+    fn verify(&self, content: &[u8], now: u64) -> Result<Heartbeat, &'static str> {
+        let heartbeat = open_signed(content, |heartbeat: &Heartbeat| &heartbeat.identity_key)?;
+        // Heartbeats queued while the service was down say nothing of the servers now.
+        if heartbeat.sent_at + self.heartbeat_timeout.as_secs() < now {
+            return Err("expired");
+        }
+        Ok(heartbeat)
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncConsumer for HeartbeatConsumer {
+    async fn consume(
+        &mut self,
+        _channel: &amqprs::channel::Channel,
+        _deliver: amqprs::Deliver,
+        _basic_properties: BasicProperties,
+        content: Vec<u8>
+    ) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let heartbeat = match self.verify(&content, now) {
+            Ok(heartbeat) => heartbeat,
+            Err(reason) => {
+                self.metrics.rejected_heartbeats.with_label_values(&[reason]).inc();
+                warn!(reason, "Decryption Server heartbeat rejected");
+                return;
+            }
+        };
+        let id = heartbeat.id;
+        let use_case = RecordServerHeartbeatUseCase::new(self.server_registry_service.as_ref());
+        let recorded = use_case.interact(RecordServerHeartbeatRequestModel {
+            heartbeat: heartbeat.into(),
+            n_servers: self.n_servers,
+            received_at: now,
+        }).await;
+        match recorded {
+            Ok(response_model) if response_model.first_seen => {
+                info!(server_id = id, "Decryption Server registered");
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let reason = match e {
+                    RecordServerHeartbeatError::UnknownServer(_) => "unknown_server",
+                    RecordServerHeartbeatError::UnknownIdentityKey(_) => "unknown_identity_key",
+                    RecordServerHeartbeatError::ServerRegistryServiceError(_) => "registry_error",
+                };
+                self.metrics.rejected_heartbeats.with_label_values(&[reason]).inc();
+                warn!(server_id = id, error = %e, "Decryption Server heartbeat rejected");
+            }
+        }
+    }
+}
+
+/// Confirmation of a Decryption Server that it loaded its key shares, signed with its identity key.
+#[derive(Serialize, Deserialize, Debug)]
+struct KeySyncAck {
+    id: usize,
+    /// Id of the key sync, copied from its `request_id` header.
+    sync_id: Option<String>,
+    identity_key: Vec<u8>,
+    key_fingerprints: Vec<ServedKey>,
+    mismatched_key_ids: Vec<String>,
+    sent_at: u64,
+}
+
+impl From<KeySyncAck> for KeySyncAcknowledgement {
+    fn from(ack: KeySyncAck) -> Self {
+        Self {
+            id: ack.id,
+            sync_id: ack.sync_id,
+            identity_key: ack.identity_key,
+            key_fingerprints: ack.key_fingerprints.into_iter().map(KeyFingerprint::from).collect(),
+            mismatched_key_ids: ack.mismatched_key_ids,
+            sent_at: ack.sent_at,
+        }
+    }
+}
+
+/// Consumer of the key sync acknowledgements, handing every valid one to the key sync awaiting it.
+struct KeySyncConsumer {
+    awaited_acknowledgements: Awaited<usize>,
+    server_registry_service: Arc<dyn ServerRegistryService>,
+    /// Fingerprints of the key shares each server should hold, by server id.
+    key_share_fingerprints: Vec<Vec<KeyFingerprint>>,
+    metrics: Arc<ServiceMetrics>,
+}
+
+#[async_trait::async_trait]
+impl AsyncConsumer for KeySyncConsumer {
+    async fn consume(
+        &mut self,
+        _channel: &amqprs::channel::Channel,
+        _deliver: amqprs::Deliver,
+        _basic_properties: BasicProperties,
+        content: Vec<u8>
+    ) {
+        let ack = match open_signed(&content, |ack: &KeySyncAck| &ack.identity_key) {
+            Ok(ack) => ack,
+            Err(reason) => {
+                self.metrics.rejected_key_sync_acknowledgements.with_label_values(&[reason]).inc();
+                warn!(reason, "Key sync acknowledgement rejected");
+                return;
+            }
+        };
+        let id = ack.id;
+        let use_case = AcknowledgeKeySyncUseCase::new(self.server_registry_service.as_ref());
+        let acknowledged = use_case.interact(AcknowledgeKeySyncRequestModel {
+            acknowledgement: ack.into(),
+            expected_key_fingerprints: self.key_share_fingerprints.clone(),
+        }).await;
+        match acknowledged {
+            Ok(response_model) => {
+                info!(server_id = id, "Key shares acknowledged");
+                let sender = response_model.sync_id
+                    .as_ref()
+                    .and_then(|sync_id| {
+                        self.awaited_acknowledgements.lock().unwrap().get(sync_id).cloned()
+                    });
+                if let Some(sender) = sender {
+                    let _ = sender.send(id).await;
+                }
+            }
+            Err(e) => {
+                let reason = match e {
+                    AcknowledgeKeySyncError::UnknownServer(_) => "unknown_server",
+                    AcknowledgeKeySyncError::UnknownIdentityKey(_) => "unknown_identity_key",
+                    AcknowledgeKeySyncError::StaleKeyShares(_, _) => "stale_key_shares",
+                    AcknowledgeKeySyncError::MismatchedKeyShares(_, _) => "mismatched_key_shares",
+                    AcknowledgeKeySyncError::ServerRegistryServiceError(_) => "registry_error",
+                };
+                self.metrics.rejected_key_sync_acknowledgements.with_label_values(&[reason]).inc();
+                warn!(server_id = id, error = %e, "Key sync acknowledgement rejected");
+            }
+        }
     }
 }
 
+/// Request of a Decryption Server started without key shares, signed with its identity key.
+#[derive(Serialize, Deserialize, Debug)]
+struct KeyShareRequestMessage {
+    id: usize,
+    identity_key: Vec<u8>,
+    sent_at: u64,
+}
+
+impl From<KeyShareRequestMessage> for KeyShareRequest {
+    fn from(message: KeyShareRequestMessage) -> Self {
+        Self {
+            id: message.id,
+            identity_key: message.identity_key,
+            sent_at: message.sent_at,
+        }
+    }
+}
+
+/// Consumer of the key share requests, delivering their key shares again to the servers that
+/// authenticate with their identity key.
+struct KeyShareRequestConsumer {
+    server_registry_service: Arc<dyn ServerRegistryService>,
+    /// Signed key sync message of each server, by server id.
+    key_share_messages: Vec<Vec<u8>>,
+    max_age: Duration,
+    metrics: Arc<ServiceMetrics>,
+}
+
+#[async_trait::async_trait]
+impl AsyncConsumer for KeyShareRequestConsumer {
+    async fn consume(
+        &mut self,
+        channel: &amqprs::channel::Channel,
+        _deliver: amqprs::Deliver,
+        _basic_properties: BasicProperties,
+        content: Vec<u8>
+    ) {
+        let message = match
+            open_signed(&content, |message: &KeyShareRequestMessage| &message.identity_key)
+        {
+            Ok(message) => message,
+            Err(reason) => {
+                self.metrics.key_share_requests.with_label_values(&[reason]).inc();
+                warn!(reason, "Key share request rejected");
+                return;
+            }
+        };
+        let id = message.id;
+        let use_case = AuthorizeKeyShareRequestUseCase::new(self.server_registry_service.as_ref());
+        let authorized = use_case.interact(AuthorizeKeyShareRequestRequestModel {
+            key_share_request: message.into(),
+            n_servers: self.key_share_messages.len(),
+            now: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            max_age_secs: self.max_age.as_secs(),
+        }).await;
+        if let Err(e) = authorized {
+            let outcome = match e {
+                AuthorizeKeyShareRequestError::UnknownServer(_) => "unknown_server",
+                AuthorizeKeyShareRequestError::UnenrolledServer(_) => "unenrolled_server",
+                AuthorizeKeyShareRequestError::UnknownIdentityKey(_) => "unknown_identity_key",
+                AuthorizeKeyShareRequestError::ExpiredRequest(_) => "expired",
+                AuthorizeKeyShareRequestError::ServerRegistryServiceError(_) => "registry_error",
+            };
+            self.metrics.key_share_requests.with_label_values(&[outcome]).inc();
+            warn!(server_id = id, error = %e, "Key share request rejected");
+            return;
+        }
+        let sync_id = generate_request_id();
+        let published = publish_key_shares(
+            channel,
+            id,
+            &sync_id,
+            self.key_share_messages[id].clone()
+        ).await;
+        match published {
+            Ok(()) => {
+                self.metrics.key_share_requests.with_label_values(&["answered"]).inc();
+                info!(server_id = id, sync_id, "Key shares delivered on request");
+            }
+            Err(e) => {
+                self.metrics.key_share_requests.with_label_values(&["broker_error"]).inc();
+                warn!(server_id = id, error = %e, "Unable to deliver requested key shares");
+            }
+        }
+    }
+}
+
+/// Collects the first threshold + 1 shares passing `verify_share`, failing as soon as too many
+/// servers refused for a quorum. Shares are not signed, so a share failing verification is
+/// only counted as rejected: anyone able to publish one could otherwise make decryptions fail.
+async fn await_shares(
+    verify_share: impl Fn(usize, &DecryptionShare) -> bool,
+    receiver: &mut Receiver<(usize, ServerReply)>,
+    (n_servers, threshold): (usize, usize),
+    decryption_timeout: Duration,
+    metrics: &ServiceMetrics,
+    published_at: Instant
+) -> Result<HashMap<usize, DecryptionShare>, DecryptionFailure> {
+    let mut received_shares = HashMap::new();
+    let mut refusals: Vec<ServerRefusal> = Vec::new();
+    while received_shares.len() < threshold + 1 {
+        match timeout(decryption_timeout, receiver.recv()).await {
+            Ok(Some((id, ServerReply::Share(decryption_share)))) => {
+                metrics.decryption_shares_received
+                    .with_label_values(&[&id.to_string()])
+                    .inc();
+                info!(
+                    server_id = id,
+                    elapsed_ms = published_at.elapsed().as_millis() as u64,
+                    "Decryption share received"
+                );
+                let rejection = if id >= n_servers {
+                    Some("unknown_server")
+                } else if received_shares.contains_key(&id) {
+                    Some("duplicate")
+                } else if !verify_share(id, &decryption_share) {
+                    Some("invalid")
+                } else {
+                    None
+                };
+                match rejection {
+                    Some(reason) => {
+                        metrics.rejected_decryption_shares
+                            .with_label_values(&[reason])
+                            .inc();
+                        warn!(server_id = id, reason, "Decryption share rejected");
+                    }
+                    None => {
+                        received_shares.insert(id, decryption_share);
+                    }
+                }
+            }
+            Ok(Some((id, ServerReply::Refusal(refusal)))) => {
+                let has_replied =
+                    received_shares.contains_key(&id) ||
+                    refusals.iter().any(|refusal| refusal.server_id == id);
+                if !has_replied {
+                    refusals.push(refusal);
+                }
+            }
+            Ok(None) => {
+                break;
+            }
+            Err(_) => {
+                metrics.decryption_timeouts.inc();
+                warn!(
+                    received_shares = received_shares.len(),
+                    "Timed out waiting for decryption shares"
+                );
+                return Err((
+                    "timeout",
+                    CryptographyServiceError::DecryptionError(
+                        "Not enough available Decryption Servers.".to_string()
+                    ),
+                ));
+            }
+        }
+        if n_servers - refusals.len() < threshold + 1 {
+            refusals.sort_by_key(|refusal| refusal.server_id);
+            warn!(?refusals, "Quorum unreachable, decryption refused");
+            return Err(("refused", CryptographyServiceError::QuorumUnreachable(refusals)));
+        }
+    }
+    Ok(received_shares)
+}
+
+/// Publishes the signed key sync message of server `id`, identified by `sync_id`.
+async fn publish_key_shares(
+    channel: &Channel,
+    id: usize,
+    sync_id: &str,
+    key_share_message: Vec<u8>
+) -> Result<(), amqprs::error::Error> {
+    let mut headers = FieldTable::new();
+    headers.insert(
+        "request_id".try_into().unwrap(),
+        FieldValue::S(sync_id.to_string().try_into().unwrap())
+    );
+    let properties = BasicProperties::default().with_headers(headers).finish();
+    channel
+        .basic_publish(
+            properties,
+            key_share_message,
+            BasicPublishArguments::new(SECRETS_EXCHANGE, &format!("server_{}_secret", id))
+        ).await
+}
+
+/// Fingerprint of a public key share, as reported by the Decryption Servers.
+fn fingerprint(bytes: &[u8]) -> String {
+    HexFmt(&digest::digest(&digest::SHA256, bytes).as_ref()[..16]).to_string()
+}
+
+#[derive(Serialize, Deserialize)]
+struct TenantKeyShare {
+    key_id: String,
+    secret_key_share: SerdeSecret<SecretKeyShare>,
+    /// Commitment the server checks its share against before loading it.
+    public_key_set: PublicKeySet,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct DecryptionServerMessage {
     cipher_text: Option<Vec<u8>>,
     public_key: Option<Vec<u8>>,
-    secret_key_share: Option<Vec<u8>>,
+    secret_key_shares: Option<Vec<u8>>,
     timestamp: Option<u64>,
+    associated_data: Option<Vec<u8>>,
+    requester: Option<String>,
+    key_id: Option<String>,
+    reason: Option<String>,
+}
+
+/// Threshold key set of a single tenant, whose shares are distributed to every server.
+struct TenantKeySet {
+    secret_key_set: SecretKeySet,
+    public_key_set: PublicKeySet,
+    key_id: KeyId,
+}
+
+impl TenantKeySet {
+    fn random(threshold: usize) -> Self {
+        let secret_key_set = SecretKeySet::random(threshold, &mut rand::thread_rng());
+        let public_key_set = secret_key_set.public_keys();
+        let mut digest = [0u8; 32];
+        let mut hasher = Sha3::v256();
+        hasher.update(&public_key_set.public_key().to_bytes());
+        hasher.finalize(&mut digest);
+        let mut key_id = [0u8; 8];
+        key_id.copy_from_slice(&digest[..8]);
+        Self {
+            secret_key_set,
+            public_key_set,
+            key_id: KeyId(key_id),
+        }
+    }
+}
+
+/// Kind of failure of a decryption, the `outcome` label of the decryptions metric.
+type DecryptionFailure = (&'static str, CryptographyServiceError);
+
+async fn open_connection() -> Result<Connection, amqprs::error::Error> {
+    let connection = Connection::open(
+        OpenConnectionArguments::new("localhost", 5672, "guest", "guest").heartbeat(30)
+    ).await?;
+    connection.register_callback(DefaultConnectionCallback).await?;
+    Ok(connection)
 }
 
 pub struct PairingCryptographyService {
-    connection: Connection,
+    connection: RwLock<Connection>,
+    /// Channel of the shares and heartbeats consumers, reopened along with the connection.
+    consumers_channel: AsyncMutex<Option<Channel>>,
+    awaited_replies: AwaitedReplies,
+    /// Senders awaiting the acknowledgements of in-flight key syncs, by sync id.
+    awaited_acknowledgements: Awaited<usize>,
+    server_registry_service: Arc<dyn ServerRegistryService>,
     n_servers: usize,
     threshold: usize,
-    secret_key_set: SecretKeySet,
-    public_key_set: PublicKeySet,
+    decryption_timeout: Duration,
+    heartbeat_timeout: Duration,
+    key_sync_timeout: Duration,
+    key_sync_attempts: usize,
+    key_sets: HashMap<String, TenantKeySet>,
     key_pair: Ed25519KeyPair,
+    metrics: Arc<ServiceMetrics>,
 }
 
 impl PairingCryptographyService {
+    /// Creates a service with an independent key set for each of `tenants`.
     pub async fn new(
         n_servers: usize,
-        threshold: usize
+        threshold: usize,
+        tenants: Vec<String>
     ) -> Result<Self, PairingCryptographyServiceError> {
         if n_servers <= threshold {
             return Err(
@@ -90,18 +777,17 @@ impl PairingCryptographyService {
                 )
             );
         }
-        let queue_name = "decryption_service";
+        if tenants.is_empty() {
+            return Err(
+                PairingCryptographyServiceError::InvalidInitialization(
+                    "At least one tenant is required.".to_string()
+                )
+            );
+        }
         let exchange_name = "partials_exchange";
-        let connection = Connection::open(
-            OpenConnectionArguments::new("localhost", 5672, "guest", "guest").heartbeat(30)
-        ).await.map_err(|e| {
+        let connection = open_connection().await.map_err(|e| {
             PairingCryptographyServiceError::InvalidInitialization(e.to_string())
         })?;
-        connection
-            .register_callback(DefaultConnectionCallback).await
-            .map_err(|e| {
-                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
-            })?;
         let channel = connection
             .open_channel(None).await
             .map_err(|e| {
@@ -113,7 +799,7 @@ impl PairingCryptographyService {
                 PairingCryptographyServiceError::InvalidInitialization(e.to_string())
             })?;
         channel
-            .queue_declare(QueueDeclareArguments::durable_client_named(&queue_name)).await
+            .queue_declare(QueueDeclareArguments::durable_client_named(SHARES_QUEUE)).await
             .map_err(|e| {
                 PairingCryptographyServiceError::InvalidInitialization(e.to_string())
             })?;
@@ -125,156 +811,713 @@ impl PairingCryptographyService {
                 PairingCryptographyServiceError::InvalidInitialization(e.to_string())
             })?;
         channel
-            .queue_bind(QueueBindArguments::new(&queue_name, exchange_name, "*")).await
+            .queue_bind(QueueBindArguments::new(SHARES_QUEUE, exchange_name, "*")).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .queue_declare(QueueDeclareArguments::durable_client_named(HEARTBEATS_QUEUE)).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .exchange_declare(
+                ExchangeDeclareArguments::new(HEARTBEATS_EXCHANGE, "fanout")
+                    .durable(true)
+                    .to_owned()
+            ).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .queue_bind(QueueBindArguments::new(HEARTBEATS_QUEUE, HEARTBEATS_EXCHANGE, "*")).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .queue_declare(QueueDeclareArguments::durable_client_named(KEY_SYNC_QUEUE)).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .exchange_declare(
+                ExchangeDeclareArguments::new(KEY_SYNC_EXCHANGE, "fanout")
+                    .durable(true)
+                    .to_owned()
+            ).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .queue_bind(QueueBindArguments::new(KEY_SYNC_QUEUE, KEY_SYNC_EXCHANGE, "*")).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .queue_declare(QueueDeclareArguments::durable_client_named(KEY_REQUESTS_QUEUE)).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .exchange_declare(
+                ExchangeDeclareArguments::new(KEY_REQUESTS_EXCHANGE, "fanout")
+                    .durable(true)
+                    .to_owned()
+            ).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .queue_bind(
+                QueueBindArguments::new(KEY_REQUESTS_QUEUE, KEY_REQUESTS_EXCHANGE, "*")
+            ).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .queue_declare(QueueDeclareArguments::durable_client_named(REFUSALS_QUEUE)).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .exchange_declare(
+                ExchangeDeclareArguments::new(REFUSALS_EXCHANGE, "fanout").durable(true).to_owned()
+            ).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        channel
+            .queue_bind(QueueBindArguments::new(REFUSALS_QUEUE, REFUSALS_EXCHANGE, "*")).await
+            .map_err(|e| {
+                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+            })?;
+        // Key shares are also published from the consumers' channel, which an undeclared
+        // exchange would close.
+        channel
+            .exchange_declare(
+                ExchangeDeclareArguments::new(SECRETS_EXCHANGE, "direct").durable(true).to_owned()
+            ).await
             .map_err(|e| {
                 PairingCryptographyServiceError::InvalidInitialization(e.to_string())
             })?;
         channel.close().await.unwrap();
-        let mut rng = rand::thread_rng();
-        let secret_key_set = SecretKeySet::random(threshold, &mut rng);
-        let public_key_set = secret_key_set.public_keys();
+        let key_sets = tenants
+            .into_iter()
+            .map(|tenant| (tenant, TenantKeySet::random(threshold)))
+            .collect();
 
         let rng = ring::rand::SystemRandom::new();
         let pkcs8_bytes = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
         let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8_bytes.as_ref()).unwrap();
 
         Ok(Self {
-            connection,
+            connection: RwLock::new(connection),
+            consumers_channel: AsyncMutex::new(None),
+            awaited_replies: Arc::new(Mutex::new(HashMap::new())),
+            awaited_acknowledgements: Arc::new(Mutex::new(HashMap::new())),
+            server_registry_service: Arc::new(InMemoryServerRegistryService::new()),
             n_servers,
             threshold,
-            secret_key_set,
-            public_key_set,
+            decryption_timeout: Duration::from_secs(10),
+            heartbeat_timeout: Duration::from_secs(15),
+            key_sync_timeout: Duration::from_secs(10),
+            key_sync_attempts: 3,
+            key_sets,
             key_pair,
+            metrics: Arc::new(ServiceMetrics::new()),
         })
     }
 
+    pub fn with_metrics(mut self, metrics: Arc<ServiceMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Time to wait for enough decryption shares. Servers running in approval mode hold requests
+    /// until an operator approves them, so deployments using it need a longer timeout.
+    pub fn with_decryption_timeout(mut self, decryption_timeout: Duration) -> Self {
+        self.decryption_timeout = decryption_timeout;
+        self
+    }
+
+    /// Registry the heartbeats of the Decryption Servers are recorded in.
+    pub fn with_server_registry(
+        mut self,
+        server_registry_service: Arc<dyn ServerRegistryService>
+    ) -> Self {
+        self.server_registry_service = server_registry_service;
+        self
+    }
+
+    /// Time after which a server that sent no heartbeat is considered silent.
+    pub fn with_heartbeat_timeout(mut self, heartbeat_timeout: Duration) -> Self {
+        self.heartbeat_timeout = heartbeat_timeout;
+        self
+    }
+
+    /// Time to wait for the acknowledgements of a key sync, and how many times to deliver the key
+    /// shares to the servers that did not acknowledge them.
+    pub fn with_key_sync_retries(mut self, key_sync_timeout: Duration, attempts: usize) -> Self {
+        self.key_sync_timeout = key_sync_timeout;
+        self.key_sync_attempts = attempts.max(1);
+        self
+    }
+
+    pub fn heartbeat_timeout(&self) -> Duration {
+        self.heartbeat_timeout
+    }
+
+    pub fn n_servers(&self) -> usize {
+        self.n_servers
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Whether shares can be received, reconnecting to the broker and restarting the consumers
+    /// when the connection was lost.
+    pub async fn is_broker_connected(&self) -> bool {
+        self.ensure_consumers().await.is_ok()
+    }
+
+    /// Fingerprints of the key shares server `id` should hold, one for each tenant key set.
+    pub fn key_share_fingerprints(&self, id: usize) -> Vec<KeyFingerprint> {
+        let mut key_fingerprints: Vec<KeyFingerprint> = self.key_sets
+            .values()
+            .map(|key_set| KeyFingerprint {
+                key_id: key_set.key_id.to_string(),
+                fingerprint: fingerprint(&key_set.public_key_set.public_key_share(id).to_bytes()),
+                public_key_fingerprint: fingerprint(
+                    &key_set.public_key_set.public_key().to_bytes()
+                ),
+            })
+            .collect();
+        key_fingerprints.sort_by(|a, b| a.key_id.cmp(&b.key_id));
+        key_fingerprints
+    }
+
+    /// Id of the key set of `tenant`, `None` for unknown tenants.
+    pub fn key_id(&self, tenant: &str) -> Option<KeyId> {
+        self.key_sets.get(tenant).map(|key_set| key_set.key_id)
+    }
+
+    fn key_set(&self, tenant: &str) -> Result<&TenantKeySet, CryptographyServiceError> {
+        self.key_sets
+            .get(tenant)
+            .ok_or_else(|| CryptographyServiceError::UnknownTenant(tenant.to_string()))
+    }
+
+    fn open_envelope(
+        &self,
+        key_set: &TenantKeySet,
+        message: &[u8],
+        context: &DecryptionContext
+    ) -> Result<Ciphertext, CryptographyServiceError> {
+        let envelope = CiphertextEnvelope::decode(message).map_err(|e| {
+            CryptographyServiceError::DecryptionError(e.to_string())
+        })?;
+        match envelope.key_id {
+            Some(key_id) if key_id != key_set.key_id => {
+                return Err(
+                    CryptographyServiceError::DecryptionError(
+                        format!("Ciphertext was produced under an unknown key {}.", key_id)
+                    )
+                );
+            }
+            // Legacy ciphertexts carry no key id and predate tenants.
+            None if context.tenant != DEFAULT_TENANT => {
+                return Err(
+                    CryptographyServiceError::DecryptionError(
+                        "Ciphertexts without key id belong to the default tenant.".to_string()
+                    )
+                );
+            }
+            _ => {}
+        }
+        let associated_data_matches = envelope.associated_data == context.associated_data;
+        if envelope.associated_data.is_some() && !associated_data_matches {
+            return Err(CryptographyServiceError::AssociatedDataMismatch);
+        }
+        let ciphertext: Ciphertext = bincode
+            ::deserialize(&envelope.payload)
+            .map_err(|e| { CryptographyServiceError::DecryptionError(e.to_string()) })?;
+        // A ciphertext only verifies for the associated data it was produced with.
+        match &context.associated_data {
+            Some(associated_data) if !labelled_ciphertext::verify(&ciphertext, associated_data) =>
+                Err(CryptographyServiceError::AssociatedDataMismatch),
+            None if !ciphertext.verify() => {
+                let error = "Ciphertext is not valid.".to_string();
+                Err(CryptographyServiceError::DecryptionError(error))
+            }
+            _ => Ok(ciphertext),
+        }
+    }
+
+    /// Opens a channel, reopening the broker connection first when it was lost.
+    async fn open_channel(&self) -> Result<Channel, amqprs::error::Error> {
+        if !self.connection.read().await.is_open() {
+            let mut connection = self.connection.write().await;
+            if !connection.is_open() {
+                *connection = open_connection().await?;
+                self.metrics.amqp_reconnects.inc();
+            }
+        }
+        let channel = self.connection.read().await.open_channel(None).await?;
+        channel.register_callback(DefaultChannelCallback).await?;
+        Ok(channel)
+    }
+
+    /// Starts consuming the shares, refusals, heartbeats, key sync acknowledgements and key share
+    /// requests queues, again whenever the previous consumers' channel closed.
+    async fn ensure_consumers(&self) -> Result<(), amqprs::error::Error> {
+        let mut consumers_channel = self.consumers_channel.lock().await;
+        if consumers_channel.as_ref().is_some_and(Channel::is_open) {
+            return Ok(());
+        }
+        let channel = self.open_channel().await?;
+        let consume_args = BasicConsumeArguments::new(SHARES_QUEUE, "decryption_service")
+            .manual_ack(false)
+            .finish();
+        let consumer = DecryptionConsumer {
+            awaited_replies: self.awaited_replies.clone(),
+            metrics: self.metrics.clone(),
+        };
+        channel.basic_consume(consumer, consume_args).await?;
+        let consume_args = BasicConsumeArguments::new(REFUSALS_QUEUE, REFUSALS_QUEUE)
+            .manual_ack(false)
+            .finish();
+        let consumer = RefusalConsumer {
+            awaited_replies: self.awaited_replies.clone(),
+            server_registry_service: self.server_registry_service.clone(),
+            n_servers: self.n_servers,
+            metrics: self.metrics.clone(),
+        };
+        channel.basic_consume(consumer, consume_args).await?;
+        let consume_args = BasicConsumeArguments::new(HEARTBEATS_QUEUE, HEARTBEATS_QUEUE)
+            .manual_ack(false)
+            .finish();
+        let consumer = HeartbeatConsumer {
+            server_registry_service: self.server_registry_service.clone(),
+            n_servers: self.n_servers,
+            heartbeat_timeout: self.heartbeat_timeout,
+            metrics: self.metrics.clone(),
+        };
+        channel.basic_consume(consumer, consume_args).await?;
+        let consume_args = BasicConsumeArguments::new(KEY_SYNC_QUEUE, KEY_SYNC_QUEUE)
+            .manual_ack(false)
+            .finish();
+        let consumer = KeySyncConsumer {
+            awaited_acknowledgements: self.awaited_acknowledgements.clone(),
+            server_registry_service: self.server_registry_service.clone(),
+            key_share_fingerprints: (0..self.n_servers)
+                .map(|id| self.key_share_fingerprints(id))
+                .collect(),
+            metrics: self.metrics.clone(),
+        };
+        channel.basic_consume(consumer, consume_args).await?;
+        let consume_args = BasicConsumeArguments::new(KEY_REQUESTS_QUEUE, KEY_REQUESTS_QUEUE)
+            .manual_ack(false)
+            .finish();
+        let consumer = KeyShareRequestConsumer {
+            server_registry_service: self.server_registry_service.clone(),
+            key_share_messages: (0..self.n_servers).map(|id| self.key_share_message(id)).collect(),
+            max_age: self.heartbeat_timeout,
+            metrics: self.metrics.clone(),
+        };
+        channel.basic_consume(consumer, consume_args).await?;
+        *consumers_channel = Some(channel);
+        Ok(())
+    }
+
     async fn combine_decryption_shares(
         &self,
+        key_set: &TenantKeySet,
         shares: &HashMap<usize, DecryptionShare>,
         ciphertext: &Ciphertext
     ) -> Result<Vec<u8>, CryptographyServiceError> {
-        self.public_key_set
+        key_set.public_key_set
             .decrypt(shares, ciphertext)
             .map_err(|e| { CryptographyServiceError::DecryptionError(e.to_string()) })
     }
 
-    pub async fn propagate_keys(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let exchange_name = "secrets_exchange";
-        let channel = self.connection
-            .open_channel(None).await
+    /// Publishes the decryption request under `dispatch_id` and combines the first threshold + 1
+    /// valid shares.
+    async fn dispatch_decryption(
+        &self,
+        message: Vec<u8>,
+        context: DecryptionContext,
+        dispatch_id: String
+    ) -> Result<DecryptedMessage, DecryptionFailure> {
+        let exchange_name = "decryptions_exchange";
+        let key_set = self.key_set(&context.tenant).map_err(|e| ("unknown_tenant", e))?;
+        let encrypted_message = self
+            .open_envelope(key_set, &message, &context)
+            .map_err(|e| ("invalid_ciphertext", e))?;
+        let cipher_text = bincode
+            ::serialize(&encrypted_message)
             .map_err(|e| {
-                PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+                ("invalid_ciphertext", CryptographyServiceError::DecryptionError(e.to_string()))
             })?;
+        let broker_error = |e: amqprs::error::Error| {
+            ("broker_error", CryptographyServiceError::DecryptionError(e.to_string()))
+        };
+        self.ensure_consumers().await.map_err(broker_error)?;
+        let channel = self.open_channel().await.map_err(broker_error)?;
+        let mut headers = FieldTable::new();
+        headers.insert(
+            "request_id".try_into().unwrap(),
+            FieldValue::S(dispatch_id.clone().try_into().unwrap())
+        );
+        let publish_span = info_span!("amqp_publish", exchange = exchange_name);
+        trace_context::inject(&publish_span, &mut headers);
+        let properties = BasicProperties::default().with_headers(headers).finish();
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let message = DecryptionServerMessage {
+            cipher_text: Some(cipher_text),
+            public_key: None,
+            secret_key_shares: None,
+            timestamp: Some(timestamp),
+            associated_data: context.associated_data.clone(),
+            requester: context.requester,
+            key_id: Some(key_set.key_id.to_string()),
+            reason: context.reason,
+        };
+        let signed_message = self.sign(&message);
+
+        // Shares are awaited before publishing so that none arrives before its decryption.
+        let (_awaited_replies_entry, mut receiver) = AwaitedEntry::register(
+            &self.awaited_replies,
+            dispatch_id,
+            self.n_servers
+        ).ok_or((
+            "duplicate_request",
+            CryptographyServiceError::DecryptionError(
+                "A decryption with the same id is already in flight.".to_string()
+            ),
+        ))?;
+        let published_at = Instant::now();
         channel
-            .register_callback(DefaultChannelCallback).await
-            .map_err(|e| {
+            .basic_publish(
+                properties.clone(),
+                signed_message,
+                BasicPublishArguments::new(exchange_name, "*")
+            )
+            .instrument(publish_span).await
+            .map_err(broker_error)?;
+        channel.close().await.map_err(broker_error)?;
+        info!("Decryption published to the Decryption Servers");
+
+        // Labelled ciphertexts carry no unlabelled validity proof to check shares against.
+        let verify_share = |id: usize, decryption_share: &DecryptionShare| {
+            let public_key_share = key_set.public_key_set.public_key_share(id);
+            match &context.associated_data {
+                Some(associated_data) =>
+                    labelled_ciphertext::verify_decryption_share(
+                        &public_key_share,
+                        decryption_share,
+                        &encrypted_message,
+                        associated_data
+                    ),
+                None =>
+                    public_key_share.verify_decryption_share(decryption_share, &encrypted_message),
+            }
+        };
+        let received_shares = await_shares(
+            verify_share,
+            &mut receiver,
+            (self.n_servers, self.threshold),
+            self.decryption_timeout,
+            &self.metrics,
+            published_at
+        )
+            .instrument(info_span!("await_shares")).await?;
+        self.metrics.time_to_quorum.observe(published_at.elapsed().as_secs_f64());
+
+        let decrypted_message = self
+            .combine_decryption_shares(key_set, &received_shares, &encrypted_message)
+            .instrument(info_span!("combine_decryption_shares")).await
+            .map_err(|e| ("combination_failed", e))?;
+        let mut participants: Vec<usize> = received_shares.into_keys().collect();
+        participants.sort_unstable();
+        info!(
+            ?participants,
+            elapsed_ms = published_at.elapsed().as_millis() as u64,
+            "Decryption shares combined"
+        );
+
+        Ok(DecryptedMessage {
+            plaintext: decrypted_message,
+            participants,
+        })
+    }
+
+    /// The serialized message, preceded by its signature with the key of the service.
+    fn sign(&self, message: &DecryptionServerMessage) -> Vec<u8> {
+        let serialized_message = bincode::serialize(message).unwrap();
+        let message_signature = self.key_pair.sign(&serialized_message);
+        let mut signed_message = Vec::new();
+        signed_message.extend_from_slice(message_signature.as_ref());
+        signed_message.extend_from_slice(&serialized_message);
+        signed_message
+    }
+
+    /// Signed key sync message carrying the share of every tenant key set for server `id`.
+    fn key_share_message(&self, id: usize) -> Vec<u8> {
+        let tenant_key_shares: Vec<TenantKeyShare> = self.key_sets
+            .values()
+            .map(|key_set| TenantKeyShare {
+                key_id: key_set.key_id.to_string(),
+                secret_key_share: SerdeSecret(key_set.secret_key_set.secret_key_share(id)),
+                public_key_set: key_set.public_key_set.clone(),
+            })
+            .collect();
+        let serialized_tenant_key_shares = bincode::serialize(&tenant_key_shares).unwrap();
+        let content = DecryptionServerMessage {
+            cipher_text: None,
+            public_key: Some(self.key_pair.public_key().as_ref().to_vec()),
+            secret_key_shares: Some(serialized_tenant_key_shares),
+            timestamp: None,
+            associated_data: None,
+            requester: None,
+            key_id: None,
+            reason: None,
+        };
+        self.sign(&content)
+    }
+
+    /// Delivers their key shares to the Decryption Servers and waits for their acknowledgements,
+    /// delivering the shares again to the servers that did not acknowledge them in time. Returns
+    /// the ids of the servers that never did.
+    pub async fn propagate_keys(&self) -> Result<Vec<usize>, Box<dyn Error + Send + Sync>> {
+        self.ensure_consumers().await.map_err(|e| {
+            PairingCryptographyServiceError::InvalidInitialization(e.to_string())
+        })?;
+        let sync_id = generate_request_id();
+        let (_awaited_acknowledgements_entry, mut receiver) = AwaitedEntry::register(
+            &self.awaited_acknowledgements,
+            sync_id.clone(),
+            self.n_servers
+        ).ok_or_else(|| {
+            PairingCryptographyServiceError::InvalidInitialization(
+                "A key sync with the same id is already in flight.".to_string()
+            )
+        })?;
+        let mut missing_ids: BTreeSet<usize> = (0..self.n_servers).collect();
+        for attempt in 1..=self.key_sync_attempts {
+            let channel = self.open_channel().await.map_err(|e| {
                 PairingCryptographyServiceError::InvalidInitialization(e.to_string())
             })?;
-        for id in 0..self.n_servers {
-            let secret_key_share: SecretKeyShare = self.secret_key_set.secret_key_share(id);
-            let serde_secret = SerdeSecret(secret_key_share.clone());
-            let serialized_serde_secret: Vec<u8> = bincode::serialize(&serde_secret).unwrap();
-            let properties = BasicProperties::default();
-            let content = DecryptionServerMessage {
-                cipher_text: None,
-                public_key: Some(self.key_pair.public_key().as_ref().to_vec()),
-                secret_key_share: Some(serialized_serde_secret),
-                timestamp: None,
-            };
-            let serialized_content = bincode::serialize(&content).unwrap();
-            channel
-                .basic_publish(
-                    properties.clone(),
-                    serialized_content,
-                    BasicPublishArguments::new(&exchange_name, &format!("server_{}_secret", id))
-                ).await
-                .map_err(|e| {
-                    PairingCryptographyServiceError::InvalidInitialization(e.to_string())
-                })?;
+            for id in &missing_ids {
+                let key_share_message = self.key_share_message(*id);
+                publish_key_shares(&channel, *id, &sync_id, key_share_message).await.map_err(
+                    |e| { PairingCryptographyServiceError::InvalidInitialization(e.to_string()) }
+                )?;
+            }
+            channel.close().await.unwrap();
+            let deadline = Instant::now() + self.key_sync_timeout;
+            while !missing_ids.is_empty() {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                match timeout(remaining, receiver.recv()).await {
+                    Ok(Some(id)) => {
+                        missing_ids.remove(&id);
+                    }
+                    _ => {
+                        break;
+                    }
+                }
+            }
+            if missing_ids.is_empty() {
+                break;
+            }
+            warn!(
+                attempt,
+                attempts = self.key_sync_attempts,
+                ?missing_ids,
+                "Decryption Servers did not acknowledge their key shares"
+            );
         }
-        channel.close().await.unwrap();
-        Ok(())
+        Ok(missing_ids.into_iter().collect())
     }
 }
 
 #[async_trait]
 impl CryptographyService for PairingCryptographyService {
-    async fn share_public_key(&self) -> Result<Vec<u8>, CryptographyServiceError> {
-        Ok(self.public_key_set.public_key().to_bytes().to_vec())
+    async fn share_public_key(&self, tenant: String) -> Result<Vec<u8>, CryptographyServiceError> {
+        Ok(self.key_set(&tenant)?.public_key_set.public_key().to_bytes().to_vec())
     }
 
-    async fn encrypt_message(&self, message: String) -> Result<Vec<u8>, CryptographyServiceError> {
-        bincode
-            ::serialize(&self.public_key_set.public_key().encrypt(&message))
-            .map_err(|e| { CryptographyServiceError::DecryptionError(e.to_string()) })
+    async fn encrypt_message(
+        &self,
+        tenant: String,
+        message: Vec<u8>,
+        associated_data: Option<Vec<u8>>
+    ) -> Result<Vec<u8>, CryptographyServiceError> {
+        let key_set = self.key_set(&tenant)?;
+        let public_key = key_set.public_key_set.public_key();
+        let ciphertext = match &associated_data {
+            Some(associated_data) =>
+                labelled_ciphertext::encrypt(&public_key, &message, associated_data).ok_or_else(||
+                    CryptographyServiceError::EncryptionError(
+                        "Unable to bind associated data to the ciphertext.".to_string()
+                    )
+                )?,
+            None => public_key.encrypt(&message),
+        };
+        let payload = bincode
+            ::serialize(&ciphertext)
+            .map_err(|e| { CryptographyServiceError::EncryptionError(e.to_string()) })?;
+        CiphertextEnvelope::new(
+            CiphertextScheme::ThresholdBls12381,
+            key_set.key_id,
+            associated_data,
+            payload
+        )
+            .encode()
+            .map_err(|e| { CryptographyServiceError::EncryptionError(e.to_string()) })
     }
 
-    async fn decrypt_message(&self, message: Vec<u8>) -> Result<Vec<u8>, CryptographyServiceError> {
-        let queue_name = "decryption_service";
-        let exchange_name = "decryptions_exchange";
-        let encrypted_message: Ciphertext = bincode
-            ::deserialize(&message)
-            .map_err(|e| { CryptographyServiceError::DecryptionError(e.to_string()) })?;
-        let channel = self.connection
-            .open_channel(None).await
-            .map_err(|e| { CryptographyServiceError::DecryptionError(e.to_string()) })?;
-        channel
-            .register_callback(DefaultChannelCallback).await
-            .map_err(|e| { CryptographyServiceError::DecryptionError(e.to_string()) })?;
-        let properties = BasicProperties::default();
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let message = DecryptionServerMessage {
-            cipher_text: Some(message),
-            public_key: None,
-            secret_key_share: None,
-            timestamp: Some(timestamp),
-        };
-        let serialized_message = bincode::serialize(&message).unwrap();
-        let message_signature = self.key_pair.sign(&serialized_message);
-        let mut signed_message = Vec::new();
-        signed_message.extend_from_slice(message_signature.as_ref());
-        signed_message.extend_from_slice(&serialized_message);
-        channel
-            .basic_publish(
-                properties.clone(),
-                signed_message,
-                BasicPublishArguments::new(exchange_name, "*")
-            ).await
-            .map_err(|e| { CryptographyServiceError::DecryptionError(e.to_string()) })?;
+    async fn generate_data_key(
+        &self,
+        key_length: usize
+    ) -> Result<Vec<u8>, CryptographyServiceError> {
+        let mut data_key = vec![0u8; key_length];
+        ring::rand::SystemRandom
+            ::new()
+            .fill(&mut data_key)
+            .map_err(|e| { CryptographyServiceError::DataKeyGenerationError(e.to_string()) })?;
+        Ok(data_key)
+    }
 
-        let (sender, mut receiver): (
-            Sender<(usize, DecryptionShare)>,
-            Receiver<(usize, DecryptionShare)>,
-        ) = tokio_channel(self.n_servers);
-        let consume_args = BasicConsumeArguments::new(&queue_name, "*").manual_ack(false).finish();
-        let consumer = DecryptionConsumer { sender };
-        channel.basic_consume(consumer, consume_args).await.unwrap();
-
-        let mut received_shares = HashMap::new();
-        let timeout_duration = Duration::from_secs(10);
-        while received_shares.len() < self.threshold + 1 {
-            match timeout(timeout_duration, receiver.recv()).await {
-                Ok(Some((id, decryption_share))) => {
-                    received_shares.insert(id, decryption_share);
-                }
-                Ok(None) => {
-                    break;
-                }
-                Err(_) => {
-                    return Err(
-                        CryptographyServiceError::DecryptionError(
-                            "Not enough available Decryption Servers.".to_string()
-                        )
-                    );
-                }
+    async fn decrypt_message(
+        &self,
+        message: Vec<u8>,
+        context: DecryptionContext
+    ) -> Result<DecryptedMessage, CryptographyServiceError> {
+        // Replies are matched on an id of the service's own, as clients choose their request ids.
+        let dispatch_id = generate_request_id();
+        let span = info_span!(
+            "decryption",
+            request_id = context.request_id.as_deref().unwrap_or_default(),
+            dispatch_id = %dispatch_id,
+            tenant = %context.tenant
+        );
+        let decryption = self
+            .dispatch_decryption(message, context, dispatch_id)
+            .instrument(span.clone()).await;
+        let outcome = match &decryption {
+            Ok(_) => "success",
+            Err((outcome, e)) => {
+                span.in_scope(|| warn!(outcome, error = %e, "Decryption failed"));
+                outcome
             }
+        };
+        self.metrics.decryptions.with_label_values(&[outcome]).inc();
+        decryption.map_err(|(_, e)| e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_not_divert_replies_of_a_decryption_in_flight() {
+        let awaited_replies: Awaited<usize> = Arc::new(Mutex::new(HashMap::new()));
+        let (first_entry, mut first_receiver) = AwaitedEntry::register(
+            &awaited_replies,
+            "5e1f".to_string(),
+            3
+        ).unwrap();
+        let second_decryption = async {
+            let second = AwaitedEntry::register(&awaited_replies, "5e1f".to_string(), 3);
+            assert!(second.is_none());
+        };
+        let first_reply = async {
+            let sender = awaited_replies.lock().unwrap().get("5e1f").cloned().unwrap();
+            sender.send(1).await.unwrap();
+        };
+        tokio::join!(second_decryption, first_reply);
+        assert_eq!(first_receiver.recv().await, Some(1));
+        drop(first_entry);
+        assert!(awaited_replies.lock().unwrap().is_empty());
+    }
+
+    fn refusal(server_id: usize) -> ServerReply {
+        ServerReply::Refusal(ServerRefusal {
+            server_id,
+            reason: "policy".to_string(),
+            detail: "refused by policy".to_string(),
+        })
+    }
+
+    async fn collect(
+        public_key_set: &PublicKeySet,
+        encrypted_message: &Ciphertext,
+        replies: Vec<(usize, ServerReply)>
+    ) -> Result<HashMap<usize, DecryptionShare>, DecryptionFailure> {
+        let verify_share = |id: usize, decryption_share: &DecryptionShare| {
+            public_key_set
+                .public_key_share(id)
+                .verify_decryption_share(decryption_share, encrypted_message)
+        };
+        let (sender, mut receiver) = tokio_channel(replies.len());
+        for reply in replies {
+            sender.send(reply).await.unwrap();
         }
-        channel.close().await.unwrap();
+        await_shares(
+            verify_share,
+            &mut receiver,
+            (3, 1),
+            Duration::from_millis(100),
+            &ServiceMetrics::new(),
+            Instant::now()
+        ).await
+    }
 
-        let decrypted_message = self
-            .combine_decryption_shares(&received_shares, &encrypted_message).await
-            .map_err(|e| { CryptographyServiceError::DecryptionError(e.to_string()) })?;
+    #[tokio::test]
+    async fn should_reach_quorum_despite_forged_invalid_shares() {
+        let secret_key_set = SecretKeySet::random(1, &mut rand::thread_rng());
+        let forged_key_set = SecretKeySet::random(1, &mut rand::thread_rng());
+        let encrypted_message = secret_key_set.public_keys().public_key().encrypt(b"data key");
+        let share = |key_set: &SecretKeySet, id: usize| {
+            let secret_key_share = key_set.secret_key_share(id);
+            ServerReply::Share(secret_key_share.decrypt_share_no_verify(&encrypted_message))
+        };
+        let replies = vec![
+            (0, share(&forged_key_set, 0)),
+            (0, share(&forged_key_set, 0)),
+            (1, share(&forged_key_set, 1)),
+            (2, share(&forged_key_set, 2)),
+            (2, share(&secret_key_set, 2)),
+            (0, share(&secret_key_set, 0))
+        ];
+        let received_shares = collect(&secret_key_set.public_keys(), &encrypted_message, replies)
+            .await
+            .unwrap();
+        let mut participants: Vec<usize> = received_shares.into_keys().collect();
+        participants.sort_unstable();
+        assert_eq!(participants, vec![0, 2]);
+    }
 
-        Ok(decrypted_message)
+    #[tokio::test]
+    async fn should_fail_fast_once_signed_refusals_leave_no_quorum() {
+        let secret_key_set = SecretKeySet::random(1, &mut rand::thread_rng());
+        let encrypted_message = secret_key_set.public_keys().public_key().encrypt(b"data key");
+        let replies = vec![(1, refusal(1)), (1, refusal(1))];
+        let result = collect(&secret_key_set.public_keys(), &encrypted_message, replies).await;
+        assert!(matches!(result, Err(("timeout", _))));
+
+        let replies = vec![(1, refusal(1)), (2, refusal(2))];
+        match collect(&secret_key_set.public_keys(), &encrypted_message, replies).await {
+            Err(("refused", CryptographyServiceError::QuorumUnreachable(refusals))) => {
+                let server_ids: Vec<usize> = refusals
+                    .iter()
+                    .map(|refusal| refusal.server_id)
+                    .collect();
+                assert_eq!(server_ids, vec![1, 2]);
+            }
+            _ => panic!("expected the decryption to be refused"),
+        }
     }
 }
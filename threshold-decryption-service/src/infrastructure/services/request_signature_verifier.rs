@@ -0,0 +1,227 @@
+use std::{ collections::HashMap, fs, sync::Mutex };
+use base64::{ engine::general_purpose, Engine };
+use ring::{ digest, hmac };
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RequestSignatureError {
+    #[error("Invalid request signing configuration. {0}")] InvalidConfiguration(String),
+    #[error("Unknown signing client `{0}`.")] UnknownClient(String),
+    #[error("Request timestamp is outside of the accepted clock skew.")] StaleTimestamp,
+    #[error("Invalid request signature.")] InvalidSignature,
+    #[error("Request nonce was already used.")] ReusedNonce,
+}
+
+#[derive(Deserialize)]
+struct SigningClientEntry {
+    id: String,
+    subject: String,
+    /// Base64-encoded shared secret.
+    secret: String,
+}
+
+struct SigningClient {
+    subject: String,
+    key: hmac::Key,
+}
+
+/// The parts of a request covered by its signature.
+pub struct SignedRequest<'a> {
+    pub client_id: &'a str,
+    pub method: &'a str,
+    pub path: &'a str,
+    pub timestamp: u64,
+    pub nonce: &'a str,
+    pub body: &'a [u8],
+    /// Hex-encoded HMAC-SHA256 of the canonical request.
+    pub signature: &'a str,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub fn from_hex(value: &str) -> Option<Vec<u8>> {
+    value
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).ok().filter(|pair| pair.len() == 2)?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+/// The string clients sign: method, path with query, timestamp, nonce and the hex SHA-256 of the
+/// body, separated by new lines.
+pub fn canonical_request(
+    method: &str,
+    path: &str,
+    timestamp: u64,
+    nonce: &str,
+    body: &[u8]
+) -> String {
+    let body_hash = to_hex(digest::digest(&digest::SHA256, body).as_ref());
+    format!("{}\n{}\n{}\n{}\n{}", method.to_uppercase(), path, timestamp, nonce, body_hash)
+}
+
+/// Verifies HMAC-SHA256 request signatures made with per-client secrets, within a clock skew
+/// window and without nonce reuse.
+pub struct RequestSignatureVerifier {
+    clients: HashMap<String, SigningClient>,
+    max_clock_skew_in_secs: u64,
+    /// Nonces seen within the clock skew window, with the time after which they can be forgotten.
+    seen_nonces: Mutex<HashMap<String, u64>>,
+}
+
+impl RequestSignatureVerifier {
+    pub fn new(max_clock_skew_in_secs: u64) -> Self {
+        Self {
+            clients: HashMap::new(),
+            max_clock_skew_in_secs,
+            seen_nonces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registers a client signing requests on behalf of the caller identity `subject`.
+    pub fn with_client(mut self, id: &str, subject: &str, secret: &[u8]) -> Self {
+        self.clients.insert(id.to_string(), SigningClient {
+            subject: subject.to_string(),
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+        });
+        self
+    }
+
+    /// Registers the clients of a JSON file holding `{ "id", "subject", "secret" }` entries.
+    pub fn with_clients_file(mut self, path: &str) -> Result<Self, RequestSignatureError> {
+        let content = fs
+            ::read(path)
+            .map_err(|e| RequestSignatureError::InvalidConfiguration(e.to_string()))?;
+        let entries: Vec<SigningClientEntry> = serde_json
+            ::from_slice(&content)
+            .map_err(|e| RequestSignatureError::InvalidConfiguration(e.to_string()))?;
+        for entry in entries {
+            let secret = general_purpose::STANDARD
+                .decode(&entry.secret)
+                .map_err(|e| RequestSignatureError::InvalidConfiguration(e.to_string()))?;
+            self = self.with_client(&entry.id, &entry.subject, &secret);
+        }
+        Ok(self)
+    }
+
+    /// Whether requests of the caller identity `subject` must be signed, which is the case once a
+    /// signing client is registered for it.
+    pub fn requires_signature(&self, subject: &str) -> bool {
+        self.clients.values().any(|client| client.subject == subject)
+    }
+
+    /// Verifies `request` at time `now`, returning the caller identity its client signs for.
+    pub fn verify(
+        &self,
+        request: &SignedRequest<'_>,
+        now: u64
+    ) -> Result<String, RequestSignatureError> {
+        let client = self.clients
+            .get(request.client_id)
+            .ok_or_else(|| RequestSignatureError::UnknownClient(request.client_id.to_string()))?;
+        if now.abs_diff(request.timestamp) > self.max_clock_skew_in_secs {
+            return Err(RequestSignatureError::StaleTimestamp);
+        }
+        let signature = from_hex(request.signature).ok_or(
+            RequestSignatureError::InvalidSignature
+        )?;
+        let message = canonical_request(
+            request.method,
+            request.path,
+            request.timestamp,
+            request.nonce,
+            request.body
+        );
+        hmac
+            ::verify(&client.key, message.as_bytes(), &signature)
+            .map_err(|_| RequestSignatureError::InvalidSignature)?;
+
+        let mut seen_nonces = self.seen_nonces.lock().unwrap();
+        seen_nonces.retain(|_, forget_after| *forget_after >= now);
+        let nonce_key = format!("{}:{}", request.client_id, request.nonce);
+        if seen_nonces.contains_key(&nonce_key) {
+            return Err(RequestSignatureError::ReusedNonce);
+        }
+        seen_nonces.insert(nonce_key, request.timestamp + self.max_clock_skew_in_secs);
+        Ok(client.subject.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &[u8], message: &str) -> String {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+        to_hex(hmac::sign(&key, message.as_bytes()).as_ref())
+    }
+
+    #[test]
+    fn should_verify_signed_request_once() {
+        let verifier = RequestSignatureVerifier::new(300).with_client(
+            "billing-1",
+            "billing-service",
+            b"secret"
+        );
+        let body = br#"{"message":"Hello, World!"}"#;
+        let signature = sign(
+            b"secret",
+            &canonical_request("POST", "/encrypt-message", 1000, "n-1", body)
+        );
+        let request = SignedRequest {
+            client_id: "billing-1",
+            method: "POST",
+            path: "/encrypt-message",
+            timestamp: 1000,
+            nonce: "n-1",
+            body,
+            signature: &signature,
+        };
+
+        assert!(verifier.requires_signature("billing-service"));
+        assert_eq!(verifier.verify(&request, 1100), Ok("billing-service".to_string()));
+        assert_eq!(verifier.verify(&request, 1100), Err(RequestSignatureError::ReusedNonce));
+    }
+
+    #[test]
+    fn should_reject_tampered_or_stale_requests() {
+        let verifier = RequestSignatureVerifier::new(300).with_client(
+            "billing-1",
+            "billing-service",
+            b"secret"
+        );
+        let signature = sign(
+            b"secret",
+            &canonical_request("POST", "/decrypt-message", 1000, "n-1", b"{}")
+        );
+        let tampered_request = SignedRequest {
+            client_id: "billing-1",
+            method: "POST",
+            path: "/decrypt-message",
+            timestamp: 1000,
+            nonce: "n-1",
+            body: br#"{"message":"other"}"#,
+            signature: &signature,
+        };
+        let stale_request = SignedRequest { body: b"{}", ..tampered_request };
+
+        assert_eq!(
+            verifier.verify(&tampered_request, 1000),
+            Err(RequestSignatureError::InvalidSignature)
+        );
+        assert_eq!(
+            verifier.verify(&stale_request, 1301),
+            Err(RequestSignatureError::StaleTimestamp)
+        );
+        assert_eq!(verifier.verify(&stale_request, 1000), Ok("billing-service".to_string()));
+    }
+}
@@ -0,0 +1,191 @@
+use prometheus::{
+    Encoder,
+    Histogram,
+    HistogramOpts,
+    HistogramVec,
+    IntCounter,
+    IntCounterVec,
+    Opts,
+    Registry,
+    TextEncoder,
+};
+
+const NAMESPACE: &str = "decryption_service";
+
+/// Prometheus metrics of the service, rendered by `GET /metrics`.
+pub struct ServiceMetrics {
+    registry: Registry,
+    pub http_requests: IntCounterVec,
+    pub http_request_duration: HistogramVec,
+    /// Decryptions by outcome, `success` or the kind of failure.
+    pub decryptions: IntCounterVec,
+    /// Time from publishing a decryption to receiving the threshold + 1 shares it needs.
+    pub time_to_quorum: Histogram,
+    pub decryption_shares_received: IntCounterVec,
+    /// Shares dropped while waiting for a quorum, by reason.
+    pub rejected_decryption_shares: IntCounterVec,
+    /// Shares arriving once their decryption completed or timed out.
+    pub late_decryption_shares: IntCounter,
+    pub decryption_timeouts: IntCounter,
+    pub rate_limited_requests: IntCounterVec,
+    pub amqp_reconnects: IntCounter,
+    /// Heartbeats of Decryption Servers dropped, by reason.
+    pub rejected_heartbeats: IntCounterVec,
+    /// Key sync acknowledgements of Decryption Servers dropped, by reason.
+    pub rejected_key_sync_acknowledgements: IntCounterVec,
+    /// Key share requests of Decryption Servers, by outcome.
+    pub key_share_requests: IntCounterVec,
+    /// Refusals of Decryption Servers to produce a share, by reason.
+    pub decryption_refusals: IntCounterVec,
+    /// Refusals of Decryption Servers dropped, by reason.
+    pub rejected_decryption_refusals: IntCounterVec,
+}
+
+fn counter(name: &str, help: &str) -> IntCounter {
+    IntCounter::with_opts(Opts::new(name, help).namespace(NAMESPACE)).unwrap()
+}
+
+fn counter_vec(name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+    IntCounterVec::new(Opts::new(name, help).namespace(NAMESPACE), labels).unwrap()
+}
+
+impl Default for ServiceMetrics {
+    fn default() -> Self {
+        let metrics = Self {
+            registry: Registry::new(),
+            http_requests: counter_vec(
+                "http_requests_total",
+                "HTTP requests handled, by route, method and status.",
+                &["route", "method", "status"]
+            ),
+            http_request_duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "http_request_duration_seconds",
+                    "Latency of HTTP requests, by route."
+                ).namespace(NAMESPACE),
+                &["route"]
+            ).unwrap(),
+            decryptions: counter_vec(
+                "decryptions_total",
+                "Decryptions dispatched to the Decryption Servers, by outcome.",
+                &["outcome"]
+            ),
+            time_to_quorum: Histogram::with_opts(
+                HistogramOpts::new(
+                    "time_to_quorum_seconds",
+                    "Time until enough decryption shares were received."
+                )
+                    .namespace(NAMESPACE)
+                    .buckets(
+                        vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]
+                    )
+            ).unwrap(),
+            decryption_shares_received: counter_vec(
+                "decryption_shares_received_total",
+                "Decryption shares received, by Decryption Server id.",
+                &["server_id"]
+            ),
+            rejected_decryption_shares: counter_vec(
+                "rejected_decryption_shares_total",
+                "Decryption shares dropped, by reason.",
+                &["reason"]
+            ),
+            late_decryption_shares: counter(
+                "late_decryption_shares_total",
+                "Decryption shares received after their decryption was over."
+            ),
+            decryption_timeouts: counter(
+                "decryption_timeouts_total",
+                "Decryptions that timed out waiting for a quorum of shares."
+            ),
+            rate_limited_requests: counter_vec(
+                "rate_limited_requests_total",
+                "Requests rejected by the rate limiter, by route.",
+                &["route"]
+            ),
+            amqp_reconnects: counter(
+                "amqp_reconnects_total",
+                "Connections to the message broker reopened after being lost."
+            ),
+            rejected_heartbeats: counter_vec(
+                "rejected_heartbeats_total",
+                "Decryption Server heartbeats dropped, by reason.",
+                &["reason"]
+            ),
+            rejected_key_sync_acknowledgements: counter_vec(
+                "rejected_key_sync_acknowledgements_total",
+                "Key sync acknowledgements of Decryption Servers dropped, by reason.",
+                &["reason"]
+            ),
+            key_share_requests: counter_vec(
+                "key_share_requests_total",
+                "Key share requests of Decryption Servers, by outcome.",
+                &["outcome"]
+            ),
+            decryption_refusals: counter_vec(
+                "decryption_refusals_total",
+                "Refusals of Decryption Servers to produce a share, by reason.",
+                &["reason"]
+            ),
+            rejected_decryption_refusals: counter_vec(
+                "rejected_decryption_refusals_total",
+                "Refusals of Decryption Servers dropped, by reason.",
+                &["reason"]
+            ),
+        };
+        let collectors: Vec<Box<dyn prometheus::core::Collector>> = vec![
+            Box::new(metrics.http_requests.clone()),
+            Box::new(metrics.http_request_duration.clone()),
+            Box::new(metrics.decryptions.clone()),
+            Box::new(metrics.time_to_quorum.clone()),
+            Box::new(metrics.decryption_shares_received.clone()),
+            Box::new(metrics.rejected_decryption_shares.clone()),
+            Box::new(metrics.late_decryption_shares.clone()),
+            Box::new(metrics.decryption_timeouts.clone()),
+            Box::new(metrics.rate_limited_requests.clone()),
+            Box::new(metrics.amqp_reconnects.clone()),
+            Box::new(metrics.rejected_heartbeats.clone()),
+            Box::new(metrics.rejected_key_sync_acknowledgements.clone()),
+            Box::new(metrics.key_share_requests.clone()),
+            Box::new(metrics.decryption_refusals.clone()),
+            Box::new(metrics.rejected_decryption_refusals.clone())
+        ];
+        for collector in collectors {
+            metrics.registry.register(collector).unwrap();
+        }
+        metrics
+    }
+}
+
+impl ServiceMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_render_recorded_metrics() {
+        let metrics = ServiceMetrics::new();
+        metrics.decryptions.with_label_values(&["timeout"]).inc();
+        metrics.decryption_shares_received.with_label_values(&["2"]).inc_by(3);
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("decryption_service_decryptions_total{outcome=\"timeout\"} 1"));
+        assert!(
+            rendered.contains(
+                "decryption_service_decryption_shares_received_total{server_id=\"2\"} 3"
+            )
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use amqprs::{ FieldTable, FieldValue };
+use opentelemetry::{
+    global,
+    propagation::Injector,
+    trace::{ TraceError, TracerProvider as _ },
+    KeyValue,
+};
+use opentelemetry_otlp::{ SpanExporter, WithExportConfig };
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    runtime,
+    trace::{ Tracer, TracerProvider },
+    Resource,
+};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Exports spans over OTLP/gRPC to the collector at `endpoint`, and propagates the W3C trace
+/// context of spans crossing the broker.
+pub fn otlp_tracer(endpoint: &str, service_name: &str) -> Result<Tracer, TraceError> {
+    let exporter = SpanExporter::builder().with_tonic().with_endpoint(endpoint).build()?;
+    let tracer_provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new(vec![KeyValue::new("service.name", service_name.to_string())]))
+        .build();
+    let tracer = tracer_provider.tracer(service_name.to_string());
+    global::set_tracer_provider(tracer_provider);
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    Ok(tracer)
+}
+
+/// Exports the spans still buffered, blocking until they are sent.
+pub fn shutdown_tracer() {
+    global::shutdown_tracer_provider();
+}
+
+struct AmqpHeaderInjector<'a>(&'a mut FieldTable);
+
+impl Injector for AmqpHeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(key), Ok(value)) = (key.try_into(), value.try_into()) {
+            self.0.insert(key, FieldValue::S(value));
+        }
+    }
+}
+
+/// Adds the `traceparent` and `tracestate` headers of `span`, so that the spans of the Decryption
+/// Servers join its trace. Nothing is added when spans are not exported.
+pub fn inject(span: &Span, headers: &mut FieldTable) {
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&span.context(), &mut AmqpHeaderInjector(headers));
+    });
+}
@@ -6,29 +6,360 @@ pub mod infrastructure;
 
 use std::env;
 use std::sync::Arc;
+use std::time::Duration;
+use rocket::figment::Figment;
 use rocket_okapi::{ openapi_get_routes, swagger_ui::* };
+use rocket::fairing::AdHoc;
+use tracing::{ info, warn };
+use tracing_subscriber::{ fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter };
+use crate::domain::entities::{
+    caller::DEFAULT_TENANT,
+    pending_decryption::ApprovalPolicy,
+    scope::Grant,
+};
 use crate::infrastructure::{
-    guards::rate_limiter_request_guard::RateLimiter,
+    guards::{
+        admin_authorization_request_guard::AdminToken,
+        authorization_request_guard::ClientCertificateGrants,
+        http_metrics_fairing::HttpMetrics,
+        rate_limiter_request_guard::{ RateLimitHeaders, RateLimiter },
+        request_id_fairing::RequestIds,
+    },
     routes::{
         healthz_route::{ healthz, okapi_add_operation_for_healthz_ },
+        readyz_route::{ readyz, okapi_add_operation_for_readyz_ },
+        metrics_route::{ metrics, okapi_add_operation_for_metrics_ },
         get_public_key_route::{ get_public_key, okapi_add_operation_for_get_public_key_ },
         decrypt_message_route::{ decrypt_message, okapi_add_operation_for_decrypt_message_ },
         encrypt_message_route::{ encrypt_message, okapi_add_operation_for_encrypt_message_ },
+        generate_data_key_route::{ generate_data_key, okapi_add_operation_for_generate_data_key_ },
+        decrypt_data_key_route::{ decrypt_data_key, okapi_add_operation_for_decrypt_data_key_ },
+        get_audit_events_route::{ get_audit_events, okapi_add_operation_for_get_audit_events_ },
+        export_audit_events_route::{
+            export_audit_events,
+            okapi_add_operation_for_export_audit_events_,
+        },
+        create_api_key_route::{ create_api_key, okapi_add_operation_for_create_api_key_ },
+        list_api_keys_route::{ list_api_keys, okapi_add_operation_for_list_api_keys_ },
+        list_servers_route::{ list_servers, okapi_add_operation_for_list_servers_ },
+        revoke_api_key_route::{ revoke_api_key, okapi_add_operation_for_revoke_api_key_ },
+        rotate_api_key_route::{ rotate_api_key, okapi_add_operation_for_rotate_api_key_ },
+        list_pending_decryptions_route::{
+            list_pending_decryptions,
+            okapi_add_operation_for_list_pending_decryptions_,
+        },
+        get_pending_decryption_route::{
+            get_pending_decryption,
+            okapi_add_operation_for_get_pending_decryption_,
+        },
+        decide_pending_decryption_route::{
+            approve_pending_decryption,
+            okapi_add_operation_for_approve_pending_decryption_,
+            deny_pending_decryption,
+            okapi_add_operation_for_deny_pending_decryption_,
+        },
+    },
+    services::{
+        file_api_key_service::FileApiKeyService,
+        file_audit_trail_service::FileAuditTrailService,
+        http_cluster_health_service::HttpClusterHealthService,
+        in_memory_pending_decryption_service::InMemoryPendingDecryptionService,
+        in_memory_server_registry_service::InMemoryServerRegistryService,
+        jwt_verifier::JwtVerifier,
+        pairing_cryptography_service::PairingCryptographyService,
+        request_signature_verifier::{ self, RequestSignatureVerifier },
+        service_metrics::ServiceMetrics,
+        trace_context,
     },
-    services::pairing_cryptography_service::PairingCryptographyService,
 };
 
+fn list_from_env(name: &str) -> Option<Vec<String>> {
+    env::var(name)
+        .ok()
+        .map(|value| value.split(',').map(|item| item.trim().to_string()).collect())
+}
+
+/// Logs JSON lines, or human readable lines when `LOG_FORMAT=text`, filtered by `RUST_LOG`
+/// (`info` by default). Spans are also exported to the OTLP collector at
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` when set, as `OTEL_SERVICE_NAME`.
+fn init_tracing() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let json = env::var("LOG_FORMAT").as_deref() != Ok("text");
+    let tracer = env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok().map(|endpoint| {
+        let service_name = env
+            ::var("OTEL_SERVICE_NAME")
+            .unwrap_or("threshold-decryption-service".to_string());
+        trace_context
+            ::otlp_tracer(&endpoint, &service_name)
+            .unwrap_or_else(|e| panic!("Unable to export spans to {}. {}", endpoint, e))
+    });
+    tracing_subscriber
+        ::registry()
+        .with(filter)
+        .with(json.then(|| fmt::layer().json()))
+        .with((!json).then(fmt::layer))
+        .with(tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer)))
+        .init();
+}
+
+/// Quotas per minute from `RATE_LIMIT_PER_MINUTE` (10 by default) and per route from
+/// `RATE_LIMITS`, e.g. `decrypt_message=5,encrypt_message=60`.
+fn rate_limiter_from_env() -> RateLimiter {
+    let max_requests_per_minute = env
+        ::var("RATE_LIMIT_PER_MINUTE")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(10);
+    let mut rate_limiter = RateLimiter::new(max_requests_per_minute);
+    for route_limit in list_from_env("RATE_LIMITS").unwrap_or_default() {
+        let Some((route, max_requests_per_minute)) = route_limit.split_once('=') else {
+            panic!("Invalid RATE_LIMITS entry `{}`, expected <route>=<requests>", route_limit);
+        };
+        rate_limiter = rate_limiter.with_route_limit(
+            route.trim(),
+            max_requests_per_minute.trim().parse().unwrap()
+        );
+    }
+    let trusted_proxies = list_from_env("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .iter()
+        .map(|address| address.parse().unwrap_or_else(|e| panic!("{}: {}", address, e)))
+        .collect();
+    rate_limiter.with_trusted_proxies(trusted_proxies)
+}
+
+/// Approvals required from `DECRYPTION_APPROVALS` (2 by default), expiry of pending decryptions
+/// from `DECRYPTION_APPROVAL_TTL_SECS` (3600 by default), and tenants whose decryptions always
+/// wait for approval from `DECRYPTION_APPROVAL_TENANTS`.
+fn approval_policy_from_env() -> ApprovalPolicy {
+    let default_policy = ApprovalPolicy::default();
+    ApprovalPolicy {
+        required_approvals: env
+            ::var("DECRYPTION_APPROVALS")
+            .map(|value| value.parse().unwrap())
+            .unwrap_or(default_policy.required_approvals),
+        expires_in_secs: env
+            ::var("DECRYPTION_APPROVAL_TTL_SECS")
+            .map(|value| value.parse().unwrap())
+            .unwrap_or(default_policy.expires_in_secs),
+        mandatory_tenants: list_from_env("DECRYPTION_APPROVAL_TENANTS").unwrap_or_default(),
+    }
+}
+
+/// Probes the Decryption Servers at `DECRYPTION_SERVER_STATUS_URLS`, comma-separated in server id
+/// order (`http://localhost:<9200 + id>` by default), waiting `READINESS_PROBE_TIMEOUT_MS` (1000
+/// by default) for each.
+fn cluster_health_service_from_env(
+    cryptography_service: Arc<PairingCryptographyService>
+) -> HttpClusterHealthService {
+    let status_urls = list_from_env("DECRYPTION_SERVER_STATUS_URLS").unwrap_or_else(|| {
+        (0..cryptography_service.n_servers())
+            .map(|id| format!("http://localhost:{}", 9200 + id))
+            .collect()
+    });
+    let probe_timeout_in_ms = env
+        ::var("READINESS_PROBE_TIMEOUT_MS")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(1000);
+    HttpClusterHealthService::new(
+        cryptography_service,
+        status_urls,
+        Duration::from_millis(probe_timeout_in_ms)
+    )
+}
+
+/// Pins the identity keys of the Decryption Servers to `DECRYPTION_SERVER_IDENTITY_KEYS`, hex
+/// Ed25519 public keys comma-separated in server id order. Without it, the key of each server is
+/// trusted on its first heartbeat.
+fn server_registry_service_from_env() -> InMemoryServerRegistryService {
+    let server_registry_service = InMemoryServerRegistryService::new();
+    let Some(identity_keys) = list_from_env("DECRYPTION_SERVER_IDENTITY_KEYS") else {
+        warn!("No DECRYPTION_SERVER_IDENTITY_KEYS provided, trusting server keys on first use");
+        return server_registry_service;
+    };
+    let identity_keys = identity_keys
+        .iter()
+        .map(|identity_key| {
+            request_signature_verifier
+                ::from_hex(identity_key)
+                .unwrap_or_else(|| panic!("Invalid identity key {}", identity_key))
+        })
+        .collect();
+    server_registry_service.with_enrolled_identity_keys(identity_keys)
+}
+
+/// Terminates TLS when `TLS_CERT_FILE` and `TLS_KEY_FILE` are set and, with `TLS_CLIENT_CA_FILE`,
+/// requires client certificates signed by that CA. Returns the grants of certificate callers when
+/// mutual TLS is enabled.
+fn tls_figment() -> (Figment, Option<ClientCertificateGrants>) {
+    let figment = rocket::Config::figment();
+    let (Ok(certs), Ok(key)) = (env::var("TLS_CERT_FILE"), env::var("TLS_KEY_FILE")) else {
+        warn!("No TLS_CERT_FILE and TLS_KEY_FILE provided, serving plain HTTP");
+        return (figment, None);
+    };
+    let figment = figment.merge(("tls.certs", certs)).merge(("tls.key", key));
+    let Ok(client_ca) = env::var("TLS_CLIENT_CA_FILE") else {
+        return (figment, None);
+    };
+    let client_certificate_optional = env
+        ::var("TLS_CLIENT_CERT_OPTIONAL")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(false);
+    let grants = list_from_env("TLS_CLIENT_SCOPES")
+        .unwrap_or(vec!["encrypt".to_string(), "decrypt".to_string(), "keys:read".to_string()])
+        .iter()
+        .map(|scope| scope.parse::<Grant>().unwrap_or_else(|e| panic!("{}", e.to_string())))
+        .collect();
+    let figment = figment
+        .merge(("tls.mutual.ca_certs", client_ca))
+        .merge(("tls.mutual.mandatory", !client_certificate_optional));
+    (figment, Some(ClientCertificateGrants(grants)))
+}
+
 #[launch]
 async fn rocket() -> _ {
-    let cryptography_service = PairingCryptographyService::new(3, 1).await.unwrap_or_else(|e|
-        panic!("{}", e.to_string())
+    init_tracing();
+    let decryption_timeout_in_secs: u64 = env
+        ::var("DECRYPTION_TIMEOUT_SECS")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(10);
+    let tenants = list_from_env("TENANTS").unwrap_or(vec![DEFAULT_TENANT.to_string()]);
+    let key_sync_timeout_in_secs: u64 = env
+        ::var("KEY_SYNC_TIMEOUT_SECS")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(10);
+    let key_sync_attempts: usize = env
+        ::var("KEY_SYNC_ATTEMPTS")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(3);
+    let heartbeat_timeout_in_secs: u64 = env
+        ::var("HEARTBEAT_TIMEOUT_SECS")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(15);
+    let service_metrics = Arc::new(ServiceMetrics::new());
+    let server_registry_service = Arc::new(server_registry_service_from_env());
+    let cryptography_service = PairingCryptographyService::new(3, 1, tenants).await
+        .unwrap_or_else(|e| panic!("{}", e.to_string()))
+        .with_decryption_timeout(Duration::from_secs(decryption_timeout_in_secs))
+        .with_heartbeat_timeout(Duration::from_secs(heartbeat_timeout_in_secs))
+        .with_key_sync_retries(Duration::from_secs(key_sync_timeout_in_secs), key_sync_attempts)
+        .with_server_registry(server_registry_service.clone())
+        .with_metrics(service_metrics.clone());
+    let missing_ids = cryptography_service
+        .propagate_keys().await
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    if missing_ids.is_empty() {
+        info!("Key shares acknowledged by every Decryption Server");
+    } else {
+        // Shares stay queued for the missing servers, which acknowledge them once started.
+        warn!(?missing_ids, "Decryption Servers did not acknowledge their key shares");
+    }
+    let cryptography_service = Arc::new(cryptography_service);
+    let cluster_health_service = cluster_health_service_from_env(cryptography_service.clone());
+    let audit_trail_file = env::var("AUDIT_TRAIL_FILE").unwrap_or("audit_trail.jsonl".to_string());
+    let audit_trail_service = FileAuditTrailService::new(audit_trail_file).await
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let mut jwt_verifier = JwtVerifier::new().with_leeway(
+        env
+            ::var("JWT_LEEWAY_SECS")
+            .map(|value| value.parse().unwrap())
+            .unwrap_or(30)
+    );
+    if let Ok(secret) = env::var("JWT_HS256_SECRET") {
+        jwt_verifier = jwt_verifier.with_hs256_secret(secret.as_bytes());
+    }
+    if let Ok(jwks_file) = env::var("JWT_JWKS_FILE") {
+        jwt_verifier = jwt_verifier
+            .with_jwks_file(&jwks_file)
+            .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    }
+    if let Some(audience) = list_from_env("JWT_AUDIENCE") {
+        jwt_verifier = jwt_verifier.with_audience(audience);
+    }
+    if let Some(issuer) = list_from_env("JWT_ISSUER") {
+        jwt_verifier = jwt_verifier.with_issuer(issuer);
+    }
+    if !jwt_verifier.has_keys() {
+        info!("No JWT_HS256_SECRET or JWT_JWKS_FILE provided, only API keys are accepted");
+    }
+    let api_keys_file = env::var("API_KEYS_FILE").unwrap_or("api_keys.json".to_string());
+    let api_key_service = FileApiKeyService::new(api_keys_file).await
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let rate_limiter = rate_limiter_from_env();
+    rate_limiter.spawn_pruning(
+        Duration::from_secs(
+            env
+                ::var("RATE_LIMIT_PRUNE_INTERVAL_SECS")
+                .map(|value| value.parse().unwrap())
+                .unwrap_or(60)
+        )
     );
-    cryptography_service.propagate_keys().await.unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let (figment, client_certificate_grants) = tls_figment();
+    let mut rocket = rocket::custom(figment);
+    if let Some(client_certificate_grants) = client_certificate_grants {
+        rocket = rocket.manage(client_certificate_grants);
+    }
+    if let Ok(signing_clients_file) = env::var("REQUEST_SIGNING_CLIENTS_FILE") {
+        let request_signature_verifier = RequestSignatureVerifier::new(
+            env
+                ::var("REQUEST_SIGNING_MAX_SKEW_SECS")
+                .map(|value| value.parse().unwrap())
+                .unwrap_or(300)
+        )
+            .with_clients_file(&signing_clients_file)
+            .unwrap_or_else(|e| panic!("{}", e.to_string()));
+        rocket = rocket.manage(request_signature_verifier);
+    }
+    match env::var("ADMIN_TOKEN") {
+        Ok(admin_token) => {
+            rocket = rocket.manage(AdminToken::new(&admin_token));
+        }
+        Err(_) => info!("No ADMIN_TOKEN provided, only admin scoped callers manage API keys"),
+    }
     rocket
-        ::build()
-        .manage(Arc::new(cryptography_service))
-        .manage(RateLimiter::new(10))
-        .mount("/", openapi_get_routes![healthz, get_public_key, encrypt_message, decrypt_message])
+        .manage(cryptography_service)
+        .manage(Arc::new(cluster_health_service))
+        .manage(server_registry_service)
+        .manage(Arc::new(audit_trail_service))
+        .manage(jwt_verifier)
+        .manage(Arc::new(api_key_service))
+        .manage(rate_limiter)
+        .manage(Arc::new(InMemoryPendingDecryptionService::new()))
+        .manage(approval_policy_from_env())
+        .manage(service_metrics)
+        .attach(RequestIds)
+        .attach(
+            AdHoc::on_shutdown("Span export", |_| {
+                Box::pin(async {
+                    tokio::task::spawn_blocking(trace_context::shutdown_tracer).await.unwrap();
+                })
+            })
+        )
+        .attach(HttpMetrics)
+        .attach(RateLimitHeaders)
+        .mount(
+            "/",
+            openapi_get_routes![
+                healthz,
+                readyz,
+                metrics,
+                get_public_key,
+                encrypt_message,
+                decrypt_message,
+                generate_data_key,
+                decrypt_data_key,
+                get_audit_events,
+                export_audit_events,
+                create_api_key,
+                list_api_keys,
+                list_servers,
+                revoke_api_key,
+                rotate_api_key,
+                list_pending_decryptions,
+                get_pending_decryption,
+                approve_pending_decryption,
+                deny_pending_decryption
+            ]
+        )
         .mount(
             "/swagger-ui/",
             make_swagger_ui(